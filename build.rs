@@ -1,14 +1,40 @@
 use std::{path::PathBuf, env};
 
+/// Set to a path to also write the generated bindings there, see scripts/generate-bindings.sh.
+const BINDINGS_OUT: &str = "MFX_SYS_BINDINGS_OUT";
+
 fn main() {
-    let libmfx = pkg_config::probe_library("mfx").unwrap();
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed={BINDINGS_OUT}");
+    if env::var_os("CARGO_FEATURE_PREBUILT_BINDINGS").is_some() {
+        // src/mfx_sys/bindings.rs is used instead, the SDK headers are not needed.
+        return;
+    }
+
+    // Only the headers are needed: the library itself is loaded at runtime (see src/loader.rs),
+    // so pkg-config must not emit any link flags.
+    let libmfx = pkg_config::Config::new()
+        .cargo_metadata(false)
+        .probe("mfx")
+        .expect("The Intel Media SDK headers (pkg-config package `mfx`) are required to generate the bindings");
     // https://github.com/Intel-Media-SDK/MediaSDK/blob/master/api/include/mfxvideo.h
     // https://rust-lang.github.io/rust-bindgen/tutorial-3.html
     let libmfx_include_path = libmfx.include_paths[0].display();
     let bindings = bindgen::Builder::default()
-        // The input header we would like to generate
-        // bindings for.
-        .header(format!("{libmfx_include_path}/mfx/mfxdefs.h"))
+        // The input headers we would like to generate bindings for. mfxvideo.h pulls in
+        // mfxsession.h, mfxcommon.h and mfxdefs.h.
+        .header(format!("{libmfx_include_path}/mfx/mfxvideo.h"))
+        .header(format!("{libmfx_include_path}/mfx/mfxstructures.h"))
+        .header(format!("{libmfx_include_path}/mfx/mfxvstructures.h"))
+        .clang_arg(format!("-I{libmfx_include_path}/mfx"))
+        // Only the SDK itself, not everything the system headers bring along.
+        .allowlist_function("MFX.*")
+        .allowlist_type("mfx.*")
+        .allowlist_var("MFX_.*")
+        // Keep the SDK constant names, e.g. MFX_ERR_NONE instead of mfxStatus_MFX_ERR_NONE.
+        .prepend_enum_name(false)
+        // The layouts are checked against the hand-written structures in src/mfx_sys/mod.rs instead.
+        .layout_tests(false)
         // Tell cargo to invalidate the built crate whenever any of the
         // included header files changed.
         .parse_callbacks(Box::new(bindgen::CargoCallbacks))
@@ -21,4 +47,7 @@ fn main() {
     bindings
         .write_to_file(out_path.join("bindings.rs"))
         .expect("Couldn't write bindings!");
+    if let Some(path) = env::var_os(BINDINGS_OUT) {
+        bindings.write_to_file(path).expect("Couldn't write bindings!");
+    }
 }
@@ -0,0 +1,14 @@
+#!/bin/sh
+# Writes the bindgen output of build.rs for the headers downloaded by scripts/mfx-headers.sh to OUT
+# (target/mfx-bindings.rs by default). Items missing from src/mfx_sys/bindings.rs are copied from it. Needs libclang.
+set -eu
+
+root=$(cd "$(dirname "$0")/.." && pwd)
+out=$(realpath -m "${1:-$root/target/mfx-bindings.rs}")
+cd "$root"
+headers=$(mktemp -d)
+trap 'rm -rf "$headers"' EXIT
+scripts/mfx-headers.sh "$headers"
+mkdir -p "$(dirname "$out")"
+PKG_CONFIG_PATH="$headers/lib/pkgconfig" MFX_SYS_BINDINGS_OUT="$out" cargo build --lib
+echo "Bindings written to $out"
@@ -0,0 +1,34 @@
+#!/bin/sh
+# Downloads the Media SDK headers the checked-in bindings are generated from (API 1.27) into DIR and writes a
+# pkg-config file for them, so build.rs can run bindgen without the SDK installed:
+#
+#     scripts/mfx-headers.sh /tmp/mfx
+#     PKG_CONFIG_PATH=/tmp/mfx/lib/pkgconfig cargo test
+#
+# MFX_HEADERS_TAG selects another release of https://github.com/Intel-Media-SDK/MediaSDK.
+set -eu
+
+dir=${1:?usage: $0 DIR}
+tag=${MFX_HEADERS_TAG:-intel-mediasdk-18.2.0}
+api_minor=27
+
+mkdir -p "$dir/include/mfx" "$dir/lib/pkgconfig"
+dir=$(cd "$dir" && pwd)
+curl -fsSL "https://github.com/Intel-Media-SDK/MediaSDK/archive/refs/tags/$tag.tar.gz" |
+    tar -xz -C "$dir/include/mfx" --strip-components=3 --wildcards "*/api/include/*.h"
+
+minor=$(sed -n 's/^#define MFX_VERSION_MINOR *\([0-9]*\).*/\1/p' "$dir/include/mfx/mfxdefs.h" | head -n 1)
+if [ -z "${MFX_HEADERS_TAG:-}" ] && [ "$minor" != "$api_minor" ]; then
+    echo "$tag has API 1.$minor instead of 1.$api_minor" >&2
+    exit 1
+fi
+
+cat > "$dir/lib/pkgconfig/mfx.pc" <<EOF
+prefix=$dir
+includedir=\${prefix}/include
+
+Name: mfx
+Description: Intel Media SDK headers ($tag)
+Version: 1.$minor
+Cflags: -I\${includedir}
+EOF
@@ -0,0 +1,329 @@
+//! Frames allocated by the application instead of the SDK, see `MFXVideoCORE_SetFrameAllocator`.
+//!
+//! The SDK calls the allocator through the C callbacks of [`mfxFrameAllocator`]; [`RegisteredAllocator`] forwards them to a [`FrameAllocator`] and keeps the memory ID arrays handed to the SDK alive until it frees them.
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::sync::{Mutex, PoisonError};
+
+use crate::constants::*;
+use crate::error::{Error, Result};
+use crate::status::MfxStatus;
+use crate::structures::*;
+
+/// Frame allocation callbacks of a session, set with [`crate::Session::set_frame_allocator`].
+///
+/// The SDK may call the allocator from its own threads. Errors returned with [`Error::Status`] are passed on to the SDK as they are, any other error becomes `MFX_ERR_MEMORY_ALLOC` for [`FrameAllocator::alloc`] and `MFX_ERR_UNKNOWN` for the others.
+pub trait FrameAllocator: Send {
+    /// Allocates the frames described by `request` and returns their memory IDs, at least `NumFrameMin` and preferably `NumFrameSuggested` of them.
+    ///
+    /// `request.Type` tells which component asks for the frames and in which memory, see the `MFX_MEMTYPE_*` constants.
+    fn alloc(&mut self, request: &mfxFrameAllocRequest) -> Result<Vec<mfxMemId>>;
+
+    /// Maps the frame `mid` into system memory, setting the plane pointers and pitch of `data`.
+    fn lock(&mut self, mid: mfxMemId, data: &mut mfxFrameData) -> Result<()>;
+
+    /// Unmaps the frame `mid`. `data` is the structure filled by [`FrameAllocator::lock`], if the SDK passed it, and its plane pointers should be cleared.
+    fn unlock(&mut self, mid: mfxMemId, data: Option<&mut mfxFrameData>) -> Result<()>;
+
+    /// The OS-specific handle of the frame `mid`, such as a VA surface ID. System memory allocators have none.
+    fn get_hdl(&mut self, _mid: mfxMemId) -> Result<mfxHDL> {
+        Err(Error::Status(MfxStatus::Unsupported))
+    }
+
+    /// Frees the frames returned together by one call to [`FrameAllocator::alloc`].
+    fn free(&mut self, mids: &[mfxMemId]) -> Result<()>;
+}
+
+struct State {
+    allocator: Box<dyn FrameAllocator>,
+    /// The memory ID arrays of the responses the SDK has not freed yet.
+    responses: Vec<Box<[mfxMemId]>>,
+}
+
+/// A [`FrameAllocator`] behind the callback table passed to `MFXVideoCORE_SetFrameAllocator`.
+///
+/// `pthis` points to the struct itself, so it is boxed and must outlive every SDK component of the session.
+pub struct RegisteredAllocator {
+    table: mfxFrameAllocator,
+    state: Mutex<State>,
+}
+
+impl RegisteredAllocator {
+    pub fn new(allocator: impl FrameAllocator + 'static) -> Box<Self> {
+        let mut registered = Box::new(RegisteredAllocator {
+            table: mfxFrameAllocator::new(),
+            state: Mutex::new(State {
+                allocator: Box::new(allocator),
+                responses: Vec::new(),
+            }),
+        });
+        registered.table.pthis = &*registered as *const RegisteredAllocator as mfxHDL;
+        registered.table.Alloc = Some(alloc);
+        registered.table.Lock = Some(lock);
+        registered.table.Unlock = Some(unlock);
+        registered.table.GetHDL = Some(get_hdl);
+        registered.table.Free = Some(free);
+        registered
+    }
+
+    /// The callback table to pass to the SDK.
+    pub fn as_ptr(&self) -> *const mfxFrameAllocator {
+        &self.table
+    }
+
+    /// Runs `f` on the allocator and turns the result into the status returned to the SDK. Panics must not unwind into the SDK.
+    fn call(&self, error: mfxStatus, f: impl FnOnce(&mut State) -> Result<()>) -> mfxStatus {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+            f(&mut state)
+        }));
+        match result {
+            Ok(Ok(())) => MFX_ERR_NONE,
+            Ok(Err(err)) => err.status().map_or(error, MfxStatus::raw),
+            Err(_) => error,
+        }
+    }
+}
+
+impl Drop for RegisteredAllocator {
+    /// Frees the frames the SDK did not free before the session was closed.
+    fn drop(&mut self) {
+        let state = self.state.get_mut().unwrap_or_else(PoisonError::into_inner);
+        for mids in state.responses.drain(..) {
+            let _ = state.allocator.free(&mids);
+        }
+    }
+}
+
+unsafe fn registered<'a>(pthis: mfxHDL) -> &'a RegisteredAllocator {
+    &*(pthis as *const RegisteredAllocator)
+}
+
+unsafe extern "C" fn alloc(
+    pthis: mfxHDL,
+    request: *mut mfxFrameAllocRequest,
+    response: *mut mfxFrameAllocResponse,
+) -> mfxStatus {
+    if pthis.is_null() || request.is_null() || response.is_null() {
+        return MFX_ERR_NULL_PTR;
+    }
+    let (request, response) = (&*request, &mut *response);
+    registered(pthis).call(MFX_ERR_MEMORY_ALLOC, |state| {
+        let mids = state.allocator.alloc(request)?;
+        if mids.len() < request.NumFrameMin as usize || mids.len() > mfxU16::MAX as usize {
+            state.allocator.free(&mids)?;
+            return Err(Error::Status(MfxStatus::MemoryAlloc));
+        }
+        let mut mids = mids.into_boxed_slice();
+        *response = mfxFrameAllocResponse::new();
+        response.AllocId = request.AllocId;
+        response.mids = mids.as_mut_ptr();
+        response.NumFrameActual = mids.len() as mfxU16;
+        state.responses.push(mids);
+        Ok(())
+    })
+}
+
+unsafe extern "C" fn lock(pthis: mfxHDL, mid: mfxMemId, data: *mut mfxFrameData) -> mfxStatus {
+    if pthis.is_null() || data.is_null() {
+        return MFX_ERR_NULL_PTR;
+    }
+    registered(pthis).call(MFX_ERR_LOCK_MEMORY, |state| state.allocator.lock(mid, &mut *data))
+}
+
+unsafe extern "C" fn unlock(pthis: mfxHDL, mid: mfxMemId, data: *mut mfxFrameData) -> mfxStatus {
+    if pthis.is_null() {
+        return MFX_ERR_NULL_PTR;
+    }
+    registered(pthis).call(MFX_ERR_UNKNOWN, |state| state.allocator.unlock(mid, data.as_mut()))
+}
+
+unsafe extern "C" fn get_hdl(pthis: mfxHDL, mid: mfxMemId, handle: *mut mfxHDL) -> mfxStatus {
+    if pthis.is_null() || handle.is_null() {
+        return MFX_ERR_NULL_PTR;
+    }
+    registered(pthis).call(MFX_ERR_UNKNOWN, |state| {
+        *handle = state.allocator.get_hdl(mid)?;
+        Ok(())
+    })
+}
+
+unsafe extern "C" fn free(pthis: mfxHDL, response: *mut mfxFrameAllocResponse) -> mfxStatus {
+    if pthis.is_null() || response.is_null() {
+        return MFX_ERR_NULL_PTR;
+    }
+    let response = &mut *response;
+    registered(pthis).call(MFX_ERR_UNKNOWN, |state| {
+        let index = state
+            .responses
+            .iter()
+            .position(|mids| ptr::eq(mids.as_ptr(), response.mids))
+            .ok_or(Error::Status(MfxStatus::InvalidHandle))?;
+        let mids = state.responses.swap_remove(index);
+        response.mids = ptr::null_mut();
+        response.NumFrameActual = 0;
+        state.allocator.free(&mids)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::encoder::Encoder;
+    use crate::ext_buffer::VideoParam;
+    use crate::loader::stub;
+    use crate::Session;
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Call {
+        Alloc(mfxU16, mfxU16),
+        Lock(usize),
+        Unlock(usize),
+        GetHdl(usize),
+        Free(Vec<usize>),
+    }
+
+    /// Hands out 1-based indices into a list of NV12 frames as memory IDs and records every call.
+    struct Arena {
+        frames: Vec<Vec<u8>>,
+        pitch: usize,
+        height: usize,
+        calls: Arc<Mutex<Vec<Call>>>,
+    }
+
+    impl Arena {
+        fn record(&self, call: Call) {
+            self.calls.lock().unwrap().push(call);
+        }
+    }
+
+    impl FrameAllocator for Arena {
+        fn alloc(&mut self, request: &mfxFrameAllocRequest) -> Result<Vec<mfxMemId>> {
+            self.record(Call::Alloc(request.NumFrameSuggested, request.Type));
+            self.pitch = request.Info.Width as usize;
+            self.height = request.Info.Height as usize;
+            let size = self.pitch * self.height * 3 / 2;
+            let first = self.frames.len();
+            self.frames.extend((0..request.NumFrameSuggested).map(|_| vec![0; size]));
+            Ok((first + 1..=self.frames.len()).map(|mid| mid as mfxMemId).collect())
+        }
+
+        fn lock(&mut self, mid: mfxMemId, data: &mut mfxFrameData) -> Result<()> {
+            self.record(Call::Lock(mid as usize));
+            let frame = self.frames[mid as usize - 1].as_mut_ptr();
+            data.Y = frame;
+            data.UV = unsafe { frame.add(self.pitch * self.height) };
+            data.set_pitch(self.pitch as mfxU32);
+            Ok(())
+        }
+
+        fn unlock(&mut self, mid: mfxMemId, data: Option<&mut mfxFrameData>) -> Result<()> {
+            self.record(Call::Unlock(mid as usize));
+            if let Some(data) = data {
+                data.Y = ptr::null_mut();
+                data.UV = ptr::null_mut();
+            }
+            Ok(())
+        }
+
+        fn get_hdl(&mut self, mid: mfxMemId) -> Result<mfxHDL> {
+            self.record(Call::GetHdl(mid as usize));
+            Ok(mid)
+        }
+
+        fn free(&mut self, mids: &[mfxMemId]) -> Result<()> {
+            self.record(Call::Free(mids.iter().map(|&mid| mid as usize).collect()));
+            Ok(())
+        }
+    }
+
+    fn session_with_arena() -> (Session, Arc<Mutex<Vec<Call>>>) {
+        let mut session =
+            Session::with_library(stub::encoder_library(), MFX_IMPL_HARDWARE_ANY, mfxVersion::new(1, 0)).unwrap();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let arena = Arena {
+            frames: Vec::new(),
+            pitch: 0,
+            height: 0,
+            calls: calls.clone(),
+        };
+        session.set_frame_allocator(arena).unwrap();
+        (session, calls)
+    }
+
+    fn encoder_params() -> VideoParam {
+        let mut params = VideoParam::new();
+        let info = &mut params.mfx_mut().FrameInfo;
+        info.Width = 64;
+        info.Height = 48;
+        params
+    }
+
+    #[test]
+    fn encoder_allocates_through_callbacks() {
+        let (session, calls) = session_with_arena();
+        let encoder = Encoder::init(&session, &mut encoder_params()).unwrap();
+
+        let suggested = stub::SUGGESTED_SURFACES;
+        let memory_type = MFX_MEMTYPE_EXTERNAL_FRAME | MFX_MEMTYPE_FROM_ENCODE | MFX_MEMTYPE_SYSTEM_MEMORY;
+        let mut expected = vec![Call::Alloc(suggested, memory_type)];
+        for mid in 1..=suggested as usize {
+            expected.push(Call::Lock(mid));
+            expected.push(Call::Unlock(mid));
+        }
+        expected.push(Call::GetHdl(1));
+        assert_eq!(*calls.lock().unwrap(), expected);
+
+        drop(encoder);
+        let freed = (1..=suggested as usize).collect();
+        assert_eq!(calls.lock().unwrap().last(), Some(&Call::Free(freed)));
+    }
+
+    #[test]
+    fn frees_frames_left_on_close() {
+        let (session, calls) = session_with_arena();
+        let encoder = Encoder::init(&session, &mut encoder_params()).unwrap();
+        // The encoder is never closed, so the SDK never calls Free.
+        std::mem::forget(encoder);
+        drop(session);
+        assert!(matches!(calls.lock().unwrap().last(), Some(Call::Free(mids)) if mids.len() == 4));
+    }
+
+    struct Failing;
+
+    impl FrameAllocator for Failing {
+        fn alloc(&mut self, _: &mfxFrameAllocRequest) -> Result<Vec<mfxMemId>> {
+            Err(Error::Status(MfxStatus::MemoryAlloc))
+        }
+
+        fn lock(&mut self, _: mfxMemId, _: &mut mfxFrameData) -> Result<()> {
+            panic!("nothing to lock")
+        }
+
+        fn unlock(&mut self, _: mfxMemId, _: Option<&mut mfxFrameData>) -> Result<()> {
+            Ok(())
+        }
+
+        fn free(&mut self, _: &[mfxMemId]) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn maps_errors_and_panics_to_status() {
+        let registered = RegisteredAllocator::new(Failing);
+        let table = unsafe { &*registered.as_ptr() };
+        let mut request = mfxFrameAllocRequest::new();
+        let mut response = mfxFrameAllocResponse::new();
+        let mut data = mfxFrameData::new();
+        let mut handle: mfxHDL = ptr::null();
+        unsafe {
+            assert_eq!((table.Alloc.unwrap())(table.pthis, &mut request, &mut response), MFX_ERR_MEMORY_ALLOC);
+            assert_eq!((table.Lock.unwrap())(table.pthis, 1 as mfxMemId, &mut data), MFX_ERR_LOCK_MEMORY);
+            assert_eq!((table.GetHDL.unwrap())(table.pthis, 1 as mfxMemId, &mut handle), MFX_ERR_UNSUPPORTED);
+            assert_eq!((table.Free.unwrap())(table.pthis, &mut response), MFX_ERR_INVALID_HANDLE);
+        }
+    }
+}
@@ -12,6 +12,20 @@ pub type mfxSession = libc::c_void;
 pub type mfxHDL = *const libc::c_void;
 pub type mfxMemId = mfxHDL;
 pub type mfxSyncPoint = *const libc::c_void;
+pub type mfxChar = libc::c_char;
+pub type mfxF32 = f32;
+pub type mfxF64 = f64;
+pub type mfxI8 = i8;
+pub type mfxI16 = i16;
+
+/// Handle of the oneVPL dispatcher, returned by `MFXLoad`.
+pub type mfxLoader = *mut libc::c_void;
+/// Handle of a dispatcher configuration, returned by `MFXCreateConfig`.
+pub type mfxConfig = *mut libc::c_void;
+pub type mfxImplType = mfxU32;
+pub type mfxAccelerationMode = mfxU32;
+pub type mfxVariantType = mfxU32;
+pub type mfxImplCapsDeliveryFormat = mfxU32;
 
 pub const MFX_IMPL_AUTO: mfxIMPL = 0x0000;
 pub const MFX_IMPL_SOFTWARE: mfxIMPL = 0x0001;
@@ -19,6 +33,46 @@ pub const MFX_IMPL_HARDWARE: mfxIMPL = 0x0002;
 pub const MFX_IMPL_AUTO_ANY: mfxIMPL = 0x0003;
 pub const MFX_IMPL_HARDWARE_ANY: mfxIMPL = 0x0004;
 
+/// Pure software implementation.
+pub const MFX_IMPL_TYPE_SOFTWARE: mfxImplType = 0x0001;
+/// Hardware accelerated implementation.
+pub const MFX_IMPL_TYPE_HARDWARE: mfxImplType = 0x0002;
+
+pub const MFX_ACCEL_MODE_NA: mfxAccelerationMode = 0;
+pub const MFX_ACCEL_MODE_VIA_D3D9: mfxAccelerationMode = 0x0200;
+pub const MFX_ACCEL_MODE_VIA_D3D11: mfxAccelerationMode = 0x0300;
+pub const MFX_ACCEL_MODE_VIA_VAAPI: mfxAccelerationMode = 0x0400;
+pub const MFX_ACCEL_MODE_VIA_VAAPI_DRM_RENDER_NODE: mfxAccelerationMode = MFX_ACCEL_MODE_VIA_VAAPI;
+pub const MFX_ACCEL_MODE_VIA_VAAPI_DRM_MODESET: mfxAccelerationMode = 0x0401;
+pub const MFX_ACCEL_MODE_VIA_VAAPI_GLX: mfxAccelerationMode = 0x0402;
+pub const MFX_ACCEL_MODE_VIA_VAAPI_X11: mfxAccelerationMode = 0x0403;
+pub const MFX_ACCEL_MODE_VIA_VAAPI_WAYLAND: mfxAccelerationMode = 0x0404;
+pub const MFX_ACCEL_MODE_VIA_HDDLUNITE: mfxAccelerationMode = 0x0500;
+
+pub const MFX_VARIANT_TYPE_UNSET: mfxVariantType = 0;
+pub const MFX_VARIANT_TYPE_U8: mfxVariantType = 1;
+pub const MFX_VARIANT_TYPE_I8: mfxVariantType = 2;
+pub const MFX_VARIANT_TYPE_U16: mfxVariantType = 3;
+pub const MFX_VARIANT_TYPE_I16: mfxVariantType = 4;
+pub const MFX_VARIANT_TYPE_U32: mfxVariantType = 5;
+pub const MFX_VARIANT_TYPE_I32: mfxVariantType = 6;
+pub const MFX_VARIANT_TYPE_U64: mfxVariantType = 7;
+pub const MFX_VARIANT_TYPE_I64: mfxVariantType = 8;
+pub const MFX_VARIANT_TYPE_F32: mfxVariantType = 9;
+pub const MFX_VARIANT_TYPE_F64: mfxVariantType = 10;
+pub const MFX_VARIANT_TYPE_PTR: mfxVariantType = 11;
+
+/// `MFX_STRUCT_VERSION(1, 0)`, the only mfxVariant version.
+pub const MFX_VARIANT_VERSION: mfxU16 = 0x0100;
+
+/// Implementation capabilities are delivered as an mfxImplDescription structure.
+pub const MFX_IMPLCAPS_IMPLDESCSTRUCTURE: mfxImplCapsDeliveryFormat = 1;
+
+/// Length of mfxImplDescription::ImplName.
+pub const MFX_IMPL_NAME_LEN: usize = 32;
+/// Length of the mfxImplDescription::License and Keywords strings.
+pub const MFX_STRFIELD_LEN: usize = 128;
+
 /// The function completed successfully.
 pub const MFX_ERR_NONE: mfxStatus = 0;
 /// An unknown error occurred in the library function operation. This is a reserved status code.
@@ -33,19 +87,66 @@ pub const MFX_ERR_MEMORY_ALLOC: mfxStatus = -4;
 pub const MFX_ERR_NOT_ENOUGH_BUFFER: mfxStatus = -5;
 /// Invalid Handle
 pub const MFX_ERR_INVALID_HANDLE: mfxStatus = -6;
+/// Failed to lock the memory block (external allocator).
+pub const MFX_ERR_LOCK_MEMORY: mfxStatus = -7;
+/// Member functions called without initialization.
+pub const MFX_ERR_NOT_INITIALIZED: mfxStatus = -8;
 /// Specified object/item/sync point not found.
 pub const MFX_ERR_NOT_FOUND: mfxStatus = -9;
-/// eed more bitstream at decoding input, encoding input, or video processing input frames.
+/// Need more bitstream at decoding input, encoding input, or video processing input frames.
 pub const MFX_ERR_MORE_DATA: mfxStatus = -10;
+/// Need more frame surfaces at decoding or video processing output
+pub const MFX_ERR_MORE_SURFACE: mfxStatus = -11;
+/// Operation aborted
+pub const MFX_ERR_ABORTED: mfxStatus = -12;
+/// Lose the hardware acceleration device.
+pub const MFX_ERR_DEVICE_LOST: mfxStatus = -13;
 /// Incompatible video parameters detected. If a Reset function returns this status code, a component—decoder, encoder or video preprocessor—cannot process the specified configuration with existing structures and frame buffers. If the function MFXVideoDECODE_DecodeFrameAsync returns this status code, the bitstream contains an incompatible video parameter configuration that the decoder cannot follow.
+pub const MFX_ERR_INCOMPATIBLE_VIDEO_PARAM: mfxStatus = -14;
+/// Invalid video parameters detected. Init and Reset functions return this status code to indicate either that mandated input parameters are unspecified, or the functions failed to correct them.
 pub const MFX_ERR_INVALID_VIDEO_PARAM: mfxStatus = -15;
-/// MFX_ERR_UNDEFINED_BEHAVIOR
+/// The function is called twice without a close, or called before the component is initialized.
 pub const MFX_ERR_UNDEFINED_BEHAVIOR: mfxStatus = -16;
+/// Hardware device returned unexpected errors. SDK was unable to restore operation.
+pub const MFX_ERR_DEVICE_FAILED: mfxStatus = -17;
+/// Need bigger bitstream buffer
+pub const MFX_ERR_MORE_BITSTREAM: mfxStatus = -18;
+/// Incompatible audio parameters detected.
+pub const MFX_ERR_INCOMPATIBLE_AUDIO_PARAM: mfxStatus = -19;
+/// Invalid audio parameters detected.
+pub const MFX_ERR_INVALID_AUDIO_PARAM: mfxStatus = -20;
+/// Device operation failure caused by GPU hang.
+pub const MFX_ERR_GPU_HANG: mfxStatus = -21;
+/// Bigger output surface required.
+pub const MFX_ERR_REALLOC_SURFACE: mfxStatus = -22;
 
+/// The previous asynchronous operation is in execution.
+pub const MFX_WRN_IN_EXECUTION: mfxStatus = 1;
+/// The hardware acceleration device is busy. Call the function again in a few milliseconds.
+pub const MFX_WRN_DEVICE_BUSY: mfxStatus = 2;
+/// The video parameters are changed during decoding.
+pub const MFX_WRN_VIDEO_PARAM_CHANGED: mfxStatus = 3;
 /// SW is used
 pub const MFX_WRN_PARTIAL_ACCELERATION: mfxStatus = 4;
 /// The function detected some video parameters were incompatible with others; incompatibility resolved.
 pub const MFX_WRN_INCOMPATIBLE_VIDEO_PARAM: mfxStatus = 5;
+/// The value is saturated based on its valid range.
+pub const MFX_WRN_VALUE_NOT_CHANGED: mfxStatus = 6;
+/// The value is out of valid range.
+pub const MFX_WRN_OUT_OF_RANGE: mfxStatus = 7;
+/// One of requested filters has been skipped.
+pub const MFX_WRN_FILTER_SKIPPED: mfxStatus = 10;
+/// Incompatible audio parameters detected; incompatibility resolved.
+pub const MFX_WRN_INCOMPATIBLE_AUDIO_PARAM: mfxStatus = 11;
+/// Frame is not ready, but bitstream contains partial output.
+pub const MFX_ERR_NONE_PARTIAL_OUTPUT: mfxStatus = 12;
+
+/// Task is still in progress (plugin threading).
+pub const MFX_TASK_WORKING: mfxStatus = 8;
+/// Task is waiting for resources (plugin threading).
+pub const MFX_TASK_BUSY: mfxStatus = 9;
+/// Internal status: more data is needed and the task has been submitted.
+pub const MFX_ERR_MORE_DATA_SUBMIT_TASK: mfxStatus = -10000;
 
 pub const MFX_TARGETUSAGE_1: u16 = 1;
 pub const MFX_TARGETUSAGE_2: u16 = 2;
@@ -61,19 +162,136 @@ pub const MFX_TARGETUSAGE_BALANCED: u16 = MFX_TARGETUSAGE_4;
 pub const MFX_TARGETUSAGE_BEST_SPEED: u16 = MFX_TARGETUSAGE_7;
 
 pub const MFX_CODEC_AVC: mfxU32 = 0x20435641;
+pub const MFX_CODEC_HEVC: mfxU32 = MFX_MAKEFOURCC!('H', 'E', 'V', 'C');
+pub const MFX_CODEC_MPEG2: mfxU32 = MFX_MAKEFOURCC!('M', 'P', 'G', '2');
+pub const MFX_CODEC_VC1: mfxU32 = MFX_MAKEFOURCC!('V', 'C', '1', ' ');
+pub const MFX_CODEC_VP8: mfxU32 = MFX_MAKEFOURCC!('V', 'P', '8', ' ');
+pub const MFX_CODEC_VP9: mfxU32 = MFX_MAKEFOURCC!('V', 'P', '9', ' ');
+pub const MFX_CODEC_AV1: mfxU32 = MFX_MAKEFOURCC!('A', 'V', '1', ' ');
+pub const MFX_CODEC_JPEG: mfxU32 = MFX_MAKEFOURCC!('J', 'P', 'E', 'G');
+
 pub const MFX_FOURCC_NV12: mfxU32 = MFX_MAKEFOURCC!('N', 'V', '1', '2');
+pub const MFX_FOURCC_NV21: mfxU32 = MFX_MAKEFOURCC!('N', 'V', '2', '1');
 pub const MFX_FOURCC_YV12: mfxU32 = MFX_MAKEFOURCC!('Y', 'V', '1', '2');
+pub const MFX_FOURCC_IYUV: mfxU32 = MFX_MAKEFOURCC!('I', 'Y', 'U', 'V');
+pub const MFX_FOURCC_I420: mfxU32 = MFX_FOURCC_IYUV;
+pub const MFX_FOURCC_NV16: mfxU32 = MFX_MAKEFOURCC!('N', 'V', '1', '6');
+pub const MFX_FOURCC_YUY2: mfxU32 = MFX_MAKEFOURCC!('Y', 'U', 'Y', '2');
+pub const MFX_FOURCC_UYVY: mfxU32 = MFX_MAKEFOURCC!('U', 'Y', 'V', 'Y');
+pub const MFX_FOURCC_RGB4: mfxU32 = MFX_MAKEFOURCC!('R', 'G', 'B', '4');
+pub const MFX_FOURCC_BGR4: mfxU32 = MFX_MAKEFOURCC!('B', 'G', 'R', '4');
+pub const MFX_FOURCC_A2RGB10: mfxU32 = MFX_MAKEFOURCC!('R', 'G', '1', '0');
+pub const MFX_FOURCC_P010: mfxU32 = MFX_MAKEFOURCC!('P', '0', '1', '0');
+pub const MFX_FOURCC_P210: mfxU32 = MFX_MAKEFOURCC!('P', '2', '1', '0');
+pub const MFX_FOURCC_AYUV: mfxU32 = MFX_MAKEFOURCC!('A', 'Y', 'U', 'V');
+pub const MFX_FOURCC_Y210: mfxU32 = MFX_MAKEFOURCC!('Y', '2', '1', '0');
+pub const MFX_FOURCC_Y410: mfxU32 = MFX_MAKEFOURCC!('Y', '4', '1', '0');
 
 pub const MFX_RATECONTROL_CBR: u16 = 1;
 pub const MFX_RATECONTROL_VBR: u16 = 2;
+pub const MFX_RATECONTROL_CQP: u16 = 3;
+pub const MFX_RATECONTROL_AVBR: u16 = 4;
+pub const MFX_RATECONTROL_LA: u16 = 8;
+pub const MFX_RATECONTROL_ICQ: u16 = 9;
+pub const MFX_RATECONTROL_VCM: u16 = 10;
+pub const MFX_RATECONTROL_LA_ICQ: u16 = 11;
+pub const MFX_RATECONTROL_LA_EXT: u16 = 12;
+pub const MFX_RATECONTROL_LA_HRD: u16 = 13;
+pub const MFX_RATECONTROL_QVBR: u16 = 14;
 
 pub const MFX_CHROMAFORMAT_MONOCHROME: u16 = 0;
 pub const MFX_CHROMAFORMAT_YUV420: u16 = 1;
+pub const MFX_CHROMAFORMAT_YUV422: u16 = 2;
+pub const MFX_CHROMAFORMAT_YUV444: u16 = 3;
+pub const MFX_CHROMAFORMAT_YUV400: u16 = MFX_CHROMAFORMAT_MONOCHROME;
+pub const MFX_CHROMAFORMAT_YUV411: u16 = 4;
+pub const MFX_CHROMAFORMAT_YUV422H: u16 = MFX_CHROMAFORMAT_YUV422;
+pub const MFX_CHROMAFORMAT_YUV422V: u16 = 5;
 
 pub const MFX_PICSTRUCT_UNKNOWN: u16 = 0;
-pub const MFX_PICSTRUCT_PROGRESSIVE: u16 = 1;
+pub const MFX_PICSTRUCT_PROGRESSIVE: u16 = 0x01;
+pub const MFX_PICSTRUCT_FIELD_TFF: u16 = 0x02;
+pub const MFX_PICSTRUCT_FIELD_BFF: u16 = 0x04;
+pub const MFX_PICSTRUCT_FIELD_REPEATED: u16 = 0x10;
+pub const MFX_PICSTRUCT_FRAME_DOUBLING: u16 = 0x20;
+pub const MFX_PICSTRUCT_FRAME_TRIPLING: u16 = 0x40;
+pub const MFX_PICSTRUCT_FIELD_SINGLE: u16 = 0x100;
+pub const MFX_PICSTRUCT_FIELD_TOP: u16 = MFX_PICSTRUCT_FIELD_SINGLE | MFX_PICSTRUCT_FIELD_TFF;
+pub const MFX_PICSTRUCT_FIELD_BOTTOM: u16 = MFX_PICSTRUCT_FIELD_SINGLE | MFX_PICSTRUCT_FIELD_BFF;
+pub const MFX_PICSTRUCT_FIELD_PAIRED_PREV: u16 = 0x200;
+pub const MFX_PICSTRUCT_FIELD_PAIRED_NEXT: u16 = 0x400;
+
+/// mfxFrameData::TimeStamp and mfxBitstream::TimeStamp without a value, -1 in the C headers like DecodeTimeStamp.
+pub const MFX_TIMESTAMP_UNKNOWN: mfxU64 = mfxU64::MAX;
+/// mfxExtAVCEncodedFrameInfo::FrameOrder of an unused reference list entry.
+pub const MFX_FRAMEORDER_UNKNOWN: mfxU32 = mfxU32::MAX;
+
+pub const MFX_FRAMETYPE_UNKNOWN: u16 = 0x0000;
+pub const MFX_FRAMETYPE_I: u16 = 0x0001;
+pub const MFX_FRAMETYPE_P: u16 = 0x0002;
+pub const MFX_FRAMETYPE_B: u16 = 0x0004;
+pub const MFX_FRAMETYPE_S: u16 = 0x0008;
+pub const MFX_FRAMETYPE_REF: u16 = 0x0040;
+pub const MFX_FRAMETYPE_IDR: u16 = 0x0080;
+// The SDK names of the second-field frame types.
+#[allow(non_upper_case_globals)]
+pub const MFX_FRAMETYPE_xI: u16 = 0x0100;
+#[allow(non_upper_case_globals)]
+pub const MFX_FRAMETYPE_xP: u16 = 0x0200;
+#[allow(non_upper_case_globals)]
+pub const MFX_FRAMETYPE_xB: u16 = 0x0400;
+#[allow(non_upper_case_globals)]
+pub const MFX_FRAMETYPE_xS: u16 = 0x0800;
+#[allow(non_upper_case_globals)]
+pub const MFX_FRAMETYPE_xREF: u16 = 0x4000;
+#[allow(non_upper_case_globals)]
+pub const MFX_FRAMETYPE_xIDR: u16 = 0x8000;
 
 pub const MFX_IOPATTERN_IN_VIDEO_MEMORY: u16 = 0x01;
 pub const MFX_IOPATTERN_IN_SYSTEM_MEMORY: u16 = 0x02;
+pub const MFX_IOPATTERN_IN_OPAQUE_MEMORY: u16 = 0x04;
 pub const MFX_IOPATTERN_OUT_VIDEO_MEMORY: u16 = 0x10;
-pub const MFX_IOPATTERN_OUT_SYSTEM_MEMORY: u16 = 0x20;
\ No newline at end of file
+pub const MFX_IOPATTERN_OUT_SYSTEM_MEMORY: u16 = 0x20;
+pub const MFX_IOPATTERN_OUT_OPAQUE_MEMORY: u16 = 0x40;
+
+// Tri-state values of the options in the extended coding option buffers.
+pub const MFX_CODINGOPTION_UNKNOWN: u16 = 0x00;
+pub const MFX_CODINGOPTION_ON: u16 = 0x10;
+pub const MFX_CODINGOPTION_OFF: u16 = 0x20;
+pub const MFX_CODINGOPTION_ADAPTIVE: u16 = 0x30;
+
+// PayloadCtrlFlags: mfxPayload::CtrlFlags.
+/// The HEVC SEI message goes into a suffix SEI NAL unit, after the slices of the picture.
+pub const MFX_PAYLOAD_CTRL_SUFFIX: u32 = 0x00000001;
+
+// SkipFrame: how frames marked with mfxEncodeCtrl::SkipFrame are skipped, set in mfxExtCodingOption2::SkipFrame.
+pub const MFX_SKIPFRAME_NO_SKIP: u16 = 0;
+pub const MFX_SKIPFRAME_INSERT_DUMMY: u16 = 1;
+pub const MFX_SKIPFRAME_INSERT_NOTHING: u16 = 2;
+pub const MFX_SKIPFRAME_BRC_ONLY: u16 = 3;
+
+pub const MFX_EXTBUFF_CODING_OPTION: mfxU32 = MFX_MAKEFOURCC!('C', 'D', 'O', 'P');
+pub const MFX_EXTBUFF_CODING_OPTION2: mfxU32 = MFX_MAKEFOURCC!('C', 'D', 'O', '2');
+pub const MFX_EXTBUFF_CODING_OPTION3: mfxU32 = MFX_MAKEFOURCC!('C', 'D', 'O', '3');
+pub const MFX_EXTBUFF_CODING_OPTION_SPSPPS: mfxU32 = MFX_MAKEFOURCC!('C', 'O', 'S', 'P');
+pub const MFX_EXTBUFF_VIDEO_SIGNAL_INFO: mfxU32 = MFX_MAKEFOURCC!('V', 'S', 'I', 'N');
+pub const MFX_EXTBUFF_ENCODER_RESET_OPTION: mfxU32 = MFX_MAKEFOURCC!('E', 'N', 'R', 'O');
+pub const MFX_EXTBUFF_ENCODED_FRAME_INFO: mfxU32 = MFX_MAKEFOURCC!('E', 'N', 'F', 'I');
+
+// ExtMemFrameType: the memory type and the component of the frames in mfxFrameAllocRequest.Type.
+pub const MFX_MEMTYPE_DXVA2_DECODER_TARGET: u16 = 0x0010;
+pub const MFX_MEMTYPE_DXVA2_PROCESSOR_TARGET: u16 = 0x0020;
+pub const MFX_MEMTYPE_VIDEO_MEMORY_DECODER_TARGET: u16 = MFX_MEMTYPE_DXVA2_DECODER_TARGET;
+pub const MFX_MEMTYPE_VIDEO_MEMORY_PROCESSOR_TARGET: u16 = MFX_MEMTYPE_DXVA2_PROCESSOR_TARGET;
+pub const MFX_MEMTYPE_SYSTEM_MEMORY: u16 = 0x0040;
+pub const MFX_MEMTYPE_VIDEO_MEMORY_ENCODER_TARGET: u16 = 0x1000;
+pub const MFX_MEMTYPE_FROM_ENCODE: u16 = 0x0100;
+pub const MFX_MEMTYPE_FROM_DECODE: u16 = 0x0200;
+pub const MFX_MEMTYPE_FROM_VPPIN: u16 = 0x0400;
+pub const MFX_MEMTYPE_FROM_VPPOUT: u16 = 0x0800;
+pub const MFX_MEMTYPE_FROM_ENC: u16 = 0x2000;
+pub const MFX_MEMTYPE_FROM_PAK: u16 = 0x4000;
+pub const MFX_MEMTYPE_INTERNAL_FRAME: u16 = 0x0001;
+pub const MFX_MEMTYPE_EXTERNAL_FRAME: u16 = 0x0002;
+pub const MFX_MEMTYPE_OPAQUE_FRAME: u16 = 0x0004;
+pub const MFX_MEMTYPE_EXPORT_FRAME: u16 = 0x0008;
\ No newline at end of file
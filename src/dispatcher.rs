@@ -0,0 +1,272 @@
+//! Implementation selection through the oneVPL 2.x dispatcher.
+//!
+//! Newer GPUs only ship the oneVPL runtime, which cannot be reached through `MFXInit`. The dispatcher instead enumerates the installed implementations with `MFXEnumImplementations`, narrowed down by the filter properties set with `MFXSetConfigFilterProperty`, and creates the session with `MFXCreateSession`. [`crate::Session::open`] tries this first and falls back to `MFXInit` when the library is not a oneVPL dispatcher or no implementation matches.
+use std::ffi::CString;
+use std::io;
+use std::ptr;
+use std::sync::Arc;
+
+use crate::constants::*;
+use crate::enums::CodecId;
+use crate::error::Result;
+use crate::ffi::DispatcherFunctions;
+use crate::loader::Library;
+use crate::structures::*;
+use crate::utils::check_error;
+
+/// Filter property selecting the implementation type, `MFX_IMPL_TYPE_*`.
+pub const PROPERTY_IMPL: &str = "mfxImplDescription.Impl";
+/// Filter property selecting the hardware acceleration stack, `MFX_ACCEL_MODE_*`.
+pub const PROPERTY_ACCELERATION_MODE: &str = "mfxImplDescription.AccelerationMode";
+/// Filter property selecting the minimum API version, `Major << 16 | Minor`.
+pub const PROPERTY_API_VERSION: &str = "mfxImplDescription.ApiVersion.Version";
+/// Filter property selecting implementations that can encode a codec, `MFX_CODEC_*`.
+pub const PROPERTY_ENCODER_CODEC_ID: &str = "mfxImplDescription.mfxEncoderDescription.encoder.CodecID";
+
+/// Properties an implementation must have to be selected. Unset fields match any implementation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImplementationFilter {
+    /// `MFX_IMPL_TYPE_HARDWARE` or `MFX_IMPL_TYPE_SOFTWARE`.
+    pub implementation_type: Option<mfxImplType>,
+    /// Hardware acceleration stack, e.g. `MFX_ACCEL_MODE_VIA_VAAPI`.
+    pub acceleration_mode: Option<mfxAccelerationMode>,
+    /// Minimum API version.
+    pub api_version: Option<mfxVersion>,
+    /// Codec the implementation must be able to encode.
+    pub encoder_codec: Option<CodecId>,
+}
+
+impl ImplementationFilter {
+    /// Hardware implementations able to encode `codec`.
+    pub fn hardware_encoder(codec: CodecId) -> Self {
+        ImplementationFilter {
+            implementation_type: Some(MFX_IMPL_TYPE_HARDWARE),
+            encoder_codec: Some(codec),
+            ..Default::default()
+        }
+    }
+
+    /// The `MFXInit` arguments closest to this filter, used when the dispatcher is not available.
+    pub fn legacy_init(&self) -> (mfxIMPL, mfxVersion) {
+        let implementation = match self.implementation_type {
+            Some(MFX_IMPL_TYPE_SOFTWARE) => MFX_IMPL_SOFTWARE,
+            Some(_) => MFX_IMPL_HARDWARE_ANY,
+            None => MFX_IMPL_AUTO_ANY,
+        };
+        (implementation, self.api_version.unwrap_or(mfxVersion::new(1, 0)))
+    }
+
+    fn properties(&self) -> Vec<(&'static str, mfxU32)> {
+        let mut properties = Vec::new();
+        if let Some(implementation_type) = self.implementation_type {
+            properties.push((PROPERTY_IMPL, implementation_type));
+        }
+        if let Some(acceleration_mode) = self.acceleration_mode {
+            properties.push((PROPERTY_ACCELERATION_MODE, acceleration_mode));
+        }
+        if let Some(version) = self.api_version {
+            properties.push((
+                PROPERTY_API_VERSION,
+                (version.Major as mfxU32) << 16 | version.Minor as mfxU32,
+            ));
+        }
+        if let Some(codec) = self.encoder_codec {
+            properties.push((PROPERTY_ENCODER_CODEC_ID, codec.raw()));
+        }
+        properties
+    }
+}
+
+/// An implementation reported by `MFXEnumImplementations`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Implementation {
+    /// Index to pass to `MFXCreateSession`.
+    pub index: mfxU32,
+    /// `MFX_IMPL_TYPE_HARDWARE` or `MFX_IMPL_TYPE_SOFTWARE`.
+    pub implementation_type: mfxImplType,
+    /// Default hardware acceleration stack.
+    pub acceleration_mode: mfxAccelerationMode,
+    /// Supported API version.
+    pub api_version: mfxVersion,
+    /// Implementation name given by the vendor.
+    pub name: String,
+    /// PCI vendor ID, 0x8086 for Intel.
+    pub vendor_id: mfxU32,
+}
+
+impl Implementation {
+    fn from_description(index: mfxU32, description: &mfxImplDescription) -> Self {
+        let name: Vec<u8> = description
+            .ImplName
+            .iter()
+            .take_while(|&&c| c != 0)
+            .map(|&c| c as u8)
+            .collect();
+        Implementation {
+            index,
+            implementation_type: description.Impl,
+            acceleration_mode: description.AccelerationMode,
+            api_version: mfxVersion::new(description.ApiVersion.Major, description.ApiVersion.Minor),
+            name: String::from_utf8_lossy(&name).into_owned(),
+            vendor_id: description.VendorID,
+        }
+    }
+}
+
+/// A dispatcher loader with the filter properties applied, unloaded with `MFXUnload` when dropped.
+///
+/// Sessions created by the loader must be closed before it is dropped.
+pub(crate) struct Loader {
+    library: Arc<Library>,
+    handle: mfxLoader,
+}
+
+impl Loader {
+    /// Creates a loader selecting the implementations matching `filter`, or returns `None` if the library has no dispatcher entry points.
+    pub(crate) fn new(library: Arc<Library>, filter: &ImplementationFilter) -> Result<Option<Loader>> {
+        let Some(functions) = library.dispatcher.as_ref() else {
+            return Ok(None);
+        };
+        let handle = unsafe { (functions.MFXLoad)() };
+        if handle.is_null() {
+            return Err(io::Error::other("MFXLoad failed").into());
+        }
+
+        let loader = Loader { library, handle };
+        for (name, value) in filter.properties() {
+            loader.set_property(name, value)?;
+        }
+        Ok(Some(loader))
+    }
+
+    fn functions(&self) -> &DispatcherFunctions {
+        self.library
+            .dispatcher
+            .as_ref()
+            .expect("loader created without dispatcher")
+    }
+
+    fn set_property(&self, name: &str, value: mfxU32) -> Result<()> {
+        let config = unsafe { (self.functions().MFXCreateConfig)(self.handle) };
+        if config.is_null() {
+            return Err(io::Error::other("MFXCreateConfig failed").into());
+        }
+        let name = CString::new(name).map_err(io::Error::from)?;
+        check_error(unsafe {
+            (self.functions().MFXSetConfigFilterProperty)(config, name.as_ptr() as *const mfxU8, mfxVariant::u32(value))
+        })
+    }
+
+    /// The implementations matching the filter, in the dispatcher's order of preference.
+    pub(crate) fn implementations(&self) -> Result<Vec<Implementation>> {
+        let mut implementations = Vec::new();
+        for index in 0.. {
+            let mut description: mfxHDL = ptr::null();
+            let status = unsafe {
+                (self.functions().MFXEnumImplementations)(
+                    self.handle,
+                    index,
+                    MFX_IMPLCAPS_IMPLDESCSTRUCTURE,
+                    &mut description,
+                )
+            };
+            if status == MFX_ERR_NOT_FOUND {
+                break;
+            }
+            check_error(status)?;
+
+            implementations.push(Implementation::from_description(index, unsafe {
+                &*(description as *const mfxImplDescription)
+            }));
+            unsafe { (self.functions().MFXDispReleaseImplDescription)(self.handle, description) };
+        }
+        Ok(implementations)
+    }
+
+    /// Loads the implementation at `index` and creates a session on it.
+    pub(crate) fn create_session(&self, index: mfxU32) -> Result<*mut mfxSession> {
+        let mut handle: *mut mfxSession = ptr::null_mut();
+        check_error(unsafe { (self.functions().MFXCreateSession)(self.handle, index, &mut handle) })?;
+        Ok(handle)
+    }
+}
+
+impl Drop for Loader {
+    fn drop(&mut self) {
+        unsafe { (self.functions().MFXUnload)(self.handle) };
+    }
+}
+
+/// Lists the implementations matching `filter`, empty if the library is not a oneVPL dispatcher.
+pub fn implementations(library: Arc<Library>, filter: &ImplementationFilter) -> Result<Vec<Implementation>> {
+    match Loader::new(library, filter)? {
+        Some(loader) => loader.implementations(),
+        None => Ok(Vec::new()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::mem::{offset_of, size_of};
+
+    use super::*;
+    use crate::loader::stub;
+
+    #[test]
+    fn impl_description_layout() {
+        assert_eq!(size_of::<mfxVariant>(), 16);
+        assert_eq!(offset_of!(mfxVariant, Type), 4);
+        assert_eq!(offset_of!(mfxVariant, Data), 8);
+
+        assert_eq!(offset_of!(mfxImplDescription, Impl), 4);
+        assert_eq!(offset_of!(mfxImplDescription, AccelerationMode), 8);
+        assert_eq!(offset_of!(mfxImplDescription, ApiVersion), 12);
+        assert_eq!(offset_of!(mfxImplDescription, ImplName), 16);
+        assert_eq!(offset_of!(mfxImplDescription, License), 48);
+        assert_eq!(offset_of!(mfxImplDescription, Keywords), 176);
+        assert_eq!(offset_of!(mfxImplDescription, VendorID), 304);
+        assert_eq!(offset_of!(mfxImplDescription, VendorImplID), 308);
+    }
+
+    #[test]
+    fn filter_properties() {
+        let filter = ImplementationFilter {
+            acceleration_mode: Some(MFX_ACCEL_MODE_VIA_VAAPI),
+            api_version: Some(mfxVersion::new(2, 5)),
+            ..ImplementationFilter::hardware_encoder(CodecId::Avc)
+        };
+        assert_eq!(
+            filter.properties(),
+            [
+                (PROPERTY_IMPL, MFX_IMPL_TYPE_HARDWARE),
+                (PROPERTY_ACCELERATION_MODE, MFX_ACCEL_MODE_VIA_VAAPI),
+                (PROPERTY_API_VERSION, 0x0002_0005),
+                (PROPERTY_ENCODER_CODEC_ID, MFX_CODEC_AVC),
+            ]
+        );
+        assert_eq!(filter.legacy_init(), (MFX_IMPL_HARDWARE_ANY, mfxVersion::new(2, 5)));
+    }
+
+    #[test]
+    fn enumerates_implementations() {
+        let found = implementations(
+            stub::dispatcher_library(),
+            &ImplementationFilter::hardware_encoder(CodecId::Avc),
+        )
+        .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "stub");
+        assert_eq!(found[0].implementation_type, MFX_IMPL_TYPE_HARDWARE);
+        assert_eq!(stub::LOADERS.with(|loaders| loaders.get()), 0);
+
+        let found = implementations(
+            stub::dispatcher_library(),
+            &ImplementationFilter::hardware_encoder(CodecId::Hevc),
+        )
+        .unwrap();
+        assert!(found.is_empty());
+        assert!(implementations(stub::library(), &ImplementationFilter::default())
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,283 @@
+//! The encoder component of a session.
+use crate::constants::*;
+use crate::encoder_config::{query_io_surf, EncoderConfig, QueryReport};
+use crate::error::Result;
+use crate::ext_buffer::VideoParam;
+use crate::status::MfxStatus;
+use crate::structures::*;
+use crate::surface_pool::SurfacePool;
+use crate::utils::check_error;
+use crate::Session;
+
+/// Initial size of the SPS and PPS buffers passed to `MFXVideoENCODE_GetVideoParam`, doubled while the SDK reports `MFX_ERR_NOT_ENOUGH_BUFFER`.
+const HEADER_BUFFER_SIZE: usize = 256;
+
+/// Sequence and picture parameter sets of an initialized encoder, as Annex B NAL units including their start codes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Headers {
+    pub sps: Vec<u8>,
+    pub pps: Vec<u8>,
+}
+
+impl Headers {
+    /// The SPS without its start code, as stored in `avcC` or `sprop-parameter-sets`.
+    pub fn sps_nal(&self) -> &[u8] {
+        strip_start_code(&self.sps)
+    }
+
+    /// The PPS without its start code.
+    pub fn pps_nal(&self) -> &[u8] {
+        strip_start_code(&self.pps)
+    }
+}
+
+fn strip_start_code(nal: &[u8]) -> &[u8] {
+    nal.strip_prefix(&[0, 0, 0, 1])
+        .or_else(|| nal.strip_prefix(&[0, 0, 1]))
+        .unwrap_or(nal)
+}
+
+/// How [`Encoder::reconfigure`] applied new parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reconfiguration {
+    /// `MFXVideoENCODE_Reset` applied them to the running encoder. With `new_sequence` the stream restarts with an IDR frame and new headers.
+    Reset { new_sequence: bool },
+    /// The encoder could not be reset with them, so it was closed and initialized again.
+    Reinitialized,
+}
+
+/// The outcome of [`Encoder::reconfigure`].
+pub struct Reconfigured {
+    pub kind: Reconfiguration,
+    /// The parameters the encoder runs with now, i.e. the Query output.
+    pub params: VideoParam,
+    /// The input surfaces the encoder needs with the new parameters.
+    pub request: mfxFrameAllocRequest,
+    pub report: QueryReport,
+    /// Whether the input surfaces were reallocated for the new `request`, see [`SurfacePool::resize`].
+    pub reallocated: bool,
+}
+
+/// An encoder initialized with `MFXVideoENCODE_Init`, closed with `MFXVideoENCODE_Close` when dropped.
+pub struct Encoder<'a> {
+    session: &'a Session,
+}
+
+impl<'a> Encoder<'a> {
+    /// Initializes the encoder of `session`. Warnings such as `MFX_WRN_PARTIAL_ACCELERATION` are not errors.
+    pub fn init(session: &'a Session, params: &mut VideoParam) -> Result<Self> {
+        check_error(unsafe { (session.library().MFXVideoENCODE_Init)(session.as_ptr(), params.as_mut_ptr()) })?;
+        Ok(Encoder { session })
+    }
+
+    pub fn session(&self) -> &'a Session {
+        self.session
+    }
+
+    /// Fills `params` and its attached extended buffers with the current working parameters, see `MFXVideoENCODE_GetVideoParam`.
+    pub fn get_video_param(&self, params: &mut VideoParam) -> Result<MfxStatus> {
+        let library = self.session.library();
+        MfxStatus::from_raw(unsafe {
+            (library.MFXVideoENCODE_GetVideoParam)(self.session.as_ptr(), params.as_mut_ptr())
+        })
+        .result()
+    }
+
+    /// The current working parameters, without extended buffers.
+    pub fn video_param(&self) -> Result<VideoParam> {
+        let mut params = VideoParam::new();
+        self.get_video_param(&mut params)?;
+        Ok(params)
+    }
+
+    /// Switches to the parameters of `config`, e.g. a new bitrate, GOP or frame size, without closing the session.
+    ///
+    /// Asks `MFXVideoENCODE_Query` with mfxExtEncoderResetOption whether `MFXVideoENCODE_Reset` can apply the new parameters, and closes and initializes the encoder again only if it cannot. Frames the encoder still buffers are discarded either way, so drain it first. If the new initialization fails, the encoder is left closed.
+    ///
+    /// The input surfaces in `pool` are then fitted to the new surface request; the SDK must not have any of them locked.
+    pub fn reconfigure(&mut self, config: &EncoderConfig, pool: &mut SurfacePool) -> Result<Reconfigured> {
+        let (mut params, report) = config.checked_query(self.session)?;
+        let kind = match self.reset_outcome(&params)? {
+            Some(new_sequence) => match self.reset(&mut params) {
+                Err(err) if err.status() == Some(MfxStatus::IncompatibleVideoParam) => self.reinit(&mut params)?,
+                result => {
+                    result?;
+                    Reconfiguration::Reset { new_sequence }
+                }
+            },
+            None => self.reinit(&mut params)?,
+        };
+        let request = query_io_surf(self.session, &mut params)?;
+        let reallocated = pool.resize(&request)?;
+        Ok(Reconfigured {
+            kind,
+            params,
+            request,
+            report,
+            reallocated,
+        })
+    }
+
+    /// Whether `MFXVideoENCODE_Reset` can apply `params` and if it then starts a new sequence, `None` if the encoder has to be initialized again.
+    fn reset_outcome(&self, params: &VideoParam) -> Result<Option<bool>> {
+        let mut input = params.clone();
+        input.ext_buffers_mut().get_or_default::<mfxExtEncoderResetOption>();
+        let mut output = input.clone();
+        let library = self.session.library();
+        let status = MfxStatus::from_raw(unsafe {
+            (library.MFXVideoENCODE_Query)(self.session.as_ptr(), input.as_mut_ptr(), output.as_mut_ptr())
+        });
+        match status.result() {
+            Err(err) if err.status() == Some(MfxStatus::IncompatibleVideoParam) => Ok(None),
+            Err(err) => Err(err),
+            Ok(_) => {
+                let option = output.ext_buffer::<mfxExtEncoderResetOption>().expect("attached above");
+                Ok(Some(option.StartNewSequence == MFX_CODINGOPTION_ON))
+            }
+        }
+    }
+
+    fn reset(&mut self, params: &mut VideoParam) -> Result<()> {
+        check_error(unsafe { (self.session.library().MFXVideoENCODE_Reset)(self.session.as_ptr(), params.as_mut_ptr()) })
+    }
+
+    fn reinit(&mut self, params: &mut VideoParam) -> Result<Reconfiguration> {
+        let library = self.session.library();
+        unsafe { (library.MFXVideoENCODE_Close)(self.session.as_ptr()) };
+        check_error(unsafe { (library.MFXVideoENCODE_Init)(self.session.as_ptr(), params.as_mut_ptr()) })?;
+        Ok(Reconfiguration::Reinitialized)
+    }
+
+    /// The SPS and PPS the encoder writes at the start of the stream, available right after [`Encoder::init`].
+    pub fn headers(&self) -> Result<Headers> {
+        let mut size = HEADER_BUFFER_SIZE;
+        loop {
+            let mut sps = vec![0u8; size];
+            let mut pps = vec![0u8; size];
+            let mut params = VideoParam::new();
+            let buffer = params.add_ext_buffer(mfxExtCodingOptionSPSPPS::new())?;
+            buffer.SPSBuffer = sps.as_mut_ptr();
+            buffer.SPSBufSize = size as mfxU16;
+            buffer.PPSBuffer = pps.as_mut_ptr();
+            buffer.PPSBufSize = size as mfxU16;
+
+            match self.get_video_param(&mut params) {
+                Err(err) if err.status() == Some(MfxStatus::NotEnoughBuffer) && size < mfxU16::MAX as usize / 2 => {
+                    size *= 2;
+                }
+                Err(err) => return Err(err),
+                Ok(_) => {
+                    let buffer = params.ext_buffer::<mfxExtCodingOptionSPSPPS>().expect("attached above");
+                    sps.truncate(buffer.SPSBufSize as usize);
+                    pps.truncate(buffer.PPSBufSize as usize);
+                    return Ok(Headers { sps, pps });
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Encoder<'_> {
+    fn drop(&mut self) {
+        unsafe { (self.session.library().MFXVideoENCODE_Close)(self.session.as_ptr()) };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::enums::CodecId;
+    use crate::loader::stub;
+    use crate::rate_control::BrcParams;
+
+    fn session() -> Session {
+        Session::with_library(stub::encoder_library(), MFX_IMPL_HARDWARE_ANY, mfxVersion::new(1, 0)).unwrap()
+    }
+
+    #[test]
+    fn returns_headers() {
+        let session = session();
+        let encoder = Encoder::init(&session, &mut VideoParam::new()).unwrap();
+        assert_eq!(stub::OPEN_ENCODERS.with(|open| open.get()), 1);
+
+        let headers = encoder.headers().unwrap();
+        assert_eq!(headers.sps, stub::SPS);
+        assert_eq!(headers.pps, stub::PPS);
+        assert_eq!(headers.sps_nal(), &stub::SPS[4..]);
+        assert_eq!(headers.pps_nal()[0] & 0x1f, 8);
+
+        drop(encoder);
+        assert_eq!(stub::OPEN_ENCODERS.with(|open| open.get()), 0);
+    }
+
+    fn config(width: u16, height: u16, kbps: u32) -> EncoderConfig {
+        EncoderConfig::new(CodecId::Avc, width, height).target_kbps(kbps)
+    }
+
+    #[test]
+    fn reconfigures_with_reset() {
+        let session = session();
+        let mut configured = config(1280, 720, 4000).init(&session).unwrap();
+        let mut pool = SurfacePool::new(&configured.request).unwrap();
+        let frame = |pool: &SurfacePool| (pool.surfaces()[0].Info.Width, pool.surfaces()[0].Data.Y);
+        let initial = frame(&pool);
+
+        let kind = configured.reconfigure(&config(1280, 720, 2000), &mut pool).unwrap();
+        assert_eq!(kind, Reconfiguration::Reset { new_sequence: false });
+        assert_eq!(unsafe { configured.params.mfx().as_encode().u2.TargetKbps }, 2000);
+        assert_eq!(frame(&pool), initial);
+
+        let kind = configured.reconfigure(&config(640, 360, 2000), &mut pool).unwrap();
+        assert_eq!(kind, Reconfiguration::Reset { new_sequence: true });
+        assert_eq!(configured.request.Info.Width, 640);
+        assert_eq!(frame(&pool).0, 640);
+        assert_ne!(frame(&pool).1, initial.1);
+        assert_eq!(stub::ENCODER_RESETS.with(|resets| resets.get()), 2);
+
+        let reconfigured = configured.encoder.reconfigure(&config(1920, 1080, 6000), &mut pool).unwrap();
+        assert!(reconfigured.reallocated);
+        assert_eq!(pool.surfaces()[0].Info.Width, 1920);
+        assert_eq!(pool.surfaces()[0].Info.CropH, 1080);
+        assert_eq!(pool.len(), stub::SUGGESTED_SURFACES as usize);
+        assert_eq!(stub::OPEN_ENCODERS.with(|open| open.get()), 1);
+    }
+
+    #[test]
+    fn scales_video_param_by_multiplier() {
+        let session = session();
+        let mut configured = config(1280, 720, 20000).init(&session).unwrap();
+        let mut pool = SurfacePool::new(&configured.request).unwrap();
+        let brc = BrcParams::read(configured.encoder.video_param().unwrap().mfx());
+        assert_eq!(brc.buffer_size_kb, stub::BUFFER_SIZE_KB as u32);
+
+        configured.reconfigure(&config(1280, 720, 200_000), &mut pool).unwrap();
+        assert_eq!(configured.params.mfx().BRCParamMultiplier, 4);
+        assert_eq!(BrcParams::read(configured.params.mfx()).target_kbps, 200_000);
+        let brc = BrcParams::read(configured.encoder.video_param().unwrap().mfx());
+        assert_eq!(brc.buffer_size_kb, 4 * stub::BUFFER_SIZE_KB as u32);
+    }
+
+    #[test]
+    fn reinitializes_when_reset_is_impossible() {
+        let session = session();
+        let mut configured = config(640, 360, 2000).init(&session).unwrap();
+        let mut pool = SurfacePool::new(&configured.request).unwrap();
+
+        let kind = configured.reconfigure(&config(1920, 1080, 6000), &mut pool).unwrap();
+        assert_eq!(kind, Reconfiguration::Reinitialized);
+        assert_eq!(configured.request.Info.Width, 1920);
+        assert_eq!(configured.request.Info.CropH, 1080);
+        assert_eq!(stub::ENCODER_RESETS.with(|resets| resets.get()), 0);
+        assert_eq!(stub::OPEN_ENCODERS.with(|open| open.get()), 1);
+
+        drop(configured);
+        assert_eq!(stub::OPEN_ENCODERS.with(|open| open.get()), 0);
+    }
+
+    #[test]
+    fn init_error() {
+        let session = Session::with_library(stub::library(), MFX_IMPL_HARDWARE_ANY, mfxVersion::new(1, 0)).unwrap();
+        let err = Encoder::init(&session, &mut VideoParam::new()).err().unwrap();
+        assert_eq!(err.status(), Some(MfxStatus::Unsupported));
+    }
+}
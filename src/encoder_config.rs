@@ -0,0 +1,479 @@
+//! High-level encoder configuration.
+//!
+//! [`EncoderConfig`] builds the encoder mfxVideoParam and its extended buffers. [`EncoderConfig::init`] runs `MFXVideoENCODE_Query` before `QueryIOSurf` and `Init`, and reports every explicitly requested field the implementation changed, so a silently clamped bitrate or a dropped option can be logged or, with [`EncoderConfig::strict`], turned into an error.
+use std::fmt;
+
+#[cfg(feature = "hardware")]
+use crate::encoder::{Encoder, Reconfiguration};
+use crate::enums::*;
+#[cfg(feature = "hardware")]
+use crate::error::{Error, Result};
+use crate::ext_buffer::{ExtBuffer, VideoParam};
+use crate::rate_control::{BrcParams, RateControl};
+use crate::status::MfxStatus;
+use crate::structures::*;
+#[cfg(feature = "hardware")]
+use crate::surface_pool::SurfacePool;
+#[cfg(feature = "hardware")]
+use crate::utils::check_error;
+use crate::utils::align16;
+#[cfg(feature = "hardware")]
+use crate::Session;
+
+/// A field whose value after `Query` differs from the requested one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Adjustment {
+    /// Name of the field, prefixed with the extended buffer ID for buffer fields, e.g. "TargetKbps" or "CDO2.MBBRC".
+    pub field: String,
+    pub requested: i64,
+    /// The corrected value; zero if the implementation does not support the requested one.
+    pub actual: i64,
+}
+
+impl Adjustment {
+    /// The implementation cleared the field, i.e. it could not determine a valid value.
+    pub fn is_zeroed(&self) -> bool {
+        self.actual == 0
+    }
+}
+
+impl fmt::Display for Adjustment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zeroed() {
+            write!(f, "{} {} not supported", self.field, self.requested)
+        } else {
+            write!(f, "{} {} changed to {}", self.field, self.requested, self.actual)
+        }
+    }
+}
+
+/// Outcome of `MFXVideoENCODE_Query` for a configuration.
+#[derive(Debug, Clone)]
+pub struct QueryReport {
+    /// The status returned by Query, e.g. `MFX_WRN_INCOMPATIBLE_VIDEO_PARAM` if fields were corrected.
+    pub status: MfxStatus,
+    /// The requested fields the implementation changed. Fields left at zero (unspecified) are not compared.
+    pub adjustments: Vec<Adjustment>,
+}
+
+impl QueryReport {
+    /// The configuration was accepted as requested.
+    pub fn is_unchanged(&self) -> bool {
+        self.adjustments.is_empty()
+    }
+}
+
+type Getter = fn(&mfxVideoParam) -> i64;
+
+/// The compared mfxVideoParam fields. The union fields are read through the encoder view of `mfx`; the rate control fields are in [`rate_control_fields`], which also covers BRCParamMultiplier: only the scaled values matter.
+const FIELDS: &[(&str, Getter)] = &[
+    ("AsyncDepth", |p| p.AsyncDepth as i64),
+    ("IOPattern", |p| p.IOPattern as i64),
+    ("LowPower", |p| p.mfx().LowPower as i64),
+    ("CodecId", |p| p.mfx().CodecId as i64),
+    ("CodecProfile", |p| p.mfx().CodecProfile as i64),
+    ("CodecLevel", |p| p.mfx().CodecLevel as i64),
+    ("TargetUsage", |p| p.mfx().as_encode().TargetUsage as i64),
+    ("GopPicSize", |p| p.mfx().as_encode().GopPicSize as i64),
+    ("GopRefDist", |p| p.mfx().as_encode().GopRefDist as i64),
+    ("GopOptFlag", |p| p.mfx().as_encode().GopOptFlag as i64),
+    ("IdrInterval", |p| p.mfx().as_encode().IdrInterval as i64),
+    ("RateControlMethod", |p| p.mfx().as_encode().RateControlMethod as i64),
+    ("NumSlice", |p| p.mfx().as_encode().NumSlice as i64),
+    ("NumRefFrame", |p| p.mfx().as_encode().NumRefFrame as i64),
+    ("EncodedOrder", |p| p.mfx().as_encode().EncodedOrder as i64),
+    ("FrameInfo.FourCC", |p| p.mfx().FrameInfo.FourCC as i64),
+    ("FrameInfo.ChromaFormat", |p| p.mfx().FrameInfo.ChromaFormat as i64),
+    ("FrameInfo.PicStruct", |p| p.mfx().FrameInfo.PicStruct as i64),
+    ("FrameInfo.Width", |p| p.mfx().FrameInfo.Width as i64),
+    ("FrameInfo.Height", |p| p.mfx().FrameInfo.Height as i64),
+    ("FrameInfo.CropX", |p| p.mfx().FrameInfo.CropX as i64),
+    ("FrameInfo.CropY", |p| p.mfx().FrameInfo.CropY as i64),
+    ("FrameInfo.CropW", |p| p.mfx().FrameInfo.CropW as i64),
+    ("FrameInfo.CropH", |p| p.mfx().FrameInfo.CropH as i64),
+    ("FrameInfo.FrameRateExtN", |p| p.mfx().FrameInfo.FrameRateExtN as i64),
+    ("FrameInfo.FrameRateExtD", |p| p.mfx().FrameInfo.FrameRateExtD as i64),
+    ("FrameInfo.AspectRatioW", |p| p.mfx().FrameInfo.AspectRatioW as i64),
+    ("FrameInfo.AspectRatioH", |p| p.mfx().FrameInfo.AspectRatioH as i64),
+    ("FrameInfo.BitDepthLuma", |p| p.mfx().FrameInfo.BitDepthLuma as i64),
+    ("FrameInfo.BitDepthChroma", |p| p.mfx().FrameInfo.BitDepthChroma as i64),
+];
+
+/// BufferSizeInKB and the fields `method` stores in the unions u1, u2 and u3 of `mfx`, named after their meaning for it,
+/// e.g. QPI rather than InitialDelayInKB for CQP. Bitrates and buffer sizes are multiplied by BRCParamMultiplier.
+fn rate_control_fields(method: Option<RateControlMethod>, mfx: &mfxInfoMFX) -> Vec<(&'static str, i64)> {
+    let brc = BrcParams::read(mfx);
+    let encode = mfx.as_encode();
+    let (u1, u2, u3) = unsafe { (encode.u1.QPI as i64, encode.u2.QPP as i64, encode.u3.QPB as i64) };
+    let mut fields = vec![("BufferSizeInKB", brc.buffer_size_kb as i64)];
+    match method {
+        Some(RateControlMethod::Cqp) => fields.extend([("QPI", u1), ("QPP", u2), ("QPB", u3)]),
+        Some(RateControlMethod::Avbr) => {
+            fields.extend([("Accuracy", u1), ("TargetKbps", brc.target_kbps as i64), ("Convergence", u3)])
+        }
+        Some(RateControlMethod::Icq | RateControlMethod::LaIcq) => fields.push(("ICQQuality", u2)),
+        _ => fields.extend([
+            ("InitialDelayInKB", brc.initial_delay_kb as i64),
+            ("TargetKbps", brc.target_kbps as i64),
+            ("MaxKbps", brc.max_kbps as i64),
+        ]),
+    }
+    fields
+}
+
+/// The explicitly requested fields of `requested`, including those of its extended buffers, that differ in `actual`.
+pub fn adjustments(requested: &VideoParam, actual: &VideoParam) -> Vec<Adjustment> {
+    let mut adjustments = Vec::new();
+    let mut compare = |field: String, requested: i64, actual: i64| {
+        if requested != 0 && requested != actual {
+            adjustments.push(Adjustment {
+                field,
+                requested,
+                actual,
+            });
+        }
+    };
+
+    for (name, get) in FIELDS {
+        compare(name.to_string(), get(requested), get(actual));
+    }
+    // Named after the requested method; a changed method is reported as RateControlMethod above.
+    let method = requested.mfx().as_encode().rate_control_method().ok();
+    let requested_fields = rate_control_fields(method, requested.mfx());
+    let actual_fields = rate_control_fields(method, actual.mfx());
+    for ((name, requested), (_, actual)) in requested_fields.into_iter().zip(actual_fields) {
+        compare(name.to_string(), requested, actual);
+    }
+
+    let actual_buffers = actual.ext_buffers().fields();
+    for (id, fields) in requested.ext_buffers().fields() {
+        let actual_fields = actual_buffers
+            .iter()
+            .find(|(actual_id, _)| *actual_id == id)
+            .map(|(_, fields)| fields.as_slice())
+            .unwrap_or_default();
+        for (name, value) in fields {
+            let actual_value = actual_fields
+                .iter()
+                .find(|(actual_name, _)| *actual_name == name)
+                .map_or(0, |(_, value)| *value);
+            compare(format!("{id}.{name}"), value, actual_value);
+        }
+    }
+    adjustments
+}
+
+/// Builder for the parameters of an encoder reading system memory surfaces.
+#[derive(Debug, Clone)]
+pub struct EncoderConfig {
+    params: VideoParam,
+    /// Set with [`EncoderConfig::rate_control_config`], validated before `Query`.
+    rate_control: Option<RateControl>,
+    strict: bool,
+}
+
+impl EncoderConfig {
+    /// An NV12 4:2:0 progressive stream of `width` x `height` pixels at 30 fps, with the balanced target usage.
+    pub fn new(codec: CodecId, width: u16, height: u16) -> Self {
+        let mut params = VideoParam::new();
+        params.set_io_pattern(IOPattern::IN_SYSTEM_MEMORY);
+        let mfx = params.mfx_mut();
+        mfx.set_codec_id(codec);
+        mfx.as_encode_mut().set_target_usage(TargetUsage::Balanced);
+        let info = &mut mfx.FrameInfo;
+        info.set_fourcc(ColorFourCC::Nv12);
+        info.set_chroma_format(ChromaFormat::Yuv420);
+        info.set_pic_struct(PicStruct::PROGRESSIVE);
+        info.FrameRateExtN = 30;
+        info.FrameRateExtD = 1;
+        info.CropX = 0;
+        info.CropY = 0;
+        info.CropW = width;
+        info.CropH = height;
+        info.Width = align16(width);
+        info.Height = align16(height);
+        EncoderConfig {
+            params,
+            rate_control: None,
+            strict: false,
+        }
+    }
+
+    pub fn frame_rate(mut self, numerator: u32, denominator: u32) -> Self {
+        self.params.mfx_mut().FrameInfo.FrameRateExtN = numerator;
+        self.params.mfx_mut().FrameInfo.FrameRateExtD = denominator;
+        self
+    }
+
+    /// Color format of the input surfaces.
+    pub fn fourcc(mut self, fourcc: ColorFourCC) -> Self {
+        self.params.mfx_mut().FrameInfo.set_fourcc(fourcc);
+        self
+    }
+
+    pub fn target_usage(mut self, target_usage: TargetUsage) -> Self {
+        self.params.mfx_mut().as_encode_mut().set_target_usage(target_usage);
+        self
+    }
+
+    pub fn rate_control(mut self, method: RateControlMethod) -> Self {
+        self.params.mfx_mut().as_encode_mut().set_rate_control_method(method);
+        self
+    }
+
+    /// Sets the bitrate control method and all of its parameters, replacing those of the previous method. The parameters
+    /// are checked with [`RateControl::validate`] when the encoder is queried.
+    pub fn rate_control_config(mut self, rate_control: RateControl) -> Self {
+        rate_control.apply(&mut self.params);
+        self.rate_control = Some(rate_control);
+        self
+    }
+
+    /// Sets TargetKbps, picking the BRCParamMultiplier for it and the other bitrates and buffer sizes.
+    pub fn target_kbps(mut self, kbps: u32) -> Self {
+        let mut brc = BrcParams::read(self.params.mfx());
+        brc.target_kbps = kbps;
+        brc.write(self.params.mfx_mut());
+        self
+    }
+
+    /// Sets MaxKbps, picking the BRCParamMultiplier for it and the other bitrates and buffer sizes.
+    pub fn max_kbps(mut self, kbps: u32) -> Self {
+        let mut brc = BrcParams::read(self.params.mfx());
+        brc.max_kbps = kbps;
+        brc.write(self.params.mfx_mut());
+        self
+    }
+
+    /// Number of frames in a GOP and distance between anchor frames; zero lets the encoder choose.
+    pub fn gop(mut self, pic_size: u16, ref_dist: u16) -> Self {
+        self.params.mfx_mut().as_encode_mut().GopPicSize = pic_size;
+        self.params.mfx_mut().as_encode_mut().GopRefDist = ref_dist;
+        self
+    }
+
+    pub fn idr_interval(mut self, interval: u16) -> Self {
+        self.params.mfx_mut().as_encode_mut().IdrInterval = interval;
+        self
+    }
+
+    pub fn async_depth(mut self, depth: u16) -> Self {
+        self.params.AsyncDepth = depth;
+        self
+    }
+
+    /// Sets fields of the extended buffer `T`, attaching it first if needed.
+    pub fn ext_buffer<T: ExtBuffer + Default>(mut self, configure: impl FnOnce(&mut T)) -> Self {
+        configure(self.params.ext_buffers_mut().get_or_default::<T>());
+        self
+    }
+
+    /// Fail [`EncoderConfig::init`] with [`crate::Error::ParamsAdjusted`] instead of applying the corrected parameters.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// The parameters built so far.
+    pub fn params(&self) -> &VideoParam {
+        &self.params
+    }
+
+    /// Direct access to fields without a builder method.
+    pub fn params_mut(&mut self) -> &mut VideoParam {
+        &mut self.params
+    }
+}
+
+/// An encoder initialized by [`EncoderConfig::init`].
+#[cfg(feature = "hardware")]
+pub struct ConfiguredEncoder<'a> {
+    pub encoder: Encoder<'a>,
+    /// The parameters the encoder was initialized with, i.e. the Query output.
+    pub params: VideoParam,
+    /// The input surfaces the encoder needs, from `MFXVideoENCODE_QueryIOSurf`.
+    pub request: mfxFrameAllocRequest,
+    pub report: QueryReport,
+}
+
+#[cfg(feature = "hardware")]
+impl EncoderConfig {
+    /// Checks the configuration with `MFXVideoENCODE_Query` and returns the corrected parameters.
+    ///
+    /// Fails with [`Error::RateControl`] without calling the SDK if the [`EncoderConfig::rate_control_config`] is invalid.
+    pub fn query(&self, session: &Session) -> Result<(VideoParam, QueryReport)> {
+        if let Some(rate_control) = &self.rate_control {
+            rate_control.validate()?;
+        }
+        let mut input = self.params.clone();
+        // The output must carry the same extended buffers as the input.
+        let mut output = self.params.clone();
+        let status = MfxStatus::from_raw(unsafe {
+            (session.library().MFXVideoENCODE_Query)(session.as_ptr(), input.as_mut_ptr(), output.as_mut_ptr())
+        })
+        .result()?;
+        let report = QueryReport {
+            status,
+            adjustments: adjustments(&self.params, &output),
+        };
+        Ok((output, report))
+    }
+
+    /// Like [`EncoderConfig::query`], but with [`EncoderConfig::strict`] any adjustment is an error.
+    pub(crate) fn checked_query(&self, session: &Session) -> Result<(VideoParam, QueryReport)> {
+        let (params, report) = self.query(session)?;
+        if self.strict && !report.is_unchanged() {
+            return Err(Error::ParamsAdjusted(report.adjustments));
+        }
+        Ok((params, report))
+    }
+
+    /// Runs Query, QueryIOSurf and Init. With [`EncoderConfig::strict`] any adjustment is an error, otherwise the encoder is initialized with the corrected parameters.
+    pub fn init<'a>(&self, session: &'a Session) -> Result<ConfiguredEncoder<'a>> {
+        let (mut params, report) = self.checked_query(session)?;
+        let request = query_io_surf(session, &mut params)?;
+        let encoder = Encoder::init(session, &mut params)?;
+        Ok(ConfiguredEncoder {
+            encoder,
+            params,
+            request,
+            report,
+        })
+    }
+}
+
+#[cfg(feature = "hardware")]
+impl ConfiguredEncoder<'_> {
+    /// Applies `config` with [`Encoder::reconfigure`], which also fits the input surfaces in `pool` to the new [`ConfiguredEncoder::request`], and replaces the parameters, surface request and report with the new ones.
+    pub fn reconfigure(&mut self, config: &EncoderConfig, pool: &mut SurfacePool) -> Result<Reconfiguration> {
+        let reconfigured = self.encoder.reconfigure(config, pool)?;
+        self.params = reconfigured.params;
+        self.request = reconfigured.request;
+        self.report = reconfigured.report;
+        Ok(reconfigured.kind)
+    }
+}
+
+/// The input surfaces the encoder needs for `params`, see `MFXVideoENCODE_QueryIOSurf`.
+#[cfg(feature = "hardware")]
+pub(crate) fn query_io_surf(session: &Session, params: &mut VideoParam) -> Result<mfxFrameAllocRequest> {
+    let mut request = mfxFrameAllocRequest::new();
+    check_error(unsafe {
+        (session.library().MFXVideoENCODE_QueryIOSurf)(session.as_ptr(), params.as_mut_ptr(), &mut request)
+    })?;
+    Ok(request)
+}
+
+#[cfg(all(test, feature = "hardware"))]
+mod test {
+    use super::*;
+    use crate::constants::*;
+    use crate::loader::stub;
+
+    fn session() -> Session {
+        Session::with_library(stub::encoder_library(), MFX_IMPL_HARDWARE_ANY, mfxVersion::new(1, 0)).unwrap()
+    }
+
+    fn config(kbps: u32) -> EncoderConfig {
+        EncoderConfig::new(CodecId::Avc, 1920, 1080)
+            .rate_control(RateControlMethod::Vbr)
+            .target_kbps(kbps)
+            .ext_buffer(|option: &mut mfxExtCodingOption2| {
+                option.MBBRC = CodingOption::On.raw();
+                option.ExtBRC = CodingOption::On.raw();
+            })
+    }
+
+    #[test]
+    fn reports_adjustments() {
+        let session = session();
+        let configured = config(600_000).init(&session).unwrap();
+        assert_eq!(configured.report.status, MfxStatus::IncompatibleVideoParamWarning);
+        assert_eq!(
+            configured.report.adjustments,
+            [
+                Adjustment {
+                    field: "TargetKbps".into(),
+                    requested: 600_000,
+                    actual: stub::MAX_KBPS as i64,
+                },
+                Adjustment {
+                    field: "CDO2.ExtBRC".into(),
+                    requested: MFX_CODINGOPTION_ON as i64,
+                    actual: 0,
+                },
+            ]
+        );
+        assert_eq!(configured.report.adjustments[1].to_string(), "CDO2.ExtBRC 16 not supported");
+        // The multiplier is kept, only TargetKbps changed
+        assert_eq!(configured.params.mfx().BRCParamMultiplier, 10);
+        assert_eq!(BrcParams::read(configured.params.mfx()).target_kbps, stub::MAX_KBPS);
+        assert_eq!(configured.request.NumFrameSuggested, stub::SUGGESTED_SURFACES);
+        assert_eq!(stub::OPEN_ENCODERS.with(|open| open.get()), 1);
+    }
+
+    #[test]
+    fn ignores_repicked_multiplier() {
+        let requested = config(600_000).params;
+        let mut actual = requested.clone();
+        // The same 600000 Kbps with a multiplier of 20
+        actual.mfx_mut().BRCParamMultiplier = 20;
+        actual.mfx_mut().as_encode_mut().u2.TargetKbps = 30000;
+        assert_eq!(requested.mfx().BRCParamMultiplier, 10);
+        assert!(adjustments(&requested, &actual).is_empty());
+
+        actual.mfx_mut().as_encode_mut().u2.TargetKbps = 20000;
+        assert_eq!(adjustments(&requested, &actual)[0].actual, 400_000);
+    }
+
+    #[test]
+    fn strict_rejects_adjustments() {
+        let session = session();
+        let err = config(600_000).strict(true).init(&session).err().unwrap();
+        assert!(matches!(&err, Error::ParamsAdjusted(adjustments) if adjustments.len() == 2));
+        assert_eq!(stub::OPEN_ENCODERS.with(|open| open.get()), 0);
+
+        let config = EncoderConfig::new(CodecId::Avc, 1280, 720).target_kbps(5000).strict(true);
+        let configured = config.init(&session).unwrap();
+        assert_eq!(configured.report.status, MfxStatus::None);
+        assert!(configured.report.is_unchanged());
+    }
+
+    #[test]
+    fn names_fields_after_rate_control_method() {
+        let session = session();
+        let cqp = RateControl::Cqp { qpi: 4, qpp: 24, qpb: 26 };
+        let config = || EncoderConfig::new(CodecId::Avc, 1280, 720).rate_control_config(cqp);
+        let configured = config().init(&session).unwrap();
+        assert_eq!(
+            configured.report.adjustments,
+            [Adjustment {
+                field: "QPI".into(),
+                requested: 4,
+                actual: stub::MIN_QPI as i64,
+            }]
+        );
+
+        let err = config().strict(true).init(&session).err().unwrap();
+        assert!(matches!(&err, Error::ParamsAdjusted(adjustments) if adjustments[0].field == "QPI"));
+    }
+
+    #[test]
+    fn validates_rate_control_before_query() {
+        let session = session();
+        let config = |rate_control: &str| {
+            let rate_control: RateControl = rate_control.parse().unwrap();
+            EncoderConfig::new(CodecId::Avc, 1280, 720).rate_control_config(rate_control.with_target_kbps(5000))
+        };
+        let err = config("vbr,max=4000").init(&session).err().unwrap();
+        assert!(matches!(err, Error::RateControl(_)));
+        assert_eq!(stub::OPEN_ENCODERS.with(|open| open.get()), 0);
+
+        let configured = config("la,lookahead=40").strict(true).init(&session).unwrap();
+        let encode = configured.params.mfx().as_encode();
+        assert_eq!(encode.rate_control_method(), Ok(RateControlMethod::La));
+        assert_eq!(unsafe { encode.u2.TargetKbps }, 5000);
+        let option = configured.params.ext_buffers().get::<mfxExtCodingOption2>().unwrap();
+        assert_eq!(option.LookAheadDepth, 40);
+    }
+}
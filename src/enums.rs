@@ -0,0 +1,423 @@
+//! Typed versions of the SDK enumerators stored as raw integers in the structures.
+//!
+//! Plain enumerators become Rust enums with a checked `TryFrom` conversion from the raw value; bit masks (IOPattern, PicStruct, FrameType) become `bitflags` types. The structures in [`crate::structures`] have accessors using these types, e.g. [`crate::structures::mfxFrameInfo::set_fourcc`].
+use std::convert::TryFrom;
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+use bitflags::bitflags;
+
+use crate::constants::*;
+use crate::utils::MFX_MAKEFOURCC;
+
+/// Error returned when a raw value does not match any variant of an enumerator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownValue {
+    /// Name of the enumerator.
+    pub kind: &'static str,
+    /// The raw value.
+    pub value: u32,
+}
+
+impl fmt::Display for UnknownValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown {} value {}", self.kind, self.value)
+    }
+}
+
+impl error::Error for UnknownValue {}
+
+/// A four character code, as built by `MFX_MAKEFOURCC`. Displays as its characters, e.g. "NV12".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FourCC(pub mfxU32);
+
+impl FourCC {
+    pub const fn from_bytes(code: [u8; 4]) -> Self {
+        FourCC(MFX_MAKEFOURCC!(code[0], code[1], code[2], code[3]))
+    }
+
+    pub const fn to_bytes(self) -> [u8; 4] {
+        self.0.to_le_bytes()
+    }
+}
+
+impl fmt::Display for FourCC {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.to_bytes().iter().all(|c| c.is_ascii_graphic() || *c == b' ') {
+            // Codec IDs are padded with spaces, e.g. "AVC ".
+            let code: String = self.to_bytes().iter().map(|&c| c as char).collect();
+            f.write_str(code.trim_end())
+        } else {
+            write!(f, "0x{:08x}", self.0)
+        }
+    }
+}
+
+impl From<mfxU32> for FourCC {
+    fn from(value: mfxU32) -> Self {
+        FourCC(value)
+    }
+}
+
+macro_rules! raw_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident: $raw:ty {
+            $($(#[$variant_meta:meta])* $variant:ident = $value:expr,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)*
+        }
+
+        impl $name {
+            /// The raw value stored in the SDK structures.
+            pub const fn raw(self) -> $raw {
+                match self {
+                    $($name::$variant => $value,)*
+                }
+            }
+        }
+
+        impl From<$name> for $raw {
+            fn from(value: $name) -> $raw {
+                value.raw()
+            }
+        }
+
+        impl TryFrom<$raw> for $name {
+            type Error = UnknownValue;
+
+            fn try_from(value: $raw) -> Result<Self, UnknownValue> {
+                $(if value == $value {
+                    return Ok($name::$variant);
+                })*
+                Err(UnknownValue {
+                    kind: stringify!($name),
+                    value: value as u32,
+                })
+            }
+        }
+    };
+}
+
+raw_enum! {
+    /// Color format of a frame surface, the FourCC field of mfxFrameInfo.
+    pub enum ColorFourCC: mfxU32 {
+        /// NV12 color planes. Native format for 4:2:0/8b Gen hardware implementations.
+        Nv12 = MFX_FOURCC_NV12,
+        /// Same as NV12 but with weaved V and U values.
+        Nv21 = MFX_FOURCC_NV21,
+        /// YV12 color planes, V before U.
+        Yv12 = MFX_FOURCC_YV12,
+        /// Same as YV12 except that the U and V planes are swapped (I420).
+        Iyuv = MFX_FOURCC_IYUV,
+        /// 4:2:2 color format with similar to NV12 layout.
+        Nv16 = MFX_FOURCC_NV16,
+        /// YUY2 color planes.
+        Yuy2 = MFX_FOURCC_YUY2,
+        /// UYVY color planes. Same as YUY2 except the byte order is reversed.
+        Uyvy = MFX_FOURCC_UYVY,
+        /// RGB4 (RGB32) color planes. BGRA is the order, 'B' is 8 MSBs, then 8 bits for 'G' channel, then 'R' and 'A' channels.
+        Rgb4 = MFX_FOURCC_RGB4,
+        /// RGBA color format. It is similar to MFX_FOURCC_RGB4 but with different order of channels. 'R' is 8 MSBs, then 8 bits for 'G' channel, then 'B' and 'A' channels.
+        Bgr4 = MFX_FOURCC_BGR4,
+        /// 10 bits ARGB color format packed in 32 bits.
+        A2Rgb10 = MFX_FOURCC_A2RGB10,
+        /// P010 color format. This is 10 bit per sample format with similar to NV12 layout.
+        P010 = MFX_FOURCC_P010,
+        /// 10 bit per sample 4:2:2 color format with similar to NV12 layout.
+        P210 = MFX_FOURCC_P210,
+        /// YUV 4:4:4, AYUV color format.
+        Ayuv = MFX_FOURCC_AYUV,
+        /// 10 bit per sample 4:2:2 packed color format with similar to YUY2 layout.
+        Y210 = MFX_FOURCC_Y210,
+        /// 10 bit per sample 4:4:4 packed color format.
+        Y410 = MFX_FOURCC_Y410,
+    }
+}
+
+impl From<ColorFourCC> for FourCC {
+    fn from(value: ColorFourCC) -> Self {
+        FourCC(value.raw())
+    }
+}
+
+impl fmt::Display for ColorFourCC {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        FourCC::from(*self).fmt(f)
+    }
+}
+
+/// Parses the characters of the code, ignoring case, e.g. "nv12".
+impl FromStr for ColorFourCC {
+    type Err = UnknownValue;
+
+    fn from_str(s: &str) -> Result<Self, UnknownValue> {
+        let mut code = [b' '; 4];
+        if s.len() > code.len() {
+            return Err(UnknownValue { kind: "ColorFourCC", value: 0 });
+        }
+        for (dst, src) in code.iter_mut().zip(s.bytes()) {
+            *dst = src.to_ascii_uppercase();
+        }
+        ColorFourCC::try_from(FourCC::from_bytes(code).0)
+    }
+}
+
+raw_enum! {
+    /// Codec of an encoder or decoder, the CodecId field of mfxInfoMFX.
+    pub enum CodecId: mfxU32 {
+        /// AVC, H.264, or MPEG-4, part 10 codec.
+        Avc = MFX_CODEC_AVC,
+        /// HEVC codec.
+        Hevc = MFX_CODEC_HEVC,
+        /// MPEG-2 codec.
+        Mpeg2 = MFX_CODEC_MPEG2,
+        /// VC-1 codec.
+        Vc1 = MFX_CODEC_VC1,
+        /// VP8 codec.
+        Vp8 = MFX_CODEC_VP8,
+        /// VP9 codec.
+        Vp9 = MFX_CODEC_VP9,
+        /// AV1 codec.
+        Av1 = MFX_CODEC_AV1,
+        /// JPEG codec.
+        Jpeg = MFX_CODEC_JPEG,
+    }
+}
+
+impl From<CodecId> for FourCC {
+    fn from(value: CodecId) -> Self {
+        FourCC(value.raw())
+    }
+}
+
+impl fmt::Display for CodecId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        FourCC::from(*self).fmt(f)
+    }
+}
+
+raw_enum! {
+    /// Bitrate control method, the RateControlMethod field of mfxInfoMFX.
+    pub enum RateControlMethod: mfxU16 {
+        /// Use the constant bitrate control algorithm.
+        Cbr = MFX_RATECONTROL_CBR,
+        /// Use the variable bitrate control algorithm.
+        Vbr = MFX_RATECONTROL_VBR,
+        /// Use the constant quantization parameter algorithm.
+        Cqp = MFX_RATECONTROL_CQP,
+        /// Use the average variable bitrate control algorithm.
+        Avbr = MFX_RATECONTROL_AVBR,
+        /// Use the VBR algorithm with look ahead. It is a special bitrate control mode in the SDK AVC encoder that has been designed to improve encoding quality.
+        La = MFX_RATECONTROL_LA,
+        /// Use the Intelligent Constant Quality algorithm.
+        Icq = MFX_RATECONTROL_ICQ,
+        /// Use the Video Conferencing Mode algorithm.
+        Vcm = MFX_RATECONTROL_VCM,
+        /// Use Intelligent Constant Quality algorithm with look ahead.
+        LaIcq = MFX_RATECONTROL_LA_ICQ,
+        /// Use the VBR algorithm with look ahead analysis done outside of the encoder.
+        LaExt = MFX_RATECONTROL_LA_EXT,
+        /// Use the HRD compliant look ahead rate control algorithm.
+        LaHrd = MFX_RATECONTROL_LA_HRD,
+        /// Use the variable bitrate control algorithm with constant quality.
+        Qvbr = MFX_RATECONTROL_QVBR,
+    }
+}
+
+raw_enum! {
+    /// Trade-off between quality and speed, the TargetUsage field of mfxInfoMFX.
+    pub enum TargetUsage: mfxU16 {
+        /// Let the encoder choose.
+        Unknown = MFX_TARGETUSAGE_UNKNOWN,
+        /// Best quality.
+        BestQuality = MFX_TARGETUSAGE_1,
+        Level2 = MFX_TARGETUSAGE_2,
+        Level3 = MFX_TARGETUSAGE_3,
+        /// Balanced quality and speed.
+        Balanced = MFX_TARGETUSAGE_4,
+        Level5 = MFX_TARGETUSAGE_5,
+        Level6 = MFX_TARGETUSAGE_6,
+        /// Best speed.
+        BestSpeed = MFX_TARGETUSAGE_7,
+    }
+}
+
+raw_enum! {
+    /// Chroma sampling, the ChromaFormat field of mfxFrameInfo.
+    pub enum ChromaFormat: mfxU16 {
+        /// Monochrome (4:0:0).
+        Monochrome = MFX_CHROMAFORMAT_MONOCHROME,
+        /// 4:2:0 color.
+        Yuv420 = MFX_CHROMAFORMAT_YUV420,
+        /// 4:2:2 color, horizontally subsampled.
+        Yuv422 = MFX_CHROMAFORMAT_YUV422,
+        /// 4:4:4 color.
+        Yuv444 = MFX_CHROMAFORMAT_YUV444,
+        /// 4:1:1 color.
+        Yuv411 = MFX_CHROMAFORMAT_YUV411,
+        /// 4:2:2 color, vertically subsampled.
+        Yuv422V = MFX_CHROMAFORMAT_YUV422V,
+    }
+}
+
+raw_enum! {
+    /// Tri-state value of the options in the extended coding option buffers, e.g. mfxExtCodingOption2::MBBRC.
+    pub enum CodingOption: mfxU16 {
+        /// Let the SDK choose.
+        Unknown = MFX_CODINGOPTION_UNKNOWN,
+        On = MFX_CODINGOPTION_ON,
+        Off = MFX_CODINGOPTION_OFF,
+        /// Enabled where the SDK decides it helps, only valid for some options.
+        Adaptive = MFX_CODINGOPTION_ADAPTIVE,
+    }
+}
+
+raw_enum! {
+    /// How the encoder skips frames, mfxExtCodingOption2::SkipFrame. Frames are marked for skipping per frame with [`crate::frame_control::FrameControl::skip`].
+    pub enum SkipFrame: mfxU16 {
+        /// Frame skipping is disabled.
+        NoSkip = MFX_SKIPFRAME_NO_SKIP,
+        /// The encoder inserts a frame that repeats the previous one, with skipped macroblocks.
+        InsertDummy = MFX_SKIPFRAME_INSERT_DUMMY,
+        /// The encoder does not write the frame at all.
+        InsertNothing = MFX_SKIPFRAME_INSERT_NOTHING,
+        /// The application skipped the frames itself; mfxEncodeCtrl::SkipFrame is the number of frames it dropped before the current one, which the rate control accounts for.
+        BrcOnly = MFX_SKIPFRAME_BRC_ONLY,
+    }
+}
+
+impl From<bool> for CodingOption {
+    fn from(enabled: bool) -> Self {
+        if enabled {
+            CodingOption::On
+        } else {
+            CodingOption::Off
+        }
+    }
+}
+
+bitflags! {
+    /// Memory types of the input and output surfaces, the IOPattern field of mfxVideoParam.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct IOPattern: mfxU16 {
+        /// Input to SDK functions is a video memory surface.
+        const IN_VIDEO_MEMORY = MFX_IOPATTERN_IN_VIDEO_MEMORY;
+        /// Input to SDK functions is a linear buffer directly in system memory or in system memory through an external allocator.
+        const IN_SYSTEM_MEMORY = MFX_IOPATTERN_IN_SYSTEM_MEMORY;
+        /// Input to SDK functions maps at runtime to either a system memory buffer or a video memory surface.
+        const IN_OPAQUE_MEMORY = MFX_IOPATTERN_IN_OPAQUE_MEMORY;
+        /// Output to SDK functions is a video memory surface.
+        const OUT_VIDEO_MEMORY = MFX_IOPATTERN_OUT_VIDEO_MEMORY;
+        /// Output to SDK functions is a linear buffer directly in system memory or in system memory through an external allocator.
+        const OUT_SYSTEM_MEMORY = MFX_IOPATTERN_OUT_SYSTEM_MEMORY;
+        /// Output to SDK functions maps at runtime to either a system memory buffer or a video memory surface.
+        const OUT_OPAQUE_MEMORY = MFX_IOPATTERN_OUT_OPAQUE_MEMORY;
+    }
+}
+
+bitflags! {
+    /// Picture structure, the PicStruct field of mfxFrameInfo and mfxBitstream. An empty value is `MFX_PICSTRUCT_UNKNOWN`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct PicStruct: mfxU16 {
+        /// Progressive picture.
+        const PROGRESSIVE = MFX_PICSTRUCT_PROGRESSIVE;
+        /// Top field in first interlaced picture.
+        const FIELD_TFF = MFX_PICSTRUCT_FIELD_TFF;
+        /// Bottom field in first interlaced picture.
+        const FIELD_BFF = MFX_PICSTRUCT_FIELD_BFF;
+        /// First field repeated: pic_struct=5 or 6 in H.264.
+        const FIELD_REPEATED = MFX_PICSTRUCT_FIELD_REPEATED;
+        /// Double the frame for display: pic_struct=7 in H.264.
+        const FRAME_DOUBLING = MFX_PICSTRUCT_FRAME_DOUBLING;
+        /// Triple the frame for display: pic_struct=8 in H.264.
+        const FRAME_TRIPLING = MFX_PICSTRUCT_FRAME_TRIPLING;
+        /// Single field in a picture.
+        const FIELD_SINGLE = MFX_PICSTRUCT_FIELD_SINGLE;
+        /// This field is paired with the previous field in decoding order.
+        const FIELD_PAIRED_PREV = MFX_PICSTRUCT_FIELD_PAIRED_PREV;
+        /// This field is paired with the next field in decoding order.
+        const FIELD_PAIRED_NEXT = MFX_PICSTRUCT_FIELD_PAIRED_NEXT;
+    }
+}
+
+bitflags! {
+    /// Frame type, the FrameType field of mfxEncodeCtrl and mfxBitstream. The `X*` flags describe the second field of an interlaced frame. An empty value is `MFX_FRAMETYPE_UNKNOWN`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct FrameType: mfxU16 {
+        /// This frame or the first field is encoded as an I frame/field.
+        const I = MFX_FRAMETYPE_I;
+        /// This frame or the first field is encoded as an P frame/field.
+        const P = MFX_FRAMETYPE_P;
+        /// This frame or the first field is encoded as an B frame/field.
+        const B = MFX_FRAMETYPE_B;
+        /// This frame or the first field is either an SI- or SP-frame/field.
+        const S = MFX_FRAMETYPE_S;
+        /// This frame or the first field is encoded as a reference.
+        const REF = MFX_FRAMETYPE_REF;
+        /// This frame or the first field is encoded as an IDR.
+        const IDR = MFX_FRAMETYPE_IDR;
+        /// The second field is encoded as an I-field.
+        const XI = MFX_FRAMETYPE_xI;
+        /// The second field is encoded as an P-field.
+        const XP = MFX_FRAMETYPE_xP;
+        /// The second field is encoded as an B-field.
+        const XB = MFX_FRAMETYPE_xB;
+        /// The second field is an SI- or SP-field.
+        const XS = MFX_FRAMETYPE_xS;
+        /// The second field is encoded as a reference.
+        const XREF = MFX_FRAMETYPE_xREF;
+        /// The second field is encoded as an IDR.
+        const XIDR = MFX_FRAMETYPE_xIDR;
+    }
+}
+
+impl FrameType {
+    /// A key frame: an IDR, or an I frame the decoder can start from.
+    pub fn is_key_frame(self) -> bool {
+        self.contains(FrameType::IDR) || self.contains(FrameType::I)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn four_cc_display() {
+        assert_eq!(FourCC::from_bytes(*b"NV12"), FourCC(MFX_FOURCC_NV12));
+        assert_eq!(ColorFourCC::Nv12.to_string(), "NV12");
+        assert_eq!(CodecId::Avc.to_string(), "AVC");
+        assert_eq!(FourCC(0x0102_0304).to_string(), "0x01020304");
+    }
+
+    #[test]
+    fn parse_color_four_cc() {
+        assert_eq!("nv12".parse::<ColorFourCC>(), Ok(ColorFourCC::Nv12));
+        assert_eq!("YV12".parse::<ColorFourCC>(), Ok(ColorFourCC::Yv12));
+        assert!("h264".parse::<ColorFourCC>().is_err());
+        assert!("nv12x".parse::<ColorFourCC>().is_err());
+    }
+
+    #[test]
+    fn checked_conversions() {
+        assert_eq!(ColorFourCC::try_from(MFX_FOURCC_YV12), Ok(ColorFourCC::Yv12));
+        assert_eq!(RateControlMethod::try_from(MFX_RATECONTROL_VBR), Ok(RateControlMethod::Vbr));
+        assert_eq!(TargetUsage::try_from(MFX_TARGETUSAGE_BALANCED), Ok(TargetUsage::Balanced));
+        assert_eq!(
+            RateControlMethod::try_from(42).unwrap_err().to_string(),
+            "unknown RateControlMethod value 42"
+        );
+        assert!(CodecId::try_from(MFX_FOURCC_NV12).is_err());
+
+        assert_eq!(PicStruct::from_bits(MFX_PICSTRUCT_FIELD_TOP), Some(PicStruct::FIELD_SINGLE | PicStruct::FIELD_TFF));
+        assert_eq!(IOPattern::from_bits(0x08), None);
+        assert!((FrameType::I | FrameType::REF | FrameType::IDR).is_key_frame());
+        assert!(!FrameType::P.is_key_frame());
+    }
+}
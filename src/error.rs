@@ -0,0 +1,139 @@
+//! The crate's error type.
+use std::error;
+use std::fmt;
+use std::io;
+
+use crate::encoder_config::Adjustment;
+use crate::enums::FourCC;
+#[cfg(feature = "hardware")]
+use crate::loader::LoadError;
+use crate::rate_control::InvalidRateControl;
+use crate::status::MfxStatus;
+
+/// Errors returned by the session, encoder and VPP wrappers.
+///
+/// Converts into [`io::Error`], so it can be propagated with `?` from functions returning `io::Result`; the original error is kept and can be recovered with [`io::Error::get_ref`] and `downcast_ref`.
+#[derive(Debug)]
+pub enum Error {
+    /// An SDK function returned an error status.
+    Status(MfxStatus),
+    /// The SDK library could not be loaded.
+    #[cfg(feature = "hardware")]
+    Library(LoadError),
+    /// Reading the input or writing the output failed.
+    Io(io::Error),
+    /// A second extended buffer with the same ID was attached to a parameter set.
+    DuplicateExtBuffer(FourCC),
+    /// `Query` changed requested encoder parameters and the configuration is strict.
+    ParamsAdjusted(Vec<Adjustment>),
+    /// Surfaces cannot be allocated in system memory for this color format.
+    UnsupportedFourCC(FourCC),
+    /// A payload does not fit in mfxPayload, whose BufSize is 16 bits; holds its size in bytes.
+    PayloadTooLarge(usize),
+    /// The bitrate control parameters are out of range, found before `Query`.
+    RateControl(InvalidRateControl),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    /// The SDK status, if the error came from an SDK function.
+    pub fn status(&self) -> Option<MfxStatus> {
+        match self {
+            Error::Status(status) => Some(*status),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Status(status) => write!(f, "SDK error: {status}"),
+            #[cfg(feature = "hardware")]
+            Error::Library(err) => err.fmt(f),
+            Error::Io(err) => err.fmt(f),
+            Error::DuplicateExtBuffer(id) => write!(f, "extended buffer {id} attached twice"),
+            Error::UnsupportedFourCC(fourcc) => write!(f, "cannot allocate {fourcc} surfaces"),
+            Error::PayloadTooLarge(size) => write!(f, "payload of {size} bytes exceeds the 65535 bytes of mfxPayload"),
+            Error::RateControl(err) => err.fmt(f),
+            Error::ParamsAdjusted(adjustments) => {
+                f.write_str("encoder parameters adjusted: ")?;
+                for (i, adjustment) in adjustments.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    adjustment.fmt(f)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Status(_)
+            | Error::DuplicateExtBuffer(_)
+            | Error::ParamsAdjusted(_)
+            | Error::UnsupportedFourCC(_)
+            | Error::PayloadTooLarge(_) => None,
+            #[cfg(feature = "hardware")]
+            Error::Library(err) => Some(err),
+            Error::Io(err) => Some(err),
+            Error::RateControl(err) => Some(err),
+        }
+    }
+}
+
+impl From<MfxStatus> for Error {
+    fn from(status: MfxStatus) -> Self {
+        Error::Status(status)
+    }
+}
+
+#[cfg(feature = "hardware")]
+impl From<LoadError> for Error {
+    fn from(err: LoadError) -> Self {
+        Error::Library(err)
+    }
+}
+
+impl From<InvalidRateControl> for Error {
+    fn from(err: InvalidRateControl) -> Self {
+        Error::RateControl(err)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(err: Error) -> io::Error {
+        match err {
+            Error::Io(err) => err,
+            #[cfg(feature = "hardware")]
+            Error::Library(err) => err.into(),
+            err => io::Error::other(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn into_io_error() {
+        let err = io::Error::from(Error::Status(MfxStatus::DeviceLost));
+        let inner = err.get_ref().unwrap().downcast_ref::<Error>().unwrap();
+        assert_eq!(inner.status(), Some(MfxStatus::DeviceLost));
+
+        let err = io::Error::from(Error::Io(io::Error::from(io::ErrorKind::UnexpectedEof)));
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
@@ -0,0 +1,387 @@
+//! Extended buffers attached to mfxVideoParam through ExtParam.
+//!
+//! ExtParam is an array of pointers to structures starting with an mfxExtBuffer header. [`ExtBuffers`] owns both the buffers and the pointer array, so the pointers stay valid for as long as it is alive, and rejects a second buffer with the same ID, which the SDK does not allow. [`VideoParam`] bundles it with an mfxVideoParam and points ExtParam at the buffers right before the parameters are handed to the SDK.
+use std::any::Any;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::ptr;
+
+use crate::constants::*;
+use crate::enums::FourCC;
+use crate::error::{Error, Result};
+use crate::structures::*;
+
+/// An extended buffer structure that can be attached to ExtParam.
+///
+/// # Safety
+///
+/// The type must be `#[repr(C)]`, start with an mfxExtBuffer header and accept any bit pattern the SDK writes into it. Its `new()` must set Header.BufferId to `BUFFER_ID` and Header.BufferSz to its size; [`ExtBuffers::push`] enforces both anyway.
+pub unsafe trait ExtBuffer: Any + Clone {
+    /// The Header.BufferId of the structure, one of the MFX_EXTBUFF_* values.
+    const BUFFER_ID: mfxU32;
+
+    /// The scalar configuration fields by name, used to report the values `Query` changed. Empty for buffers that only carry data.
+    fn fields(&self) -> Vec<(&'static str, i64)> {
+        Vec::new()
+    }
+}
+
+macro_rules! fields {
+    ($buffer:expr; $($field:ident),* $(,)?) => {
+        vec![$((stringify!($field), $buffer.$field as i64)),*]
+    };
+}
+
+unsafe impl ExtBuffer for mfxExtCodingOption {
+    const BUFFER_ID: mfxU32 = MFX_EXTBUFF_CODING_OPTION;
+
+    fn fields(&self) -> Vec<(&'static str, i64)> {
+        fields![self;
+            RateDistortionOpt, FramePicture, CAVLC, RecoveryPointSEI, ViewOutput, NalHrdConformance,
+            SingleSeiNalUnit, VuiVclHrdParameters, RefPicListReordering, ResetRefList, RefPicMarkRep, FieldOutput,
+            MaxDecFrameBuffering, AUDelimiter, PicTimingSEI, VuiNalHrdParameters,
+        ]
+    }
+}
+
+unsafe impl ExtBuffer for mfxExtCodingOption2 {
+    const BUFFER_ID: mfxU32 = MFX_EXTBUFF_CODING_OPTION2;
+
+    fn fields(&self) -> Vec<(&'static str, i64)> {
+        fields![self;
+            IntRefType, IntRefCycleSize, IntRefQPDelta, MaxFrameSize, MaxSliceSize, BitrateLimit, MBBRC, ExtBRC,
+            LookAheadDepth, Trellis, RepeatPPS, BRefType, AdaptiveI, AdaptiveB, LookAheadDS, NumMbPerSlice, SkipFrame,
+            MinQPI, MaxQPI, MinQPP, MaxQPP, MinQPB, MaxQPB, FixedFrameRate, DisableDeblockingIdc, DisableVUI,
+            BufferingPeriodSEI, EnableMAD, UseRawRef,
+        ]
+    }
+}
+
+unsafe impl ExtBuffer for mfxExtCodingOption3 {
+    const BUFFER_ID: mfxU32 = MFX_EXTBUFF_CODING_OPTION3;
+
+    fn fields(&self) -> Vec<(&'static str, i64)> {
+        fields![self;
+            NumSliceI, NumSliceP, NumSliceB, WinBRCMaxAvgKbps, WinBRCSize, QVBRQuality, EnableMBQP, IntRefCycleDist,
+            DirectBiasAdjustment, GlobalMotionBiasAdjustment, MVCostScalingFactor, MBDisableSkipMap, WeightedPred,
+            WeightedBiPred, AspectRatioInfoPresent, OverscanInfoPresent, OverscanAppropriate, TimingInfoPresent,
+            BitstreamRestriction, LowDelayHrd, MotionVectorsOverPicBoundaries, ScenarioInfo, ContentInfo, PRefType,
+            FadeDetection, GPB, MaxFrameSizeI, MaxFrameSizeP, EnableQPOffset, TransformSkip, TargetChromaFormatPlus1,
+            TargetBitDepthLuma, TargetBitDepthChroma, BRCPanicMode, LowDelayBRC, EnableMBForceIntra,
+            AdaptiveMaxFrameSize, RepartitionCheckEnable, EncodedUnitsInfo, EnableNalUnitType, ExtBrcAdaptiveLTR,
+        ]
+    }
+}
+
+unsafe impl ExtBuffer for mfxExtCodingOptionSPSPPS {
+    const BUFFER_ID: mfxU32 = MFX_EXTBUFF_CODING_OPTION_SPSPPS;
+}
+
+unsafe impl ExtBuffer for mfxExtEncoderResetOption {
+    const BUFFER_ID: mfxU32 = MFX_EXTBUFF_ENCODER_RESET_OPTION;
+}
+
+unsafe impl ExtBuffer for mfxExtAVCEncodedFrameInfo {
+    const BUFFER_ID: mfxU32 = MFX_EXTBUFF_ENCODED_FRAME_INFO;
+}
+
+unsafe impl ExtBuffer for mfxExtVideoSignalInfo {
+    const BUFFER_ID: mfxU32 = MFX_EXTBUFF_VIDEO_SIGNAL_INFO;
+
+    fn fields(&self) -> Vec<(&'static str, i64)> {
+        fields![self;
+            VideoFormat, VideoFullRange, ColourDescriptionPresent, ColourPrimaries, TransferCharacteristics,
+            MatrixCoefficients,
+        ]
+    }
+}
+
+/// Object-safe part of [`ExtBuffer`], so buffers of different types can be stored together.
+trait AnyExtBuffer {
+    fn buffer_id(&self) -> mfxU32;
+    fn fields(&self) -> Vec<(&'static str, i64)>;
+    fn header_ptr(&mut self) -> *mut mfxExtBuffer;
+    fn clone_box(&self) -> Box<dyn AnyExtBuffer>;
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<T: ExtBuffer> AnyExtBuffer for T {
+    fn buffer_id(&self) -> mfxU32 {
+        T::BUFFER_ID
+    }
+
+    fn fields(&self) -> Vec<(&'static str, i64)> {
+        ExtBuffer::fields(self)
+    }
+
+    fn header_ptr(&mut self) -> *mut mfxExtBuffer {
+        self as *mut T as *mut mfxExtBuffer
+    }
+
+    fn clone_box(&self) -> Box<dyn AnyExtBuffer> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// A set of extended buffers with at most one buffer per ID, kept in the order they were added.
+///
+/// Every buffer is boxed, so its address does not change when more buffers are added.
+#[derive(Default)]
+pub struct ExtBuffers {
+    buffers: Vec<Box<dyn AnyExtBuffer>>,
+    pointers: Vec<*mut mfxExtBuffer>,
+}
+
+impl ExtBuffers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `buffer`, failing with [`Error::DuplicateExtBuffer`] if a buffer with the same ID is already attached.
+    pub fn push<T: ExtBuffer>(&mut self, buffer: T) -> Result<&mut T> {
+        if self.contains_id(T::BUFFER_ID) {
+            return Err(Error::DuplicateExtBuffer(FourCC(T::BUFFER_ID)));
+        }
+        let mut buffer = Box::new(buffer);
+        // The header is the first field, see the safety contract of ExtBuffer.
+        let header = unsafe { &mut *buffer.header_ptr() };
+        *header = mfxExtBuffer::header::<T>(T::BUFFER_ID);
+        self.buffers.push(buffer);
+        Ok(self.get_mut::<T>().expect("buffer just added"))
+    }
+
+    /// The attached buffer of type `T`, adding a default one if there is none.
+    pub fn get_or_default<T: ExtBuffer + Default>(&mut self) -> &mut T {
+        if !self.contains_id(T::BUFFER_ID) {
+            return self.push(T::default()).expect("no buffer with this ID");
+        }
+        self.get_mut::<T>().expect("buffer ID used by another type")
+    }
+
+    pub fn get<T: ExtBuffer>(&self) -> Option<&T> {
+        self.buffers
+            .iter()
+            .find_map(|buffer| buffer.as_any().downcast_ref::<T>())
+    }
+
+    pub fn get_mut<T: ExtBuffer>(&mut self) -> Option<&mut T> {
+        self.buffers
+            .iter_mut()
+            .find_map(|buffer| buffer.as_any_mut().downcast_mut::<T>())
+    }
+
+    /// Detaches and returns the buffer of type `T`.
+    pub fn remove<T: ExtBuffer>(&mut self) -> Option<T> {
+        let index = self.buffers.iter().position(|buffer| buffer.as_any().is::<T>())?;
+        let buffer = self.buffers.remove(index).into_any().downcast::<T>().ok()?;
+        Some(*buffer)
+    }
+
+    pub fn contains_id(&self, buffer_id: mfxU32) -> bool {
+        self.buffers.iter().any(|buffer| buffer.buffer_id() == buffer_id)
+    }
+
+    /// The IDs of the attached buffers, in ExtParam order.
+    pub fn ids(&self) -> impl Iterator<Item = FourCC> + '_ {
+        self.buffers.iter().map(|buffer| FourCC(buffer.buffer_id()))
+    }
+
+    /// The [`ExtBuffer::fields`] of every attached buffer, with the buffer ID.
+    pub fn fields(&self) -> Vec<(FourCC, Vec<(&'static str, i64)>)> {
+        self.buffers
+            .iter()
+            .map(|buffer| (FourCC(buffer.buffer_id()), buffer.fields()))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.buffers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffers.is_empty()
+    }
+
+    /// Points ExtParam and NumExtParam of `param` at the attached buffers.
+    ///
+    /// The pointers stay valid until the buffers are next modified or dropped, so call this again right before every SDK call taking `param`.
+    pub fn attach_to(&mut self, param: &mut mfxVideoParam) {
+        self.pointers.clear();
+        self.pointers
+            .extend(self.buffers.iter_mut().map(|buffer| buffer.header_ptr()));
+        param.NumExtParam = self.pointers.len() as mfxU16;
+        param.ExtParam = if self.pointers.is_empty() {
+            ptr::null_mut()
+        } else {
+            self.pointers.as_mut_ptr()
+        };
+    }
+}
+
+impl Clone for ExtBuffers {
+    fn clone(&self) -> Self {
+        ExtBuffers {
+            buffers: self.buffers.iter().map(|buffer| buffer.clone_box()).collect(),
+            pointers: Vec::new(),
+        }
+    }
+}
+
+impl fmt::Debug for ExtBuffers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.ids().map(|id| id.to_string())).finish()
+    }
+}
+
+/// An mfxVideoParam owning its extended buffers.
+///
+/// Dereferences to the mfxVideoParam; ExtParam and NumExtParam are only filled in by [`VideoParam::as_mut_ptr`].
+#[derive(Default, Clone)]
+pub struct VideoParam {
+    param: mfxVideoParam,
+    ext_buffers: ExtBuffers,
+}
+
+impl VideoParam {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ext_buffers(&self) -> &ExtBuffers {
+        &self.ext_buffers
+    }
+
+    pub fn ext_buffers_mut(&mut self) -> &mut ExtBuffers {
+        &mut self.ext_buffers
+    }
+
+    /// Shorthand for `ext_buffers_mut().push(buffer)`.
+    pub fn add_ext_buffer<T: ExtBuffer>(&mut self, buffer: T) -> Result<&mut T> {
+        self.ext_buffers.push(buffer)
+    }
+
+    pub fn ext_buffer<T: ExtBuffer>(&self) -> Option<&T> {
+        self.ext_buffers.get()
+    }
+
+    pub fn ext_buffer_mut<T: ExtBuffer>(&mut self) -> Option<&mut T> {
+        self.ext_buffers.get_mut()
+    }
+
+    /// The parameters to pass to the SDK, with ExtParam pointing at the attached buffers.
+    ///
+    /// The pointer is valid until `self` is moved, modified or dropped.
+    pub fn as_mut_ptr(&mut self) -> *mut mfxVideoParam {
+        self.ext_buffers.attach_to(&mut self.param);
+        &mut self.param
+    }
+}
+
+impl From<mfxVideoParam> for VideoParam {
+    /// Takes the parameters without the extended buffers, which are not owned by `param`.
+    fn from(mut param: mfxVideoParam) -> Self {
+        param.ExtParam = ptr::null_mut();
+        param.NumExtParam = 0;
+        VideoParam {
+            param,
+            ext_buffers: ExtBuffers::new(),
+        }
+    }
+}
+
+impl Deref for VideoParam {
+    type Target = mfxVideoParam;
+
+    fn deref(&self) -> &mfxVideoParam {
+        &self.param
+    }
+}
+
+impl DerefMut for VideoParam {
+    fn deref_mut(&mut self) -> &mut mfxVideoParam {
+        &mut self.param
+    }
+}
+
+impl fmt::Debug for VideoParam {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VideoParam")
+            .field("ext_buffers", &self.ext_buffers)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::mem::size_of;
+    use std::slice;
+
+    use super::*;
+    use crate::enums::CodingOption;
+
+    #[test]
+    fn attaches_buffers_in_order() {
+        let mut par = VideoParam::new();
+        par.add_ext_buffer(mfxExtCodingOption2::new()).unwrap().MBBRC = CodingOption::On.raw();
+        par.add_ext_buffer(mfxExtVideoSignalInfo::new()).unwrap();
+        par.ext_buffers_mut().get_or_default::<mfxExtCodingOption>().AUDelimiter = CodingOption::Off.raw();
+
+        let raw = unsafe { &*par.as_mut_ptr() };
+        assert_eq!(raw.NumExtParam, 3);
+        let headers = unsafe { slice::from_raw_parts(raw.ExtParam, raw.NumExtParam as usize) };
+        let ids: Vec<_> = headers.iter().map(|&header| unsafe { (*header).BufferId }).collect();
+        assert_eq!(
+            ids,
+            [
+                MFX_EXTBUFF_CODING_OPTION2,
+                MFX_EXTBUFF_VIDEO_SIGNAL_INFO,
+                MFX_EXTBUFF_CODING_OPTION
+            ]
+        );
+        assert_eq!(
+            unsafe { (*headers[0]).BufferSz } as usize,
+            size_of::<mfxExtCodingOption2>()
+        );
+        let option2 = unsafe { &*(headers[0] as *const mfxExtCodingOption2) };
+        assert_eq!(option2.MBBRC, MFX_CODINGOPTION_ON);
+
+        assert!(par.ext_buffers_mut().remove::<mfxExtVideoSignalInfo>().is_some());
+        let raw = unsafe { &*par.as_mut_ptr() };
+        assert_eq!(raw.NumExtParam, 2);
+        assert_eq!(format!("{:?}", par.ext_buffers()), r#"["CDO2", "CDOP"]"#);
+    }
+
+    #[test]
+    fn rejects_duplicate_ids() {
+        let mut buffers = ExtBuffers::new();
+        let mut option = mfxExtCodingOption3::new();
+        option.Header.BufferSz = 0;
+        buffers.push(option).unwrap();
+        assert_eq!(
+            buffers.get::<mfxExtCodingOption3>().unwrap().Header.BufferSz as usize,
+            size_of::<mfxExtCodingOption3>()
+        );
+
+        let err = buffers.push(mfxExtCodingOption3::new()).unwrap_err();
+        assert_eq!(err.to_string(), "extended buffer CDO3 attached twice");
+        assert_eq!(buffers.len(), 1);
+
+        let mut copy = buffers.clone();
+        copy.get_mut::<mfxExtCodingOption3>().unwrap().QVBRQuality = 23;
+        assert_eq!(buffers.get::<mfxExtCodingOption3>().unwrap().QVBRQuality, 0);
+    }
+}
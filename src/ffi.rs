@@ -0,0 +1,413 @@
+use crate::constants::*;
+use crate::structures::*;
+
+/// Entry points of the SDK library, resolved at runtime by [`crate::loader::Library`].
+///
+/// <https://github.com/Intel-Media-SDK/MediaSDK#media-sdk-support-matrix>
+pub struct Functions {
+    /// This function creates and initializes an SDK session. Call this function before calling any other SDK functions. If the desired implementation specified by impl is MFX_IMPL_AUTO, the function will search for the platform-specific SDK implementation. If the function cannot find it, it will use the software implementation.
+    ///
+    /// The argument ver indicates the desired version of the library implementation. The loaded SDK will have an API version compatible to the specified version (equal in the major version number, and no less in the minor version number.) If the desired version is not specified, the default is to use the API version from the SDK release, with which an application is built.
+    ///
+    /// We recommend that production applications always specify the minimum API version that meets their functional requirements. For example, if an application uses only H.264 decoding as described in API v1.0, have the application initialize the library with API v1.0. This ensures backward compatibility.
+    ///
+    /// # Arguments
+    /// * `implementation` - mfxIMPL enumerator that indicates the desired SDK implementation
+    /// * `ver` - Pointer to the minimum library version or zero, if not specified
+    /// * `session` - Pointer to the SDK session handle
+    ///
+    /// # Returns
+    /// * `MFX_ERR_NONE` - The function completed successfully. The output parameter contains the handle of the session.
+    /// * `MFX_ERR_UNSUPPORTED` - The function cannot find the desired SDK implementation or version.
+    pub MFXInit: unsafe extern "C" fn(
+        implementation: mfxIMPL,
+        ver: *const mfxVersion,
+        session: *mut *mut mfxSession,
+    ) -> mfxStatus,
+
+    /// This function returns the implementation type of a given session.
+    /// # Arguments
+    /// * `session` - SDK session handle
+    /// * `implementation` - Pointer to the implementation type
+    ///
+    /// # Returns
+    /// * `MFX_ERR_NONE` - The function completed successfully. The output parameter contains the handle of the session.
+    ///
+    /// # Since
+    /// SDK API 1.0
+    pub MFXQueryIMPL: unsafe extern "C" fn(session: *const mfxSession, implementation: *mut mfxIMPL) -> mfxStatus,
+
+    /// This function completes and de-initializes an SDK session. Any active tasks in execution or in queue are aborted. The application cannot call any SDK function after this function.
+    ///
+    /// All child sessions must be disjoined before closing a parent session.
+    /// # Arguments
+    /// * `session` - SDK session handle
+    ///
+    /// # Returns
+    /// * `MFX_ERR_NONE` - The function completed successfully.
+    ///
+    /// # Since
+    /// SDK API 1.0
+    pub MFXClose: unsafe extern "C" fn(session: *const mfxSession) -> mfxStatus,
+
+    /// This function works in either of four modes:
+
+    /// If the in pointer is zero, the function returns the class configurability in the output structure. A non-zero value in each field of the output structure indicates that the SDK implementation can configure the field with Init.
+    ///
+    /// If the in parameter is non-zero, the function checks the validity of the fields in the input structure. Then the function returns the corrected values in the output structure. If there is insufficient information to determine the validity or correction is impossible, the function zeroes the fields. This feature can verify whether the SDK implementation supports certain profiles, levels or bitrates.
+    ///
+    /// If the in parameter is non-zero and mfxExtEncoderResetOption structure is attached to it, then the function queries for the outcome of the MFXVideoENCODE_Reset function and returns it in the mfxExtEncoderResetOption structure attached to out. The query function succeeds if such reset is possible and returns error otherwise. Unlike other modes that are independent of the SDK encoder state, this one checks if reset is possible in the present SDK encoder state. This mode also requires completely defined mfxVideoParam structure, unlike other modes that support partially defined configurations. See mfxExtEncoderResetOption description for more details.
+    ///
+    /// If the in parameter is non-zero and mfxExtEncoderCapability structure is attached to it, then the function returns encoder capability in mfxExtEncoderCapability structure attached to out. It is recommended to fill in mfxVideoParam structure and set hardware acceleration device handle before calling the function in this mode.
+    ///
+    /// The application can call this function before or after it initializes the encoder. The CodecId field of the output structure is a mandated field (to be filled by the application) to identify the coding standard.
+    /// # Arguments
+    /// * `session` - SDK session handle
+    /// * `in` - Pointer to the mfxVideoParam structure as input
+    /// * `out` - Pointer to the mfxVideoParam structure as output
+    ///
+    /// # Returns
+    /// * `MFX_ERR_NONE` - The function completed successfully.
+    /// * `MFX_ERR_UNSUPPORTED` - The function failed to identify a specific implementation for the required features.
+    /// * `MFX_WRN_PARTIAL_ACCELERATION` - The underlying hardware does not fully support the specified video parameters; The encoding may be partially accelerated. Only SDK HW implementations may return this status code.
+    /// * `MFX_WRN_INCOMPATIBLE_VIDEO_PARAM` - The function detected some video parameters were incompatible with others; incompatibility resolved.
+    ///
+    /// # Since
+    /// SDK API 1.0
+    pub MFXVideoENCODE_Query: unsafe extern "C" fn(
+        session: *const mfxSession,
+        input: *const mfxVideoParam,
+        output: *mut mfxVideoParam,
+    ) -> mfxStatus,
+
+    /// This function returns minimum and suggested numbers of the input frame surfaces required for encoding initialization and their type. Init will call the external allocator for the required frames with the same set of numbers.
+    ///
+    /// The use of this function is recommended. For more information, see the section Working with hardware acceleration.
+    ///
+    /// This function does not validate I/O parameters except those used in calculating the number of input surfaces.
+    /// # Arguments
+    /// * `session` - SDK session handle
+    /// * `par` - Pointer to the mfxVideoParam structure as input
+    /// * `request` - Pointer to the mfxFrameAllocRequest structure as output
+    ///
+    /// # Returns
+    /// * `MFX_ERR_NONE` - The function completed successfully.
+    /// * `MFX_WRN_PARTIAL_ACCELERATION` - The underlying hardware does not fully support the specified video parameters; The encoding may be partially accelerated. Only SDK HW implementations may return this status code.
+    /// * `MFX_ERR_INVALID_VIDEO_PARAM` - The function detected invalid video parameters. These parameters may be out of the valid range, or the combination of them resulted in incompatibility. Incompatibility not resolved.
+    /// * `MFX_WRN_INCOMPATIBLE_VIDEO_PARAM` - The function detected some video parameters were incompatible with others; incompatibility resolved.
+    pub MFXVideoENCODE_QueryIOSurf: unsafe extern "C" fn(
+        session: *const mfxSession,
+        par: *const mfxVideoParam,
+        request: *mut mfxFrameAllocRequest,
+    ) -> mfxStatus,
+
+    /// This function allocates memory and prepares tables and necessary structures for encoding. This function also does extensive validation to ensure if the configuration, as specified in the input parameters, is supported.
+    /// # Arguments
+    /// * `session` - SDK session handle
+    /// * `par` - Pointer to the mfxVideoParam structure
+    ///
+    /// # Returns
+    /// * `MFX_ERR_NONE` - The function completed successfully.
+    /// * `MFX_WRN_PARTIAL_ACCELERATION` - The underlying hardware does not fully support the specified video parameters; The encoding may be partially accelerated. Only SDK HW implementations may return this status code.
+    /// * `MFX_ERR_INVALID_VIDEO_PARAM` - The function detected invalid video parameters. These parameters may be out of the valid range, or the combination of them resulted in incompatibility. Incompatibility not resolved.
+    /// * `MFX_WRN_INCOMPATIBLE_VIDEO_PARAM` - The function detected some video parameters were incompatible with others; incompatibility resolved.
+    /// * `MFX_ERR_UNDEFINED_BEHAVIOR` - The function is called twice without a close;
+    pub MFXVideoENCODE_Init: unsafe extern "C" fn(session: *const mfxSession, par: *const mfxVideoParam) -> mfxStatus,
+
+    /// This function stops the current encoding operation and restores internal structures or parameters for a new encoding operation, possibly with new parameters.
+    ///
+    /// The application can attach the mfxExtEncoderResetOption structure to control whether the encoder starts a new coded sequence; the outcome can be queried beforehand with MFXVideoENCODE_Query. Frames the encoder still buffers are discarded, so the application should drain it first.
+    /// # Arguments
+    /// * `session` - SDK session handle
+    /// * `par` - Pointer to the mfxVideoParam structure
+    ///
+    /// # Returns
+    /// * `MFX_ERR_NONE` - The function completed successfully.
+    /// * `MFX_ERR_INVALID_VIDEO_PARAM` - The function detected invalid video parameters. These parameters may be out of the valid range, or the combination of them resulted in incompatibility. Incompatibility not resolved.
+    /// * `MFX_ERR_INCOMPATIBLE_VIDEO_PARAM` - The function detected that provided by the application video parameters are incompatible with initialization parameters. Reset requires additional memory allocation and cannot be executed. The application should close the SDK component and then reinitialize it.
+    /// * `MFX_WRN_INCOMPATIBLE_VIDEO_PARAM` - The function detected some video parameters were incompatible with others; incompatibility resolved.
+    ///
+    /// # Since
+    /// SDK API 1.0
+    pub MFXVideoENCODE_Reset: unsafe extern "C" fn(session: *const mfxSession, par: *const mfxVideoParam) -> mfxStatus,
+
+    /// This function retrieves current working parameters to the specified output structure. If extended buffers are to be returned, the application must allocate those extended buffers and attach them as part of the output structure.
+    ///
+    /// The application can retrieve a copy of the bitstream header, by attaching the mfxExtCodingOptionSPSPPS structure to the mfxVideoParam structure.
+    /// # Arguments
+    /// * `session` - SDK session handle
+    /// * `par` - Pointer to the corresponding parameter structure
+    ///
+    /// # Returns
+    /// * `MFX_ERR_NONE` - The function completed successfully.
+    pub MFXVideoENCODE_GetVideoParam: unsafe extern "C" fn(
+        session: *const mfxSession,
+        par: *mut mfxVideoParam,
+    ) -> mfxStatus,
+
+    /// This function takes a single input frame in either encoded or display order and generates its output bitstream. In the case of encoded ordering the mfxEncodeCtrl structure must specify the explicit frame type. In the case of display ordering, this function handles frame order shuffling according to the GOP structure parameters specified during initialization.
+    ///
+    /// Since encoding may process frames differently from the input order, not every call of the function generates output and the function returns MFX_ERR_MORE_DATA. If the encoder needs to cache the frame, the function locks the frame. The application should not alter the frame until the encoder unlocks the frame. If there is output (with return status MFX_ERR_NONE), the return is a frame worth of bitstream.
+    ///
+    /// It is the calling application’s responsibility to ensure that there is sufficient space in the output buffer. The value BufferSizeInKB in the mfxVideoParam structure at encoding initialization specifies the maximum possible size for any compressed frames. This value can also be obtained from MFXVideoENCODE_GetVideoParam after encoding initialization.
+    ///
+    /// To mark the end of the encoding sequence, call this function with a NULL surface pointer. Repeat the call to drain any remaining internally cached bitstreams(one frame at a time) until MFX_ERR_MORE_DATA is returned.
+    ///
+    /// This function is asynchronous.
+    /// # Remarks
+    ///
+    /// If the EncodedOrder field in the mfxInfoMFX structure is true, input frames enter the encoder in the order of their encoding. However, the FrameOrder field in the mfxFrameData structure of each frame must be set to the display order. If EncodedOrder is false, the function ignores the FrameOrder field.
+    /// # Arguments
+    /// * `session` - SDK session handle
+    /// * `ctrl` - Pointer to the mfxEncodeCtrl structure for per-frame encoding control; this parameter is optional(it can be NULL) if the encoder works in the display order mode.
+    /// * `surface` - Pointer to the frame surface structure
+    /// * `bs` - Pointer to the output bitstream
+    /// * `syncp` - Pointer to the returned sync point associated with this operation
+    ///
+    /// # Returns
+    /// * `MFX_ERR_NONE` - The function completed successfully.
+    /// * `MFX_ERR_NOT_ENOUGH_BUFFER` - The bitstream buffer size is insufficient.
+    /// * `MFX_ERR_MORE_DATA` - The function requires more data to generate any output.
+    /// * `MFX_ERR_DEVICE_LOST` - Hardware device was lost; See Working with Microsoft* DirectX* Applications section for further information.
+    /// * `MFX_WRN_DEVICE_BUSY` - Hardware device is currently busy. Call this function again in a few milliseconds.
+    /// * `MFX_ERR_INCOMPATIBLE_VIDEO_PARAM` - Inconsistent parameters detected not conforming to Appendix A.
+    pub MFXVideoENCODE_EncodeFrameAsync: unsafe extern "C" fn(
+        session: *const mfxSession,
+        ctrl: *const mfxEncodeCtrl,
+        surface: *const mfxFrameSurface1,
+        bs: *mut mfxBitstream,
+        syncp: *mut mfxSyncPoint,
+    ) -> mfxStatus,
+
+    /// This function sets the external allocator callback functions for frame allocation.
+    ///
+    /// If the allocator argument is NULL, the function resets the allocator to the default allocator.
+    ///
+    /// The SDK implementation may call the external allocator only when the pipeline is not running, which is before initialization, between any of the Init functions and the first asynchronous call, and after Close. The application must set the allocator before initializing any SDK component that uses it.
+    /// # Arguments
+    /// * `session` - SDK session handle
+    /// * `allocator` - Pointer to the mfxFrameAllocator structure
+    ///
+    /// # Returns
+    /// * `MFX_ERR_NONE` - The function completed successfully.
+    ///
+    /// # Since
+    /// SDK API 1.0
+    pub MFXVideoCORE_SetFrameAllocator:
+        unsafe extern "C" fn(session: *const mfxSession, allocator: *const mfxFrameAllocator) -> mfxStatus,
+
+    /// This function initiates execution of an asynchronous function not already started and returns the status code after the specified asynchronous operation completes. If wait is zero, the function returns immediately.
+    ///
+    /// # Arguments
+    /// * `session` - SDK session handle
+    /// * `syncp` - Sync point
+    /// * `wait` - Wait time in milliseconds
+    ///
+    /// # Returns
+    /// * `MFX_ERR_NONE` - The function completed successfully.
+    /// * `MFX_ERR_NONE_PARTIAL_OUTPUT` - The function completed successfully, bitstream contains a portion of the encoded frame according to required granularity.
+    /// * `MFX_WRN_IN_EXECUTION` - The specified asynchronous function is in execution.
+    /// * `MFX_ERR_ABORTED` - he specified asynchronous function aborted due to data dependency on a previous asynchronous function that did not complete.
+    pub MFXVideoCORE_SyncOperation: unsafe extern "C" fn(
+        session: *const mfxSession,
+        syncp: mfxSyncPoint,
+        wait: mfxU32,
+    ) -> mfxStatus,
+
+    pub MFXVideoENCODE_Close: unsafe extern "C" fn(session: *const mfxSession) -> mfxStatus,
+
+    /// This function works in either of two modes:
+    ///
+    /// If in is zero, the function returns the class configurability in the output structure. A non-zero value in a field indicates that the SDK implementation can configure it with Init.
+    ///
+    /// If in is non-zero, the function checks the validity of the fields in the input structure. Then the function returns the corrected values in the output structure. If there is insufficient information to determine the validity or correction is impossible, the function zeroes the fields.
+    ///
+    /// The application can call this function before or after it initializes the preprocessor.
+    ///
+    /// # Arguments
+    /// * `session` - SDK session handle
+    /// * `in` - Pointer to the mfxVideoParam structure as input
+    /// * `out` - Pointer to the mfxVideoParam structure as output
+    ///
+    /// # Returns
+    /// * `MFX_ERR_NONE` - The function completed successfully.
+    /// * `MFX_WRN_PARTIAL_ACCELERATION` - The underlying hardware does not fully support the specified video parameters; The video processing may be partially accelerated. Only SDK HW implementation may return this status code.
+    /// * `MFX_ERR_INVALID_VIDEO_PARAM` - The function detected invalid video parameters. These parameters may be out of the valid range, or the combination of them resulted in incompatibility. Incompatibility not resolved.
+    /// * `MFX_WRN_INCOMPATIBLE_VIDEO_PARAM` - The function detected some video parameters were incompatible with others; incompatibility resolved.
+    pub MFXVideoVPP_Query: unsafe extern "C" fn(
+        session: *const mfxSession,
+        r#in: *const mfxVideoParam,
+        out: *mut mfxVideoParam,
+    ) -> mfxStatus,
+
+    /// This function returns minimum and suggested numbers of input and output frame surfaces required for video processing initialization and their type. The parameter request[0] refers to the input requirements; request[1] refers to output requirements. Init will call the external allocator for the required frames with the same set of numbers.
+    ///
+    /// The function is recommended. For more information, see the Working with hardware acceleration.
+    ///
+    /// This function does not validate I/O parameters except those used in calculating the number of input and output surfaces.
+    ///
+    /// # Arguments
+    /// * `session` - SDK session handle
+    /// * `par` - Pointer to the mfxVideoParam structure as input
+    /// * `request` - Pointer to the output mfxFrameAllocRequest structure; use request[0] for input requirements and request[1] for output requirements for video processing.
+    ///
+    /// # Returns
+    /// * `MFX_ERR_NONE` - The function completed successfully.
+    /// * `MFX_WRN_PARTIAL_ACCELERATION` - The underlying hardware does not fully support the specified video parameters; The video processing may be partially accelerated. Only SDK HW implementation may return this status code.
+    /// * `MFX_ERR_INVALID_VIDEO_PARAM` - The function detected invalid video parameters. These parameters may be out of the valid range, or the combination of them resulted in incompatibility. Incompatibility not resolved.
+    /// * `MFX_WRN_INCOMPATIBLE_VIDEO_PARAM` - The function detected some video parameters were incompatible with others; incompatibility resolved.
+    pub MFXVideoVPP_QueryIOSurf: unsafe extern "C" fn(
+        session: *const mfxSession,
+        par: *const mfxVideoParam,
+        request: &mut [mfxFrameAllocRequest; 2],
+    ) -> mfxStatus,
+
+    pub MFXVideoVPP_Init: unsafe extern "C" fn(session: *const mfxSession, par: *mut mfxVideoParam) -> mfxStatus,
+
+    /// This function processes a single input frame to a single output frame. Retrieval of the auxiliary data is optional; the encoding process may use it.
+    ///
+    /// The video processing process may not generate an instant output given an input. See section Video Processing Procedures for details on how to correctly send input and retrieve output.
+    ///
+    /// At the end of the stream, call this function with the input argument in=NULL to retrieve any remaining frames, until the function returns MFX_ERR_MORE_DATA.
+    ///
+    /// This function is asynchronous.
+    ///
+    /// # Arguments
+    /// * `session` - SDK session handle
+    /// * `in` - Pointer to the input video surface structure
+    /// * `out` - Pointer to the output video surface structure
+    /// * `aux` - Optional pointer to the auxiliary data structure
+    /// * `syncp` - Pointer to the output sync point
+    ///
+    /// # Returns
+    /// * `MFX_ERR_NONE` - The output frame is ready after synchronization.
+    /// * `MFX_ERR_MORE_DATA` - Need more input frames before VPP can produce an output
+    /// * `MFX_ERR_MORE_SURFACE` - The output frame is ready after synchronization. Need more surfaces at output for additional output frames available.
+    /// * `MFX_ERR_DEVICE_LOST` - Hardware device was lost; See the Working with Microsoft* DirectX* Applications section for further information.
+    /// * `MFX_WRN_DEVICE_BUSY` - Hardware device is currently busy. Call this function again in a few milliseconds.
+    pub MFXVideoVPP_RunFrameVPPAsync: unsafe extern "C" fn(
+        session: *const mfxSession,
+        input: *const mfxFrameSurface1,
+        output: *mut mfxFrameSurface1,
+        aux: *const mfxExtVppAuxData,
+        syncp: *mut mfxSyncPoint,
+    ) -> mfxStatus,
+
+    pub MFXVideoVPP_Close: unsafe extern "C" fn(session: *const mfxSession) -> mfxStatus,
+    /// The oneVPL 2.x dispatcher entry points, `None` if the library is a Media SDK 1.x dispatcher or runtime.
+    pub dispatcher: Option<DispatcherFunctions>,
+}
+
+/// Entry points of the oneVPL dispatcher (`libvpl`), used to enumerate the installed implementations and create a session on one of them.
+///
+/// <https://spec.oneapi.io/versions/latest/elements/oneVPL/source/API_ref/VPL_disp_api_func.html>
+pub struct DispatcherFunctions {
+    /// Creates the loader.
+    ///
+    /// # Returns
+    /// Loader Loader handle or NULL if failed.
+    ///
+    /// # Since
+    /// API 2.0
+    pub MFXLoad: unsafe extern "C" fn() -> mfxLoader,
+
+    /// Destroys the dispatcher.
+    ///
+    /// # Arguments
+    /// * `loader` - Loader handle.
+    ///
+    /// # Since
+    /// API 2.0
+    pub MFXUnload: unsafe extern "C" fn(loader: mfxLoader),
+
+    /// Creates dispatcher configuration. This function creates the dispatcher internal configuration, which is used to filter out available implementations. This configuration is used to walk through selected implementations to gather more details and select the appropriate implementation to load. The loader object remembers all created mfxConfig objects and destroys them during the mfxUnload function call.
+    ///
+    /// Multiple configurations per single mfxLoader object are possible.
+    ///
+    /// # Arguments
+    /// * `loader` - Loader handle.
+    ///
+    /// # Returns
+    /// Config handle or NULL pointer is failed.
+    ///
+    /// # Since
+    /// API 2.0
+    pub MFXCreateConfig: unsafe extern "C" fn(loader: mfxLoader) -> mfxConfig,
+
+    /// Adds additional filter properties (any fields of the mfxImplDescription structure) to the configuration of the loader object. One mfxConfig properties can hold only single filter property.
+    ///
+    /// # Arguments
+    /// * `config` - Config handle.
+    /// * `name` - Name of the parameter (see mfxImplDescription structure and example).
+    /// * `value` - Value of the parameter.
+    ///
+    /// # Returns
+    /// * `MFX_ERR_NONE` - The function completed successfully.
+    /// * `MFX_ERR_NULL_PTR` - If config is NULL. If name is NULL.
+    /// * `MFX_ERR_NOT_FOUND` - If name contains unknown parameter name.
+    /// * `MFX_ERR_UNSUPPORTED` - If value data type does not equal the parameter with provided name.
+    ///
+    /// # Since
+    /// API 2.0
+    pub MFXSetConfigFilterProperty:
+        unsafe extern "C" fn(config: mfxConfig, name: *const mfxU8, value: mfxVariant) -> mfxStatus,
+
+    /// Iterates over filtered out implementations to gather their details. This function allocates memory to store a structure or string corresponding to the type specified by format. For example, if format is set to MFX_IMPLCAPS_IMPLDESCSTRUCTURE, then idesc will return a pointer to a structure of type mfxImplDescription. Use the MFXDispReleaseImplDescription function to free memory allocated to this structure or string.
+    ///
+    /// # Arguments
+    /// * `loader` - Loader handle.
+    /// * `i` - Index of the implementation.
+    /// * `format` - Format in which capabilities need to be delivered. See the mfxImplCapsDeliveryFormat enumerator for more details.
+    /// * `idesc` - Pointer to the structure or string corresponding to the requested format.
+    ///
+    /// # Returns
+    /// * `MFX_ERR_NONE` - The function completed successfully. The idesc contains valid information.
+    /// * `MFX_ERR_NULL_PTR` - If loader is NULL. If idesc is NULL.
+    /// * `MFX_ERR_NOT_FOUND` - Provided index is out of possible range.
+    /// * `MFX_ERR_UNSUPPORTED` - If requested format is not supported.
+    ///
+    /// # Since
+    /// API 2.0
+    pub MFXEnumImplementations: unsafe extern "C" fn(
+        loader: mfxLoader,
+        i: mfxU32,
+        format: mfxImplCapsDeliveryFormat,
+        idesc: *mut mfxHDL,
+    ) -> mfxStatus,
+
+    /// Loads and initializes the implementation.
+    ///
+    /// # Arguments
+    /// * `loader` - Loader handle.
+    /// * `i` - Index of the implementation.
+    /// * `session` - Pointer to the SDK session handle.
+    ///
+    /// # Returns
+    /// * `MFX_ERR_NONE` - The function completed successfully. The session contains a pointer to the SDK session handle.
+    /// * `MFX_ERR_NULL_PTR` - If loader is NULL. If session is NULL.
+    /// * `MFX_ERR_NOT_FOUND` - Provided index is out of possible range.
+    ///
+    /// # Since
+    /// API 2.0
+    pub MFXCreateSession: unsafe extern "C" fn(
+        loader: mfxLoader,
+        i: mfxU32,
+        session: *mut *mut mfxSession,
+    ) -> mfxStatus,
+
+    /// Destroys the handle allocated by the MFXEnumImplementations function.
+    ///
+    /// # Arguments
+    /// * `loader` - Loader handle.
+    /// * `hdl` - Handle to destroy. Can be equal to NULL.
+    ///
+    /// # Returns
+    /// * `MFX_ERR_NONE` - The function completed successfully.
+    /// * `MFX_ERR_NULL_PTR` - If loader is NULL.
+    /// * `MFX_ERR_INVALID_HANDLE` - Provided hdl handle is not associated with this loader.
+    ///
+    /// # Since
+    /// API 2.0
+    pub MFXDispReleaseImplDescription:
+        unsafe extern "C" fn(loader: mfxLoader, hdl: mfxHDL) -> mfxStatus,
+}
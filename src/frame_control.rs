@@ -0,0 +1,322 @@
+//! Per-frame encoding control.
+//!
+//! [`FrameControl`] builds the mfxEncodeCtrl passed with a surface to `MFXVideoENCODE_EncodeFrameAsync`, to force a keyframe, override the QP, skip a frame or insert [`SeiMessage`]s. [`KeyFrames`] lists the frames that have to be keyframes, by frame number or by time, as given on the command line.
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::constants::*;
+use crate::enums::FrameType;
+use crate::error::Error;
+use crate::structures::*;
+
+/// SEI payload type of user_data_registered_itu_t_t35(), ISO/IEC 14496-10 Annex D.
+pub const SEI_USER_DATA_REGISTERED_ITU_T_T35: mfxU16 = 4;
+/// SEI payload type of user_data_unregistered().
+pub const SEI_USER_DATA_UNREGISTERED: mfxU16 = 5;
+
+/// An H.264 SEI message: the sei_message() syntax of ISO/IEC 14496-10 7.3.2.3.1, i.e. the payload type and size, coded
+/// as runs of 0xFF bytes, followed by the payload. The encoder wraps it in a SEI NAL unit and adds the emulation
+/// prevention bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeiMessage {
+    payload_type: mfxU16,
+    bytes: Vec<u8>,
+}
+
+impl SeiMessage {
+    /// A message of `payload_type` carrying `payload`. Fails if the framed message exceeds the 16-bit BufSize of mfxPayload.
+    pub fn new(payload_type: mfxU16, payload: &[u8]) -> crate::Result<Self> {
+        let mut bytes = Vec::with_capacity(payload.len() + 4);
+        push_ff_coded(&mut bytes, payload_type as usize);
+        push_ff_coded(&mut bytes, payload.len());
+        bytes.extend_from_slice(payload);
+        if bytes.len() > mfxU16::MAX as usize {
+            return Err(Error::PayloadTooLarge(bytes.len()));
+        }
+        Ok(SeiMessage { payload_type, bytes })
+    }
+
+    /// user_data_unregistered(): `data` in a format identified by `uuid`, the uuid_iso_iec_11578 field.
+    pub fn user_data_unregistered(uuid: [u8; 16], data: &[u8]) -> crate::Result<Self> {
+        SeiMessage::new(SEI_USER_DATA_UNREGISTERED, &[&uuid[..], data].concat())
+    }
+
+    /// user_data_registered_itu_t_t35(): `data` as registered with ITU-T T.35, usually starting with the terminal
+    /// provider code. For `country_code` 0xFF, `data` starts with the country code extension byte.
+    pub fn itu_t_t35(country_code: u8, data: &[u8]) -> crate::Result<Self> {
+        SeiMessage::new(SEI_USER_DATA_REGISTERED_ITU_T_T35, &[&[country_code][..], data].concat())
+    }
+
+    pub fn payload_type(&self) -> mfxU16 {
+        self.payload_type
+    }
+
+    /// The sei_message() bytes handed to the encoder.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+/// Appends `value` as a run of 0xFF bytes and a last byte below 0xFF, like payloadType and payloadSize.
+fn push_ff_coded(bytes: &mut Vec<u8>, mut value: usize) {
+    while value >= 0xff {
+        bytes.push(0xff);
+        value -= 0xff;
+    }
+    bytes.push(value as u8);
+}
+
+/// Encoding controls for one frame, see mfxEncodeCtrl.
+///
+/// The SDK may read the structure until the frame is encoded, so it has to stay alive until the frame's sync point completes.
+pub struct FrameControl {
+    ctrl: mfxEncodeCtrl,
+    sei: Vec<SeiMessage>,
+    /// One mfxPayload per SEI message, pointing into its bytes, and the array ctrl.Payload points to.
+    payloads: Vec<mfxPayload>,
+    payload_ptrs: Vec<*const mfxPayload>,
+}
+
+impl Default for FrameControl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameControl {
+    /// No controls: the encoder decides everything, like passing NULL.
+    pub fn new() -> Self {
+        FrameControl {
+            ctrl: mfxEncodeCtrl::new(),
+            sei: Vec::new(),
+            payloads: Vec::new(),
+            payload_ptrs: Vec::new(),
+        }
+    }
+
+    /// Shorthand for `FrameControl::new().force_key_frame()`.
+    pub fn key_frame() -> Self {
+        FrameControl::new().force_key_frame()
+    }
+
+    /// Encodes the frame as an IDR frame, so a decoder can start from it, e.g. when a viewer joins or reports picture loss (PLI). In display order this is the only frame type the encoder accepts.
+    pub fn force_key_frame(mut self) -> Self {
+        self.ctrl.set_frame_type(FrameType::I | FrameType::REF | FrameType::IDR);
+        self
+    }
+
+    /// Encodes the frame with `qp` instead of the QP of the encoder parameters. Only used with [`crate::enums::RateControlMethod::Cqp`].
+    pub fn qp(mut self, qp: u16) -> Self {
+        self.ctrl.QP = qp;
+        self
+    }
+
+    /// Skips the frame, as configured by mfxExtCodingOption2::SkipFrame at initialization; see [`crate::enums::SkipFrame`].
+    pub fn skip(mut self) -> Self {
+        self.ctrl.SkipFrame = 1;
+        self
+    }
+
+    /// Inserts `message` before the first slice of the frame. Messages are written in the order they were added.
+    pub fn sei(mut self, message: SeiMessage) -> Self {
+        self.sei.push(message);
+        self.payloads = self
+            .sei
+            .iter()
+            .map(|message| {
+                let mut payload = mfxPayload::new();
+                payload.Type = message.payload_type;
+                payload.Data = message.bytes.as_ptr();
+                payload.BufSize = message.bytes.len() as mfxU16;
+                payload.NumBit = 8 * message.bytes.len() as mfxU32;
+                payload
+            })
+            .collect();
+        self.payload_ptrs = self.payloads.iter().map(|payload| payload as *const mfxPayload).collect();
+        self.ctrl.NumPayload = self.payload_ptrs.len() as mfxU16;
+        self.ctrl.Payload = self.payload_ptrs.as_ptr();
+        self
+    }
+
+    pub fn sei_messages(&self) -> &[SeiMessage] {
+        &self.sei
+    }
+
+    pub fn is_key_frame(&self) -> bool {
+        self.ctrl.frame_type().contains(FrameType::IDR)
+    }
+
+    pub fn as_raw(&self) -> &mfxEncodeCtrl {
+        &self.ctrl
+    }
+
+    /// Pointer to pass to `MFXVideoENCODE_EncodeFrameAsync`.
+    pub fn as_ptr(&self) -> *const mfxEncodeCtrl {
+        &self.ctrl
+    }
+}
+
+/// Where a forced keyframe goes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyFramePosition {
+    /// Frame number, counting the frames given to the encoder from 0.
+    Frame(u32),
+    /// Time from the start of the stream in seconds, rounded to the nearest frame.
+    Seconds(f64),
+}
+
+/// Frames the encoder must encode as keyframes, in addition to the ones the GOP structure places.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KeyFrames {
+    pub positions: Vec<KeyFramePosition>,
+}
+
+impl KeyFrames {
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// The sorted frame numbers of the keyframes at a frame rate of `frame_rate_n / frame_rate_d`.
+    pub fn frame_numbers(&self, frame_rate_n: u32, frame_rate_d: u32) -> Vec<u32> {
+        let fps = frame_rate_n as f64 / frame_rate_d.max(1) as f64;
+        let mut frames: Vec<u32> = self
+            .positions
+            .iter()
+            .map(|position| match *position {
+                KeyFramePosition::Frame(frame) => frame,
+                KeyFramePosition::Seconds(seconds) => (seconds * fps).round() as u32,
+            })
+            .collect();
+        frames.sort_unstable();
+        frames.dedup();
+        frames
+    }
+}
+
+/// Error returned when parsing [`KeyFrames`] fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidKeyFrame(pub String);
+
+impl fmt::Display for InvalidKeyFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid keyframe position {:?}, expected a frame number or seconds like 2.5s", self.0)
+    }
+}
+
+impl error::Error for InvalidKeyFrame {}
+
+impl FromStr for KeyFramePosition {
+    type Err = InvalidKeyFrame;
+
+    /// A frame number such as `300`, or a time in seconds such as `12.5s`.
+    fn from_str(s: &str) -> Result<Self, InvalidKeyFrame> {
+        let invalid = || InvalidKeyFrame(s.to_string());
+        match s.strip_suffix('s') {
+            Some(seconds) => {
+                let seconds: f64 = seconds.parse().map_err(|_| invalid())?;
+                if !seconds.is_finite() || seconds < 0.0 {
+                    return Err(invalid());
+                }
+                Ok(KeyFramePosition::Seconds(seconds))
+            }
+            None => s.parse().map(KeyFramePosition::Frame).map_err(|_| invalid()),
+        }
+    }
+}
+
+impl FromStr for KeyFrames {
+    type Err = InvalidKeyFrame;
+
+    /// A comma-separated list of positions, e.g. `0,300,12.5s`.
+    fn from_str(s: &str) -> Result<Self, InvalidKeyFrame> {
+        let positions = s
+            .split(',')
+            .map(str::trim)
+            .filter(|position| !position.is_empty())
+            .map(str::parse)
+            .collect::<Result<_, _>>()?;
+        Ok(KeyFrames { positions })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::slice;
+
+    #[test]
+    fn builds_encode_ctrl() {
+        let ctrl = FrameControl::new();
+        assert!(!ctrl.is_key_frame());
+        assert_eq!(ctrl.as_raw().FrameType, MFX_FRAMETYPE_UNKNOWN);
+
+        let ctrl = FrameControl::key_frame().qp(30).skip();
+        assert!(ctrl.is_key_frame());
+        let raw = ctrl.as_raw();
+        assert_eq!(raw.FrameType, MFX_FRAMETYPE_I | MFX_FRAMETYPE_REF | MFX_FRAMETYPE_IDR);
+        assert_eq!(raw.QP, 30);
+        assert_eq!(raw.SkipFrame, 1);
+        assert_eq!(raw.NumPayload, 0);
+    }
+
+    #[test]
+    fn frames_sei_messages() {
+        let uuid = *b"0123456789abcdef";
+        let message = SeiMessage::user_data_unregistered(uuid, b"cam-7").unwrap();
+        assert_eq!(message.payload_type(), SEI_USER_DATA_UNREGISTERED);
+        assert_eq!(message.as_bytes(), [&[5, 21][..], &uuid, b"cam-7"].concat());
+
+        let message = SeiMessage::itu_t_t35(0xb5, &[0x00, 0x31, b'G', b'A', b'9', b'4']).unwrap();
+        assert_eq!(message.as_bytes(), [4, 7, 0xb5, 0x00, 0x31, b'G', b'A', b'9', b'4']);
+
+        let message = SeiMessage::new(300, &[0xaa; 510]).unwrap();
+        assert_eq!(message.as_bytes()[..5], [0xff, 45, 0xff, 0xff, 0]);
+        assert_eq!(message.as_bytes().len(), 515);
+
+        let err = SeiMessage::new(SEI_USER_DATA_UNREGISTERED, &[0; 65535]).err().unwrap();
+        assert!(matches!(err, Error::PayloadTooLarge(size) if size == 1 + 258 + 65535));
+    }
+
+    #[test]
+    fn points_ctrl_at_payloads() {
+        let first = SeiMessage::user_data_unregistered([1; 16], b"ts=1234").unwrap();
+        let second = SeiMessage::itu_t_t35(0xb5, b"data").unwrap();
+        let ctrl = FrameControl::new().sei(first.clone()).sei(second.clone());
+        // Moving the control must not move the payloads.
+        let ctrl = Box::new(ctrl);
+
+        let raw = ctrl.as_raw();
+        assert_eq!(raw.NumPayload, 2);
+        let payloads = unsafe { slice::from_raw_parts(raw.Payload, raw.NumPayload as usize) };
+        for (&payload, message) in payloads.iter().zip([first, second]) {
+            let payload = unsafe { &*payload };
+            let bytes = message.as_bytes();
+            assert_eq!(payload.Type, message.payload_type());
+            assert_eq!(payload.BufSize as usize, bytes.len());
+            assert_eq!(payload.NumBit as usize, 8 * bytes.len());
+            assert_eq!(unsafe { slice::from_raw_parts(payload.Data, bytes.len()) }, bytes);
+            assert_eq!(payload.CtrlFlags, 0);
+        }
+    }
+
+    #[test]
+    fn parses_key_frames() {
+        let key_frames: KeyFrames = "0, 300,2.5s".parse().unwrap();
+        assert_eq!(
+            key_frames.positions,
+            [
+                KeyFramePosition::Frame(0),
+                KeyFramePosition::Frame(300),
+                KeyFramePosition::Seconds(2.5),
+            ]
+        );
+        assert_eq!(key_frames.frame_numbers(30, 1), [0, 75, 300]);
+        assert_eq!(key_frames.frame_numbers(30000, 1001), [0, 75, 300]);
+        assert_eq!("10,0.4s".parse::<KeyFrames>().unwrap().frame_numbers(25, 1), [10]);
+
+        assert!("".parse::<KeyFrames>().unwrap().is_empty());
+        assert_eq!("12,x".parse::<KeyFrames>(), Err(InvalidKeyFrame("x".into())));
+        assert!("-1s".parse::<KeyFrames>().is_err());
+    }
+}
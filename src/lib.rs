@@ -0,0 +1,38 @@
+//! Quick Sync video encoding on top of the Intel Media SDK.
+//!
+//! Based on [https://software.intel.com/en-us/articles/media-sdk-tutorials-for-client-and-server](https://software.intel.com/en-us/articles/media-sdk-tutorials-for-client-and-server) `simple_3_encode`.
+#![allow(non_camel_case_types, non_snake_case)]
+
+#[cfg(feature = "hardware")]
+pub mod allocator;
+pub mod constants;
+#[cfg(feature = "hardware")]
+pub mod dispatcher;
+#[cfg(feature = "hardware")]
+pub mod encoder;
+pub mod encoder_config;
+pub mod enums;
+pub mod error;
+pub mod ext_buffer;
+#[cfg(feature = "hardware")]
+pub mod ffi;
+pub mod frame_control;
+#[cfg(feature = "hardware")]
+pub mod loader;
+pub mod mfx_sys;
+pub mod packet;
+pub mod rate_control;
+#[cfg(feature = "hardware")]
+pub mod pipeline;
+#[cfg(feature = "hardware")]
+pub mod session;
+pub mod stats;
+pub mod status;
+pub mod structures;
+pub mod surface_pool;
+pub mod utils;
+
+pub use error::{Error, Result};
+#[cfg(feature = "hardware")]
+pub use session::Session;
+pub use status::{MfxStatus, StatusClass};
@@ -0,0 +1,883 @@
+//! Runtime loading of the SDK library.
+//!
+//! Nothing links against `libmfx`/`libvpl` at build time; the library is opened with `dlopen` (`LoadLibrary` on Windows) when the first [`crate::Session`] is created, so a machine without the Intel runtime gets a [`LoadError`] instead of a binary that fails to start.
+use std::env;
+use std::error;
+use std::fmt;
+use std::io;
+use std::ops::Deref;
+use std::sync::{Arc, OnceLock};
+
+use crate::ffi::{DispatcherFunctions, Functions};
+
+/// Environment variable with the path of the library to load instead of the platform defaults, e.g. a stub library on CI machines without a GPU.
+pub const LIBRARY_PATH_ENV: &str = "QSV_MFX_LIBRARY";
+
+/// Libraries tried in order: the oneVPL dispatcher, the Media SDK dispatcher and the Media SDK hardware runtime. All of them export the `MFXInit` API.
+#[cfg(windows)]
+pub const DEFAULT_LIBRARY_NAMES: &[&str] = &["libvpl.dll", "libmfxhw64.dll"];
+#[cfg(not(windows))]
+pub const DEFAULT_LIBRARY_NAMES: &[&str] = &["libvpl.so.2", "libmfx.so.1", "libmfxhw64.so.1"];
+
+/// Error returned when the SDK library cannot be loaded.
+#[derive(Debug, Clone)]
+pub enum LoadError {
+    /// None of the candidate libraries could be opened.
+    NotFound {
+        /// Libraries that were tried, in order.
+        tried: Vec<String>,
+        /// Reason the last candidate failed to load.
+        reason: String,
+    },
+    /// The library was opened but does not export a required entry point.
+    MissingSymbol {
+        library: String,
+        symbol: &'static str,
+    },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::NotFound { tried, reason } => write!(
+                f,
+                "Intel Media SDK runtime not found (tried {}; set {LIBRARY_PATH_ENV} to override): {reason}",
+                tried.join(", ")
+            ),
+            LoadError::MissingSymbol { library, symbol } => {
+                write!(f, "{library} does not export {symbol}")
+            }
+        }
+    }
+}
+
+impl error::Error for LoadError {}
+
+impl From<LoadError> for io::Error {
+    fn from(err: LoadError) -> io::Error {
+        io::Error::new(io::ErrorKind::NotFound, err)
+    }
+}
+
+/// The SDK library and its resolved entry points.
+///
+/// Dereferences to [`Functions`], so an entry point is called as `(library.MFXInit)(...)`.
+pub struct Library {
+    functions: Functions,
+    // Keeps the shared object mapped for as long as the function pointers are in use.
+    _library: Option<libloading::Library>,
+}
+
+macro_rules! load_functions {
+    ($library:expr, $name:expr, $type:ident { $($symbol:ident),* $(,)? } $(, $field:ident: $value:expr)*) => {
+        $type {
+            $(
+                $symbol: *unsafe { $library.get(concat!(stringify!($symbol), "\0").as_bytes()) }
+                    .map_err(|_| LoadError::MissingSymbol {
+                        library: $name.to_string(),
+                        symbol: stringify!($symbol),
+                    })?,
+            )*
+            $($field: $value,)*
+        }
+    };
+}
+
+impl Library {
+    /// Opens the library named by [`LIBRARY_PATH_ENV`] if set, otherwise the first of [`DEFAULT_LIBRARY_NAMES`] that can be loaded.
+    pub fn open_default() -> Result<Library, LoadError> {
+        let candidates: Vec<String> = match env::var(LIBRARY_PATH_ENV) {
+            Ok(path) if !path.is_empty() => vec![path],
+            _ => DEFAULT_LIBRARY_NAMES.iter().map(|name| name.to_string()).collect(),
+        };
+
+        let mut reason = String::new();
+        for candidate in &candidates {
+            match Library::open(candidate) {
+                Err(LoadError::NotFound { reason: r, .. }) => reason = r,
+                result => return result,
+            }
+        }
+        Err(LoadError::NotFound {
+            tried: candidates,
+            reason,
+        })
+    }
+
+    /// Opens the library at `path` (a file name is looked up in the platform search path) and resolves all entry points.
+    pub fn open(path: &str) -> Result<Library, LoadError> {
+        let library = unsafe { libloading::Library::new(path) }.map_err(|err| LoadError::NotFound {
+            tried: vec![path.to_string()],
+            reason: err.to_string(),
+        })?;
+
+        let functions = load_functions!(library, path, Functions {
+            MFXInit,
+            MFXQueryIMPL,
+            MFXClose,
+            MFXVideoENCODE_Query,
+            MFXVideoENCODE_QueryIOSurf,
+            MFXVideoENCODE_Init,
+            MFXVideoENCODE_Reset,
+            MFXVideoENCODE_GetVideoParam,
+            MFXVideoENCODE_EncodeFrameAsync,
+            MFXVideoCORE_SetFrameAllocator,
+            MFXVideoCORE_SyncOperation,
+            MFXVideoENCODE_Close,
+            MFXVideoVPP_Query,
+            MFXVideoVPP_QueryIOSurf,
+            MFXVideoVPP_Init,
+            MFXVideoVPP_RunFrameVPPAsync,
+            MFXVideoVPP_Close,
+        }, dispatcher: Library::load_dispatcher(&library, path).ok());
+
+        Ok(Library {
+            functions,
+            _library: Some(library),
+        })
+    }
+
+    /// Resolves the oneVPL 2.x dispatcher entry points, which Media SDK libraries do not export.
+    fn load_dispatcher(library: &libloading::Library, path: &str) -> Result<DispatcherFunctions, LoadError> {
+        Ok(load_functions!(library, path, DispatcherFunctions {
+            MFXLoad,
+            MFXUnload,
+            MFXCreateConfig,
+            MFXSetConfigFilterProperty,
+            MFXEnumImplementations,
+            MFXCreateSession,
+            MFXDispReleaseImplDescription,
+        }))
+    }
+
+    /// Wraps entry points that are already resolved, e.g. a statically linked SDK or a test stub.
+    pub fn from_functions(functions: Functions) -> Library {
+        Library {
+            functions,
+            _library: None,
+        }
+    }
+}
+
+impl Deref for Library {
+    type Target = Functions;
+
+    fn deref(&self) -> &Functions {
+        &self.functions
+    }
+}
+
+/// The library opened by [`Library::open_default`], loaded once per process.
+pub fn default_library() -> Result<Arc<Library>, LoadError> {
+    static DEFAULT: OnceLock<Result<Arc<Library>, LoadError>> = OnceLock::new();
+    DEFAULT
+        .get_or_init(|| Library::open_default().map(Arc::new))
+        .clone()
+}
+
+/// A stand-in for the SDK library in unit tests: sessions open and close (counted per thread), every component call fails with `MFX_ERR_UNSUPPORTED`. [`stub::dispatcher_library`] adds oneVPL dispatcher entry points reporting a single implementation, [`stub::encoder_library`] an encoder that clamps the bitrate, does not support external BRC, returns fixed headers and buffers a few frames, [`stub::pipeline_library`] a VPP on top of it.
+#[cfg(test)]
+pub(crate) mod stub {
+    use std::cell::{Cell, RefCell};
+    use std::collections::VecDeque;
+    use std::ffi::CStr;
+    use std::ptr;
+    use std::slice;
+    use std::sync::Arc;
+    use std::thread::LocalKey;
+
+    use super::Library;
+    use crate::constants::*;
+    use crate::dispatcher::PROPERTY_ENCODER_CODEC_ID;
+    use crate::enums::FrameType;
+    use crate::ffi::{DispatcherFunctions, Functions};
+    use crate::rate_control::BrcParams;
+    use crate::structures::*;
+
+    thread_local! {
+        pub static OPEN_SESSIONS: Cell<i32> = const { Cell::new(0) };
+        pub static LOADERS: Cell<i32> = const { Cell::new(0) };
+        /// Filter properties set on the last loader.
+        pub static FILTERS: RefCell<Vec<(String, mfxU32)>> = const { RefCell::new(Vec::new()) };
+        /// Status returned by `MFXCreateSession` of [`dispatcher_library`] instead of creating the session, unless `MFX_ERR_NONE`.
+        pub static CREATE_SESSION_STATUS: Cell<mfxStatus> = const { Cell::new(MFX_ERR_NONE) };
+        pub static OPEN_ENCODERS: Cell<i32> = const { Cell::new(0) };
+        /// Frame size the encoder of [`encoder_library`] was initialized with, which `Reset` cannot exceed, and its current frame size.
+        static ENCODER_MAX_SIZE: Cell<(mfxU16, mfxU16)> = const { Cell::new((0, 0)) };
+        static ENCODER_SIZE: Cell<(mfxU16, mfxU16)> = const { Cell::new((0, 0)) };
+        /// BRCParamMultiplier of the open encoder of [`encoder_library`], which scales the BufferSizeInKB returned by `GetVideoParam`.
+        static ENCODER_BRC_MULTIPLIER: Cell<mfxU16> = const { Cell::new(0) };
+        /// Number of successful `MFXVideoENCODE_Reset` calls to the encoder of [`encoder_library`].
+        pub static ENCODER_RESETS: Cell<u32> = const { Cell::new(0) };
+        /// Frames held back by the encoder of [`encoder_library`].
+        static ENCODER_FRAMES: RefCell<VecDeque<HeldFrame>> = const { RefCell::new(VecDeque::new()) };
+        static ENCODER_INPUTS: Cell<mfxU32> = const { Cell::new(0) };
+        /// Whether the VPP of [`pipeline_library`] holds a frame, and how many outputs it made from it.
+        static VPP_PENDING: Cell<bool> = const { Cell::new(false) };
+        static VPP_OUTPUTS: Cell<u32> = const { Cell::new(0) };
+        /// Timestamp of the frame the VPP of [`pipeline_library`] holds.
+        static VPP_TIME_STAMP: Cell<mfxU64> = const { Cell::new(MFX_TIMESTAMP_UNKNOWN) };
+        /// Output frames the VPP of [`pipeline_library`] makes from each input, asking for more surfaces with `MFX_ERR_MORE_SURFACE`.
+        pub static VPP_OUTPUTS_PER_INPUT: Cell<u32> = const { Cell::new(1) };
+        /// Status returned by `QueryIOSurf` of the VPP of [`pipeline_library`].
+        pub static VPP_QUERY_IO_SURF_STATUS: Cell<mfxStatus> = const { Cell::new(MFX_ERR_NONE) };
+        /// Number of times the VPP of [`pipeline_library`] was initialized.
+        pub static VPP_INITS: Cell<u32> = const { Cell::new(0) };
+        /// Number of times the VPP of [`pipeline_library`] was closed.
+        pub static VPP_CLOSES: Cell<u32> = const { Cell::new(0) };
+        /// The surface the VPP of [`pipeline_library`] wrote last, and the number of frames encoded straight from such a surface.
+        static VPP_OUTPUT: Cell<usize> = const { Cell::new(0) };
+        pub static FRAMES_FROM_VPP: Cell<u32> = const { Cell::new(0) };
+        /// Number of the next encoder and VPP calls that return `MFX_WRN_DEVICE_BUSY`.
+        pub static BUSY_CALLS: Cell<u32> = const { Cell::new(0) };
+        /// Number of the next VPP `Query` and `Init` calls that return `MFX_WRN_DEVICE_BUSY`.
+        pub static VPP_SETUP_BUSY_CALLS: Cell<u32> = const { Cell::new(0) };
+        /// Status returned by `EncodeFrameAsync` of [`encoder_library`] for a bitstream carrying mfxExtAVCEncodedFrameInfo, unless `MFX_ERR_NONE`.
+        pub static ENCODED_FRAME_INFO_STATUS: Cell<mfxStatus> = const { Cell::new(MFX_ERR_NONE) };
+        /// Number of the input frame for which `EncodeFrameAsync` of [`encoder_library`] fails with `MFX_ERR_DEVICE_FAILED`.
+        pub static FAILING_FRAME: Cell<mfxU32> = const { Cell::new(mfxU32::MAX) };
+        /// Added to the timestamp of each frame written by the encoder of [`encoder_library`].
+        pub static PACKET_TIME_STAMP_SHIFT: Cell<mfxU64> = const { Cell::new(0) };
+        /// Frames encoded by [`encoder_library`] whose sync point has not been waited on, and the highest such count.
+        static ENCODES_IN_FLIGHT: Cell<u32> = const { Cell::new(0) };
+        pub static PEAK_ENCODES_IN_FLIGHT: Cell<u32> = const { Cell::new(0) };
+        /// Size of each frame written by the encoder of [`encoder_library`], at least [`PACKET_SIZE`].
+        pub static FRAME_SIZE: Cell<usize> = const { Cell::new(PACKET_SIZE) };
+        /// The allocator set with `MFXVideoCORE_SetFrameAllocator`, and the frames the encoder of [`encoder_library`] allocated with it.
+        static FRAME_ALLOCATOR: Cell<*const mfxFrameAllocator> = const { Cell::new(ptr::null()) };
+        static ALLOC_RESPONSE: Cell<Option<mfxFrameAllocResponse>> = const { Cell::new(None) };
+    }
+
+    /// Headers returned through mfxExtCodingOptionSPSPPS by the encoder of [`encoder_library`].
+    pub const SPS: &[u8] = &[0, 0, 0, 1, 0x67, 0x64, 0x00, 0x28, 0xac, 0xd9, 0x40, 0x78];
+    pub const PPS: &[u8] = &[0, 0, 0, 1, 0x68, 0xeb, 0xe3, 0xcb, 0x22, 0xc0];
+    /// Highest target bitrate accepted by `Query` of [`encoder_library`], TargetKbps times BRCParamMultiplier.
+    pub const MAX_KBPS: u32 = 400_000;
+    /// Lowest QPI accepted by `Query` of [`encoder_library`] with MFX_RATECONTROL_CQP.
+    pub const MIN_QPI: mfxU16 = 10;
+    /// BufferSizeInKB returned by `GetVideoParam` of [`encoder_library`], before BRCParamMultiplier.
+    pub const BUFFER_SIZE_KB: mfxU16 = 64;
+    /// NumFrameSuggested returned by `QueryIOSurf` of [`encoder_library`].
+    pub const SUGGESTED_SURFACES: mfxU16 = 4;
+    /// Number of frames the encoder of [`encoder_library`] buffers before returning the first one, like B-frames or lookahead.
+    pub const ENCODER_DELAY: usize = 2;
+    /// QP reported through mfxExtAVCEncodedFrameInfo by the encoder of [`encoder_library`].
+    pub const ENCODED_QP: mfxU16 = 26;
+    /// Header of each frame written by the encoder of [`encoder_library`]: a start code, an IDR or non-IDR slice NAL header and the frame number, followed by zeros up to [`FRAME_SIZE`].
+    pub const PACKET_SIZE: usize = 8;
+    /// A frame given to the encoder of [`encoder_library`]: its number, its controls and its timestamp.
+    struct HeldFrame {
+        number: mfxU32,
+        ctrl: *const mfxEncodeCtrl,
+        time_stamp: mfxU64,
+    }
+
+    const ENCODE_SYNC_POINT: mfxSyncPoint = 0xe5 as mfxSyncPoint;
+    const VPP_SYNC_POINT: mfxSyncPoint = 0x5f as mfxSyncPoint;
+
+    unsafe extern "C" fn init(_: mfxIMPL, _: *const mfxVersion, session: *mut *mut mfxSession) -> mfxStatus {
+        OPEN_SESSIONS.with(|open| open.set(open.get() + 1));
+        *session = 0x5e55 as *mut mfxSession;
+        MFX_ERR_NONE
+    }
+
+    unsafe extern "C" fn query_impl(_: *const mfxSession, implementation: *mut mfxIMPL) -> mfxStatus {
+        *implementation = MFX_IMPL_HARDWARE;
+        MFX_ERR_NONE
+    }
+
+    unsafe extern "C" fn close(_: *const mfxSession) -> mfxStatus {
+        OPEN_SESSIONS.with(|open| open.set(open.get() - 1));
+        FRAME_ALLOCATOR.with(|allocator| allocator.set(ptr::null()));
+        MFX_ERR_NONE
+    }
+
+    unsafe extern "C" fn set_frame_allocator(_: *const mfxSession, allocator: *const mfxFrameAllocator) -> mfxStatus {
+        FRAME_ALLOCATOR.with(|current| current.set(allocator));
+        MFX_ERR_NONE
+    }
+
+    unsafe extern "C" fn unsupported(_: *const mfxSession) -> mfxStatus {
+        MFX_ERR_UNSUPPORTED
+    }
+
+    unsafe extern "C" fn unsupported_param(_: *const mfxSession, _: *const mfxVideoParam) -> mfxStatus {
+        MFX_ERR_UNSUPPORTED
+    }
+
+    unsafe extern "C" fn unsupported_param_mut(_: *const mfxSession, _: *mut mfxVideoParam) -> mfxStatus {
+        MFX_ERR_UNSUPPORTED
+    }
+
+    unsafe extern "C" fn unsupported_query(_: *const mfxSession, _: *const mfxVideoParam, _: *mut mfxVideoParam) -> mfxStatus {
+        MFX_ERR_UNSUPPORTED
+    }
+
+    unsafe extern "C" fn unsupported_encode_query_io_surf(
+        _: *const mfxSession,
+        _: *const mfxVideoParam,
+        _: *mut mfxFrameAllocRequest,
+    ) -> mfxStatus {
+        MFX_ERR_UNSUPPORTED
+    }
+
+    unsafe extern "C" fn unsupported_vpp_query_io_surf(
+        _: *const mfxSession,
+        _: *const mfxVideoParam,
+        _: &mut [mfxFrameAllocRequest; 2],
+    ) -> mfxStatus {
+        MFX_ERR_UNSUPPORTED
+    }
+
+    unsafe extern "C" fn unsupported_encode_frame(
+        _: *const mfxSession,
+        _: *const mfxEncodeCtrl,
+        _: *const mfxFrameSurface1,
+        _: *mut mfxBitstream,
+        _: *mut mfxSyncPoint,
+    ) -> mfxStatus {
+        MFX_ERR_UNSUPPORTED
+    }
+
+    unsafe extern "C" fn unsupported_run_frame_vpp(
+        _: *const mfxSession,
+        _: *const mfxFrameSurface1,
+        _: *mut mfxFrameSurface1,
+        _: *const mfxExtVppAuxData,
+        _: *mut mfxSyncPoint,
+    ) -> mfxStatus {
+        MFX_ERR_UNSUPPORTED
+    }
+
+    unsafe extern "C" fn unsupported_sync(_: *const mfxSession, _: mfxSyncPoint, _: mfxU32) -> mfxStatus {
+        MFX_ERR_UNSUPPORTED
+    }
+
+    pub fn functions() -> Functions {
+        Functions {
+            MFXInit: init,
+            MFXQueryIMPL: query_impl,
+            MFXClose: close,
+            MFXVideoENCODE_Query: unsupported_query,
+            MFXVideoENCODE_QueryIOSurf: unsupported_encode_query_io_surf,
+            MFXVideoENCODE_Init: unsupported_param,
+            MFXVideoENCODE_Reset: unsupported_param,
+            MFXVideoENCODE_GetVideoParam: unsupported_param_mut,
+            MFXVideoENCODE_EncodeFrameAsync: unsupported_encode_frame,
+            MFXVideoCORE_SetFrameAllocator: set_frame_allocator,
+            MFXVideoCORE_SyncOperation: unsupported_sync,
+            MFXVideoENCODE_Close: unsupported,
+            MFXVideoVPP_Query: unsupported_query,
+            MFXVideoVPP_QueryIOSurf: unsupported_vpp_query_io_surf,
+            MFXVideoVPP_Init: unsupported_param_mut,
+            MFXVideoVPP_RunFrameVPPAsync: unsupported_run_frame_vpp,
+            MFXVideoVPP_Close: unsupported,
+            dispatcher: None,
+        }
+    }
+
+    pub fn library() -> Arc<Library> {
+        Arc::new(Library::from_functions(functions()))
+    }
+
+    unsafe extern "C" fn load() -> mfxLoader {
+        LOADERS.with(|loaders| loaders.set(loaders.get() + 1));
+        FILTERS.with(|filters| filters.borrow_mut().clear());
+        0x10ad as mfxLoader
+    }
+
+    unsafe extern "C" fn unload(_: mfxLoader) {
+        LOADERS.with(|loaders| loaders.set(loaders.get() - 1));
+    }
+
+    unsafe extern "C" fn create_config(_: mfxLoader) -> mfxConfig {
+        0xc0f1 as mfxConfig
+    }
+
+    unsafe extern "C" fn set_config_filter_property(_: mfxConfig, name: *const mfxU8, value: mfxVariant) -> mfxStatus {
+        if value.Type != MFX_VARIANT_TYPE_U32 {
+            return MFX_ERR_UNSUPPORTED;
+        }
+        let name = CStr::from_ptr(name as *const mfxChar).to_string_lossy().into_owned();
+        FILTERS.with(|filters| filters.borrow_mut().push((name, value.Data.U32)));
+        MFX_ERR_NONE
+    }
+
+    /// Reports a single hardware implementation named "stub" that only encodes AVC.
+    unsafe extern "C" fn enum_implementations(
+        _: mfxLoader,
+        i: mfxU32,
+        format: mfxImplCapsDeliveryFormat,
+        idesc: *mut mfxHDL,
+    ) -> mfxStatus {
+        if format != MFX_IMPLCAPS_IMPLDESCSTRUCTURE {
+            return MFX_ERR_UNSUPPORTED;
+        }
+        let unsupported_codec = FILTERS.with(|filters| {
+            filters
+                .borrow()
+                .iter()
+                .any(|(name, value)| name == PROPERTY_ENCODER_CODEC_ID && *value != MFX_CODEC_AVC)
+        });
+        if i > 0 || unsupported_codec {
+            return MFX_ERR_NOT_FOUND;
+        }
+
+        let mut name = [0; MFX_IMPL_NAME_LEN];
+        for (dst, src) in name.iter_mut().zip(b"stub") {
+            *dst = *src as mfxChar;
+        }
+        let description = Box::new(mfxImplDescription {
+            Version: 0x0102,
+            Impl: MFX_IMPL_TYPE_HARDWARE,
+            AccelerationMode: MFX_ACCEL_MODE_VIA_VAAPI,
+            ApiVersion: mfxVersion::new(2, 9),
+            ImplName: name,
+            License: [0; MFX_STRFIELD_LEN],
+            Keywords: [0; MFX_STRFIELD_LEN],
+            VendorID: 0x8086,
+            VendorImplID: 0,
+        });
+        *idesc = Box::into_raw(description) as mfxHDL;
+        MFX_ERR_NONE
+    }
+
+    unsafe extern "C" fn create_session(_: mfxLoader, _: mfxU32, session: *mut *mut mfxSession) -> mfxStatus {
+        let status = CREATE_SESSION_STATUS.with(Cell::get);
+        if status != MFX_ERR_NONE {
+            return status;
+        }
+        init(MFX_IMPL_HARDWARE, ptr::null(), session)
+    }
+
+    unsafe extern "C" fn release_impl_description(_: mfxLoader, hdl: mfxHDL) -> mfxStatus {
+        drop(Box::from_raw(hdl as *mut mfxImplDescription));
+        MFX_ERR_NONE
+    }
+
+    /// The extended buffers of `par`.
+    unsafe fn ext_buffers(par: &mfxVideoParam) -> &[*mut mfxExtBuffer] {
+        if par.NumExtParam == 0 {
+            return &[];
+        }
+        slice::from_raw_parts(par.ExtParam, par.NumExtParam as usize)
+    }
+
+    unsafe extern "C" fn encode_query(
+        _: *const mfxSession,
+        input: *const mfxVideoParam,
+        output: *mut mfxVideoParam,
+    ) -> mfxStatus {
+        let (input, output) = (&*input, &mut *output);
+        let mut status = MFX_ERR_NONE;
+        output.u = input.u;
+        output.AsyncDepth = input.AsyncDepth;
+        output.IOPattern = input.IOPattern;
+        let multiplier = output.mfx().BRCParamMultiplier.max(1) as u32;
+        let target_kbps = BrcParams::read(output.mfx()).target_kbps;
+        let encode = output.mfx_mut().as_encode_mut();
+        if encode.RateControlMethod == MFX_RATECONTROL_CQP {
+            if (1..MIN_QPI).contains(&encode.u1.QPI) {
+                encode.u1.QPI = MIN_QPI;
+                status = MFX_WRN_INCOMPATIBLE_VIDEO_PARAM;
+            }
+        } else if target_kbps > MAX_KBPS {
+            // Clamps the bitrate, keeping the multiplier
+            encode.u2.TargetKbps = (MAX_KBPS / multiplier) as mfxU16;
+            status = MFX_WRN_INCOMPATIBLE_VIDEO_PARAM;
+        }
+
+        for &source in ext_buffers(input) {
+            let target = ext_buffers(output)
+                .iter()
+                .find(|&&target| (*target).BufferId == (*source).BufferId);
+            let Some(&target) = target else {
+                return MFX_ERR_UNDEFINED_BEHAVIOR;
+            };
+            ptr::copy_nonoverlapping(source as *const u8, target as *mut u8, (*source).BufferSz as usize);
+            if (*target).BufferId == MFX_EXTBUFF_ENCODER_RESET_OPTION {
+                let option = &mut *(target as *mut mfxExtEncoderResetOption);
+                match reset_outcome(input) {
+                    Ok(new_sequence) => {
+                        option.StartNewSequence = if new_sequence { MFX_CODINGOPTION_ON } else { MFX_CODINGOPTION_OFF }
+                    }
+                    Err(status) => return status,
+                }
+            }
+            if (*target).BufferId == MFX_EXTBUFF_CODING_OPTION2 {
+                let option = &mut *(target as *mut mfxExtCodingOption2);
+                if option.ExtBRC != 0 {
+                    option.ExtBRC = 0;
+                    status = MFX_WRN_INCOMPATIBLE_VIDEO_PARAM;
+                }
+            }
+        }
+        status
+    }
+
+    unsafe extern "C" fn encode_query_io_surf(
+        _: *const mfxSession,
+        par: *const mfxVideoParam,
+        request: *mut mfxFrameAllocRequest,
+    ) -> mfxStatus {
+        (*request).Info = (*par).mfx().FrameInfo;
+        (*request).NumFrameMin = SUGGESTED_SURFACES - 1;
+        (*request).NumFrameSuggested = SUGGESTED_SURFACES;
+        MFX_ERR_NONE
+    }
+
+    /// Allocates [`SUGGESTED_SURFACES`] input frames with the external allocator, if one is set, and locks each of them once like the SDK does to check them.
+    unsafe extern "C" fn encode_init(_: *const mfxSession, par: *const mfxVideoParam) -> mfxStatus {
+        let allocator = FRAME_ALLOCATOR.with(Cell::get);
+        if let Some(allocator) = allocator.as_ref() {
+            let mut request = mfxFrameAllocRequest::new();
+            request.Info = (*par).mfx().FrameInfo;
+            request.Type = MFX_MEMTYPE_EXTERNAL_FRAME | MFX_MEMTYPE_FROM_ENCODE | MFX_MEMTYPE_SYSTEM_MEMORY;
+            request.NumFrameMin = SUGGESTED_SURFACES - 1;
+            request.NumFrameSuggested = SUGGESTED_SURFACES;
+            let mut response = mfxFrameAllocResponse::new();
+            let status = (allocator.Alloc.unwrap())(allocator.pthis, &mut request, &mut response);
+            if status != MFX_ERR_NONE {
+                return status;
+            }
+            ALLOC_RESPONSE.with(|saved| saved.set(Some(response)));
+
+            let mids = slice::from_raw_parts(response.mids, response.NumFrameActual as usize);
+            for &mid in mids {
+                let mut data = mfxFrameData::new();
+                let status = (allocator.Lock.unwrap())(allocator.pthis, mid, &mut data);
+                if status != MFX_ERR_NONE {
+                    return status;
+                }
+                if data.Y.is_null() {
+                    return MFX_ERR_LOCK_MEMORY;
+                }
+                (allocator.Unlock.unwrap())(allocator.pthis, mid, &mut data);
+            }
+            let mut handle: mfxHDL = ptr::null();
+            let status = (allocator.GetHDL.unwrap())(allocator.pthis, mids[0], &mut handle);
+            if status != MFX_ERR_NONE && status != MFX_ERR_UNSUPPORTED {
+                return status;
+            }
+        }
+        OPEN_ENCODERS.with(|open| open.set(open.get() + 1));
+        let info = &(*par).mfx().FrameInfo;
+        ENCODER_MAX_SIZE.with(|size| size.set((info.Width, info.Height)));
+        ENCODER_SIZE.with(|size| size.set((info.Width, info.Height)));
+        ENCODER_BRC_MULTIPLIER.with(|multiplier| multiplier.set((*par).mfx().BRCParamMultiplier));
+        MFX_ERR_NONE
+    }
+
+    /// Whether resetting the open encoder with `par` starts a new sequence, which it does when the frame size changes. Growing the frames past the size at `Init` needs new surfaces, so it is refused.
+    fn reset_outcome(par: &mfxVideoParam) -> Result<bool, mfxStatus> {
+        if OPEN_ENCODERS.with(Cell::get) == 0 {
+            return Err(MFX_ERR_NOT_INITIALIZED);
+        }
+        let info = &par.mfx().FrameInfo;
+        let (max_width, max_height) = ENCODER_MAX_SIZE.with(Cell::get);
+        if info.Width > max_width || info.Height > max_height {
+            return Err(MFX_ERR_INCOMPATIBLE_VIDEO_PARAM);
+        }
+        Ok(ENCODER_SIZE.with(Cell::get) != (info.Width, info.Height))
+    }
+
+    /// Applies the new frame size and bitrate multiplier and drops the frames held back.
+    unsafe extern "C" fn encode_reset(_: *const mfxSession, par: *const mfxVideoParam) -> mfxStatus {
+        if let Err(status) = reset_outcome(&*par) {
+            return status;
+        }
+        let info = &(*par).mfx().FrameInfo;
+        ENCODER_SIZE.with(|size| size.set((info.Width, info.Height)));
+        ENCODER_BRC_MULTIPLIER.with(|multiplier| multiplier.set((*par).mfx().BRCParamMultiplier));
+        ENCODER_FRAMES.with(|frames| frames.borrow_mut().clear());
+        ENCODER_RESETS.with(|resets| resets.set(resets.get() + 1));
+        MFX_ERR_NONE
+    }
+
+    unsafe extern "C" fn encode_close(_: *const mfxSession) -> mfxStatus {
+        OPEN_ENCODERS.with(|open| open.set(open.get() - 1));
+        ENCODER_FRAMES.with(|frames| frames.borrow_mut().clear());
+        let allocator = FRAME_ALLOCATOR.with(Cell::get);
+        if let (Some(allocator), Some(mut response)) = (allocator.as_ref(), ALLOC_RESPONSE.with(|saved| saved.take())) {
+            (allocator.Free.unwrap())(allocator.pthis, &mut response);
+        }
+        MFX_ERR_NONE
+    }
+
+    unsafe extern "C" fn encode_get_video_param(_: *const mfxSession, par: *mut mfxVideoParam) -> mfxStatus {
+        let par = &mut *par;
+        par.mfx_mut().BRCParamMultiplier = ENCODER_BRC_MULTIPLIER.with(Cell::get);
+        par.mfx_mut().as_encode_mut().BufferSizeInKB = BUFFER_SIZE_KB;
+        for &buffer in ext_buffers(par) {
+            if (*buffer).BufferId != MFX_EXTBUFF_CODING_OPTION_SPSPPS {
+                continue;
+            }
+            let headers = &mut *(buffer as *mut mfxExtCodingOptionSPSPPS);
+            if (headers.SPSBufSize as usize) < SPS.len() || (headers.PPSBufSize as usize) < PPS.len() {
+                return MFX_ERR_NOT_ENOUGH_BUFFER;
+            }
+            ptr::copy_nonoverlapping(SPS.as_ptr(), headers.SPSBuffer, SPS.len());
+            headers.SPSBufSize = SPS.len() as mfxU16;
+            ptr::copy_nonoverlapping(PPS.as_ptr(), headers.PPSBuffer, PPS.len());
+            headers.PPSBufSize = PPS.len() as mfxU16;
+        }
+        MFX_ERR_NONE
+    }
+
+    /// Whether the encoder of [`encoder_library`] holds a frame whose controls are `ctrl`, which it reads once it encodes the frame.
+    pub fn holds_ctrl(ctrl: *const mfxEncodeCtrl) -> bool {
+        ENCODER_FRAMES.with(|frames| frames.borrow().iter().any(|frame| frame.ctrl == ctrl))
+    }
+
+    /// Consumes one of the [`BUSY_CALLS`].
+    fn busy() -> bool {
+        consume_busy(&BUSY_CALLS)
+    }
+
+    /// Consumes one of the busy `calls`, returns whether the call should report `MFX_WRN_DEVICE_BUSY`.
+    fn consume_busy(calls: &'static LocalKey<Cell<u32>>) -> bool {
+        calls.with(|calls| {
+            let busy = calls.get() > 0;
+            calls.set(calls.get().saturating_sub(1));
+            busy
+        })
+    }
+
+    /// Holds back [`ENCODER_DELAY`] frames, then writes one packet per input frame. A NULL surface drains the held frames.
+    ///
+    /// The first frame and the frames forced with mfxEncodeCtrl are IDR frames; like the SDK, the controls are read when
+    /// the held frame is encoded. Frames are not reordered, so the decode timestamp of each packet is the timestamp of its
+    /// surface.
+    unsafe extern "C" fn encode_frame(
+        _: *const mfxSession,
+        ctrl: *const mfxEncodeCtrl,
+        surface: *const mfxFrameSurface1,
+        bs: *mut mfxBitstream,
+        syncp: *mut mfxSyncPoint,
+    ) -> mfxStatus {
+        if busy() {
+            return MFX_WRN_DEVICE_BUSY;
+        }
+        let bs = &mut *bs;
+        let frame_info_status = ENCODED_FRAME_INFO_STATUS.with(Cell::get);
+        if frame_info_status != MFX_ERR_NONE && bs.NumExtParam > 0 {
+            return frame_info_status;
+        }
+        let end = (bs.DataOffset + bs.DataLength) as usize;
+        let size = FRAME_SIZE.with(Cell::get);
+        if end + size > bs.MaxLength as usize {
+            return MFX_ERR_NOT_ENOUGH_BUFFER;
+        }
+
+        if !surface.is_null() && ENCODER_INPUTS.with(Cell::get) == FAILING_FRAME.with(Cell::get) {
+            return MFX_ERR_DEVICE_FAILED;
+        }
+        if !surface.is_null() && surface as usize == VPP_OUTPUT.with(Cell::get) {
+            FRAMES_FROM_VPP.with(|frames| frames.set(frames.get() + 1));
+        }
+        let frame = ENCODER_FRAMES.with(|frames| {
+            let mut frames = frames.borrow_mut();
+            if !surface.is_null() {
+                let input = ENCODER_INPUTS.with(|inputs| inputs.replace(inputs.get() + 1));
+                frames.push_back(HeldFrame {
+                    number: input,
+                    ctrl,
+                    time_stamp: (*surface).Data.TimeStamp,
+                });
+                if frames.len() <= ENCODER_DELAY {
+                    return None;
+                }
+            }
+            frames.pop_front()
+        });
+        let Some(frame) = frame else {
+            return MFX_ERR_MORE_DATA;
+        };
+
+        let forced = frame.ctrl.as_ref().is_some_and(|ctrl| ctrl.frame_type().contains(FrameType::IDR));
+        let idr = frame.number == 0 || forced;
+        let [_, high, mid, low] = frame.number.to_be_bytes();
+        let nal_header = if idr { 0x65 } else { 0x41 };
+        let packet: [u8; PACKET_SIZE] = [0, 0, 0, 1, nal_header, high, mid, low];
+        let data = (bs.Data as *mut u8).add(end);
+        ptr::write_bytes(data, 0, size);
+        ptr::copy_nonoverlapping(packet.as_ptr(), data, PACKET_SIZE);
+        bs.DataLength += size as mfxU32;
+        bs.TimeStamp = match frame.time_stamp {
+            MFX_TIMESTAMP_UNKNOWN => MFX_TIMESTAMP_UNKNOWN,
+            time_stamp => time_stamp + PACKET_TIME_STAMP_SHIFT.with(Cell::get),
+        };
+        bs.DecodeTimeStamp = frame.time_stamp as mfxI64;
+        bs.FrameType = if idr {
+            MFX_FRAMETYPE_I | MFX_FRAMETYPE_REF | MFX_FRAMETYPE_IDR
+        } else {
+            MFX_FRAMETYPE_P | MFX_FRAMETYPE_REF
+        };
+        bs.PicStruct = MFX_PICSTRUCT_PROGRESSIVE;
+        for i in 0..bs.NumExtParam as usize {
+            let buffer = *bs.ExtParam.add(i);
+            if (*buffer).BufferId == MFX_EXTBUFF_ENCODED_FRAME_INFO {
+                let info = &mut *(buffer as *mut mfxExtAVCEncodedFrameInfo);
+                info.FrameOrder = frame.number;
+                info.PicStruct = MFX_PICSTRUCT_PROGRESSIVE;
+                info.QP = ENCODED_QP;
+            }
+        }
+        *syncp = ENCODE_SYNC_POINT;
+        let in_flight = ENCODES_IN_FLIGHT.with(|count| count.get() + 1);
+        ENCODES_IN_FLIGHT.with(|count| count.set(in_flight));
+        PEAK_ENCODES_IN_FLIGHT.with(|peak| peak.set(peak.get().max(in_flight)));
+        MFX_ERR_NONE
+    }
+
+    unsafe extern "C" fn sync(_: *const mfxSession, syncp: mfxSyncPoint, _: mfxU32) -> mfxStatus {
+        if syncp.is_null() {
+            return MFX_ERR_NULL_PTR;
+        }
+        if syncp == ENCODE_SYNC_POINT {
+            ENCODES_IN_FLIGHT.with(|count| count.set(count.get() - 1));
+        }
+        MFX_ERR_NONE
+    }
+
+    pub fn encoder_library() -> Arc<Library> {
+        ENCODER_FRAMES.with(|frames| frames.borrow_mut().clear());
+        ENCODER_INPUTS.with(|inputs| inputs.set(0));
+        ENCODES_IN_FLIGHT.with(|count| count.set(0));
+        PEAK_ENCODES_IN_FLIGHT.with(|peak| peak.set(0));
+        ALLOC_RESPONSE.with(|saved| saved.set(None));
+        ENCODER_RESETS.with(|resets| resets.set(0));
+        Arc::new(Library::from_functions(encoder_functions()))
+    }
+
+    fn encoder_functions() -> Functions {
+        let mut functions = functions();
+        functions.MFXVideoENCODE_Query = encode_query;
+        functions.MFXVideoENCODE_QueryIOSurf = encode_query_io_surf;
+        functions.MFXVideoENCODE_Init = encode_init;
+        functions.MFXVideoENCODE_Reset = encode_reset;
+        functions.MFXVideoENCODE_GetVideoParam = encode_get_video_param;
+        functions.MFXVideoENCODE_EncodeFrameAsync = encode_frame;
+        functions.MFXVideoCORE_SyncOperation = sync;
+        functions.MFXVideoENCODE_Close = encode_close;
+        functions
+    }
+
+    unsafe extern "C" fn vpp_query(
+        _: *const mfxSession,
+        input: *const mfxVideoParam,
+        output: *mut mfxVideoParam,
+    ) -> mfxStatus {
+        if consume_busy(&VPP_SETUP_BUSY_CALLS) {
+            return MFX_WRN_DEVICE_BUSY;
+        }
+        if !input.is_null() {
+            *output = *input;
+        }
+        MFX_ERR_NONE
+    }
+
+    unsafe extern "C" fn vpp_query_io_surf(
+        _: *const mfxSession,
+        par: *const mfxVideoParam,
+        request: &mut [mfxFrameAllocRequest; 2],
+    ) -> mfxStatus {
+        request[0].Info = (*par).vpp().In;
+        request[1].Info = (*par).vpp().Out;
+        for request in request.iter_mut() {
+            request.NumFrameMin = 1;
+            request.NumFrameSuggested = 2;
+        }
+        VPP_QUERY_IO_SURF_STATUS.with(Cell::get)
+    }
+
+    unsafe extern "C" fn vpp_init(_: *const mfxSession, _: *mut mfxVideoParam) -> mfxStatus {
+        if consume_busy(&VPP_SETUP_BUSY_CALLS) {
+            return MFX_WRN_DEVICE_BUSY;
+        }
+        VPP_INITS.with(|inits| inits.set(inits.get() + 1));
+        VPP_PENDING.with(|pending| pending.set(false));
+        VPP_OUTPUTS.with(|outputs| outputs.set(0));
+        VPP_TIME_STAMP.with(|time_stamp| time_stamp.set(MFX_TIMESTAMP_UNKNOWN));
+        VPP_OUTPUT.with(|surface| surface.set(0));
+        FRAMES_FROM_VPP.with(|frames| frames.set(0));
+        MFX_ERR_NONE
+    }
+
+    unsafe extern "C" fn vpp_close(_: *const mfxSession) -> mfxStatus {
+        VPP_CLOSES.with(|closes| closes.set(closes.get() + 1));
+        MFX_ERR_NONE
+    }
+
+    /// Delays the output by one frame and makes [`VPP_OUTPUTS_PER_INPUT`] frames from each input. A NULL input returns the held frame.
+    ///
+    /// The outputs carry the timestamp of the frame they were made from.
+    unsafe extern "C" fn run_frame_vpp(
+        _: *const mfxSession,
+        input: *const mfxFrameSurface1,
+        output: *mut mfxFrameSurface1,
+        _: *const mfxExtVppAuxData,
+        syncp: *mut mfxSyncPoint,
+    ) -> mfxStatus {
+        if busy() {
+            return MFX_WRN_DEVICE_BUSY;
+        }
+        let time_stamp = input.as_ref().map_or(MFX_TIMESTAMP_UNKNOWN, |input| input.Data.TimeStamp);
+        if !VPP_PENDING.with(|pending| pending.replace(!input.is_null())) {
+            VPP_TIME_STAMP.with(|held| held.set(time_stamp));
+            return MFX_ERR_MORE_DATA;
+        }
+        *syncp = VPP_SYNC_POINT;
+        (*output).Data.TimeStamp = VPP_TIME_STAMP.with(Cell::get);
+        VPP_OUTPUT.with(|surface| surface.set(output as usize));
+        let outputs = VPP_OUTPUTS.with(|outputs| outputs.get() + 1);
+        if outputs < VPP_OUTPUTS_PER_INPUT.with(Cell::get) {
+            // The held frame stays held until the last output, the caller passes the same input again.
+            VPP_PENDING.with(|pending| pending.set(true));
+            VPP_OUTPUTS.with(|count| count.set(outputs));
+            return MFX_ERR_MORE_SURFACE;
+        }
+        VPP_OUTPUTS.with(|count| count.set(0));
+        VPP_TIME_STAMP.with(|held| held.set(time_stamp));
+        MFX_ERR_NONE
+    }
+
+    /// [`encoder_library`] with a VPP that converts frames without touching them, enough to run [`crate::pipeline::run`].
+    pub fn pipeline_library() -> Arc<Library> {
+        encoder_library();
+        let mut functions = encoder_functions();
+        functions.MFXVideoVPP_Query = vpp_query;
+        functions.MFXVideoVPP_QueryIOSurf = vpp_query_io_surf;
+        functions.MFXVideoVPP_Init = vpp_init;
+        functions.MFXVideoVPP_RunFrameVPPAsync = run_frame_vpp;
+        functions.MFXVideoVPP_Close = vpp_close;
+        Arc::new(Library::from_functions(functions))
+    }
+
+    pub fn dispatcher_library() -> Arc<Library> {
+        CREATE_SESSION_STATUS.with(|status| status.set(MFX_ERR_NONE));
+        let mut functions = functions();
+        functions.dispatcher = Some(DispatcherFunctions {
+            MFXLoad: load,
+            MFXUnload: unload,
+            MFXCreateConfig: create_config,
+            MFXSetConfigFilterProperty: set_config_filter_property,
+            MFXEnumImplementations: enum_implementations,
+            MFXCreateSession: create_session,
+            MFXDispReleaseImplDescription: release_impl_description,
+        });
+        Arc::new(Library::from_functions(functions))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn missing_library() {
+        let err = Library::open("libmfx-does-not-exist.so").err().unwrap();
+        assert!(matches!(err, LoadError::NotFound { .. }));
+        assert!(err.to_string().contains("libmfx-does-not-exist.so"));
+    }
+}
@@ -0,0 +1,924 @@
+/* Maintained by hand: the items of the Media SDK 2018 R2 headers (API 1.27) used by this crate, as rust-bindgen 0.64.0 */
+/* generates them. New items are copied from the output of build.rs; the layout tests in src/mfx_sys/mod.rs do not use this file. */
+
+pub const MFX_VERSION_MAJOR: u32 = 1;
+pub const MFX_VERSION_MINOR: u32 = 27;
+pub const MFX_VERSION: u32 = 1027;
+pub type mfxU8 = ::std::os::raw::c_uchar;
+pub type mfxI8 = ::std::os::raw::c_schar;
+pub type mfxI16 = ::std::os::raw::c_short;
+pub type mfxU16 = ::std::os::raw::c_ushort;
+pub type mfxU32 = ::std::os::raw::c_uint;
+pub type mfxI32 = ::std::os::raw::c_int;
+pub type mfxUL32 = ::std::os::raw::c_uint;
+pub type mfxL32 = ::std::os::raw::c_int;
+pub type mfxU64 = ::std::os::raw::c_ulonglong;
+pub type mfxI64 = ::std::os::raw::c_longlong;
+pub type mfxF32 = f32;
+pub type mfxF64 = f64;
+pub type mfxHDL = *mut ::std::os::raw::c_void;
+pub type mfxMemId = mfxHDL;
+pub type mfxThreadTask = *mut ::std::os::raw::c_void;
+pub type mfxChar = ::std::os::raw::c_char;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mfxI16Pair {
+    pub x: mfxI16,
+    pub y: mfxI16,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mfxHDLPair {
+    pub first: mfxHDL,
+    pub second: mfxHDL,
+}
+pub const MFX_ERR_NONE: mfxStatus = 0;
+pub const MFX_ERR_UNKNOWN: mfxStatus = -1;
+pub const MFX_ERR_NULL_PTR: mfxStatus = -2;
+pub const MFX_ERR_UNSUPPORTED: mfxStatus = -3;
+pub const MFX_ERR_MEMORY_ALLOC: mfxStatus = -4;
+pub const MFX_ERR_NOT_ENOUGH_BUFFER: mfxStatus = -5;
+pub const MFX_ERR_INVALID_HANDLE: mfxStatus = -6;
+pub const MFX_ERR_LOCK_MEMORY: mfxStatus = -7;
+pub const MFX_ERR_NOT_INITIALIZED: mfxStatus = -8;
+pub const MFX_ERR_NOT_FOUND: mfxStatus = -9;
+pub const MFX_ERR_MORE_DATA: mfxStatus = -10;
+pub const MFX_ERR_MORE_SURFACE: mfxStatus = -11;
+pub const MFX_ERR_ABORTED: mfxStatus = -12;
+pub const MFX_ERR_DEVICE_LOST: mfxStatus = -13;
+pub const MFX_ERR_INCOMPATIBLE_VIDEO_PARAM: mfxStatus = -14;
+pub const MFX_ERR_INVALID_VIDEO_PARAM: mfxStatus = -15;
+pub const MFX_ERR_UNDEFINED_BEHAVIOR: mfxStatus = -16;
+pub const MFX_ERR_DEVICE_FAILED: mfxStatus = -17;
+pub const MFX_ERR_MORE_BITSTREAM: mfxStatus = -18;
+pub const MFX_ERR_INCOMPATIBLE_AUDIO_PARAM: mfxStatus = -19;
+pub const MFX_ERR_INVALID_AUDIO_PARAM: mfxStatus = -20;
+pub const MFX_ERR_GPU_HANG: mfxStatus = -21;
+pub const MFX_ERR_REALLOC_SURFACE: mfxStatus = -22;
+pub const MFX_WRN_IN_EXECUTION: mfxStatus = 1;
+pub const MFX_WRN_DEVICE_BUSY: mfxStatus = 2;
+pub const MFX_WRN_VIDEO_PARAM_CHANGED: mfxStatus = 3;
+pub const MFX_WRN_PARTIAL_ACCELERATION: mfxStatus = 4;
+pub const MFX_WRN_INCOMPATIBLE_VIDEO_PARAM: mfxStatus = 5;
+pub const MFX_WRN_VALUE_NOT_CHANGED: mfxStatus = 6;
+pub const MFX_WRN_OUT_OF_RANGE: mfxStatus = 7;
+pub const MFX_WRN_FILTER_SKIPPED: mfxStatus = 10;
+pub const MFX_WRN_INCOMPATIBLE_AUDIO_PARAM: mfxStatus = 11;
+pub const MFX_ERR_NONE_PARTIAL_OUTPUT: mfxStatus = 12;
+pub const MFX_TASK_DONE: mfxStatus = 0;
+pub const MFX_TASK_WORKING: mfxStatus = 8;
+pub const MFX_TASK_BUSY: mfxStatus = 9;
+pub const MFX_ERR_MORE_DATA_SUBMIT_TASK: mfxStatus = -10000;
+pub type mfxStatus = ::std::os::raw::c_int;
+pub type mfxIMPL = mfxI32;
+pub const MFX_IMPL_AUTO: _bindgen_ty_1 = 0;
+pub const MFX_IMPL_SOFTWARE: _bindgen_ty_1 = 1;
+pub const MFX_IMPL_HARDWARE: _bindgen_ty_1 = 2;
+pub const MFX_IMPL_AUTO_ANY: _bindgen_ty_1 = 3;
+pub const MFX_IMPL_HARDWARE_ANY: _bindgen_ty_1 = 4;
+pub const MFX_IMPL_HARDWARE2: _bindgen_ty_1 = 5;
+pub const MFX_IMPL_HARDWARE3: _bindgen_ty_1 = 6;
+pub const MFX_IMPL_HARDWARE4: _bindgen_ty_1 = 7;
+pub const MFX_IMPL_RUNTIME: _bindgen_ty_1 = 8;
+pub const MFX_IMPL_VIA_ANY: _bindgen_ty_1 = 256;
+pub const MFX_IMPL_VIA_D3D9: _bindgen_ty_1 = 512;
+pub const MFX_IMPL_VIA_D3D11: _bindgen_ty_1 = 768;
+pub const MFX_IMPL_VIA_VAAPI: _bindgen_ty_1 = 1024;
+pub const MFX_IMPL_AUDIO: _bindgen_ty_1 = 32768;
+pub const MFX_IMPL_UNSUPPORTED: _bindgen_ty_1 = 0;
+pub type _bindgen_ty_1 = ::std::os::raw::c_uint;
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union mfxVersion {
+    pub __bindgen_anon_1: mfxVersion__bindgen_ty_1,
+    pub Version: mfxU32,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mfxVersion__bindgen_ty_1 {
+    pub Minor: mfxU16,
+    pub Major: mfxU16,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _mfxSyncPoint {
+    _unused: [u8; 0],
+}
+pub type mfxSyncPoint = *mut _mfxSyncPoint;
+pub const MFX_GPUCOPY_DEFAULT: _bindgen_ty_2 = 0;
+pub const MFX_GPUCOPY_ON: _bindgen_ty_2 = 1;
+pub const MFX_GPUCOPY_OFF: _bindgen_ty_2 = 2;
+pub type _bindgen_ty_2 = ::std::os::raw::c_uint;
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct mfxInitParam {
+    pub Implementation: mfxIMPL,
+    pub Version: mfxVersion,
+    pub ExternalThreads: mfxU16,
+    pub __bindgen_anon_1: mfxInitParam__bindgen_ty_1,
+    pub NumExtParam: mfxU16,
+    pub GPUCopy: mfxU16,
+    pub reserved: [mfxU16; 21usize],
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union mfxInitParam__bindgen_ty_1 {
+    pub reserved2: mfxU64,
+    pub ExtParam: *mut *mut mfxExtBuffer,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _mfxSession {
+    _unused: [u8; 0],
+}
+pub type mfxSession = *mut _mfxSession;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mfxExtBuffer {
+    pub BufferId: mfxU32,
+    pub BufferSz: mfxU32,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mfxY410 {
+    pub _bitfield_align_1: [u32; 0],
+    pub _bitfield_1: u32,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mfxA2RGB10 {
+    pub _bitfield_align_1: [u32; 0],
+    pub _bitfield_1: u32,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct mfxFrameId {
+    pub TemporalId: mfxU16,
+    pub PriorityId: mfxU16,
+    pub __bindgen_anon_1: mfxFrameId__bindgen_ty_1,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union mfxFrameId__bindgen_ty_1 {
+    pub __bindgen_anon_1: mfxFrameId__bindgen_ty_1__bindgen_ty_1,
+    pub __bindgen_anon_2: mfxFrameId__bindgen_ty_1__bindgen_ty_2,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mfxFrameId__bindgen_ty_1__bindgen_ty_1 {
+    pub DependencyId: mfxU16,
+    pub QualityId: mfxU16,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mfxFrameId__bindgen_ty_1__bindgen_ty_2 {
+    pub ViewId: mfxU16,
+}
+#[repr(C, packed(4))]
+#[derive(Copy, Clone)]
+pub struct mfxFrameInfo {
+    pub reserved: [mfxU32; 4usize],
+    pub reserved4: mfxU16,
+    pub BitDepthLuma: mfxU16,
+    pub BitDepthChroma: mfxU16,
+    pub Shift: mfxU16,
+    pub FrameId: mfxFrameId,
+    pub FourCC: mfxU32,
+    pub __bindgen_anon_1: mfxFrameInfo__bindgen_ty_1,
+    pub FrameRateExtN: mfxU32,
+    pub FrameRateExtD: mfxU32,
+    pub reserved3: mfxU16,
+    pub AspectRatioW: mfxU16,
+    pub AspectRatioH: mfxU16,
+    pub PicStruct: mfxU16,
+    pub ChromaFormat: mfxU16,
+    pub reserved2: mfxU16,
+}
+#[repr(C, packed(4))]
+#[derive(Copy, Clone)]
+pub union mfxFrameInfo__bindgen_ty_1 {
+    pub __bindgen_anon_1: mfxFrameInfo__bindgen_ty_1__bindgen_ty_1,
+    pub __bindgen_anon_2: mfxFrameInfo__bindgen_ty_1__bindgen_ty_2,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mfxFrameInfo__bindgen_ty_1__bindgen_ty_1 {
+    pub Width: mfxU16,
+    pub Height: mfxU16,
+    pub CropX: mfxU16,
+    pub CropY: mfxU16,
+    pub CropW: mfxU16,
+    pub CropH: mfxU16,
+}
+#[repr(C, packed(4))]
+#[derive(Debug, Copy, Clone)]
+pub struct mfxFrameInfo__bindgen_ty_1__bindgen_ty_2 {
+    pub BufferSize: mfxU64,
+    pub reserved5: mfxU32,
+}
+pub const MFX_FOURCC_NV12: _bindgen_ty_3 = 842094158;
+pub const MFX_FOURCC_YV12: _bindgen_ty_3 = 842094169;
+pub const MFX_FOURCC_NV16: _bindgen_ty_3 = 909203022;
+pub const MFX_FOURCC_YUY2: _bindgen_ty_3 = 844715353;
+pub const MFX_FOURCC_RGB565: _bindgen_ty_3 = 2;
+pub const MFX_FOURCC_RGBP: _bindgen_ty_3 = 1346520914;
+pub const MFX_FOURCC_RGB3: _bindgen_ty_3 = 859981650;
+pub const MFX_FOURCC_RGB4: _bindgen_ty_3 = 876021586;
+pub const MFX_FOURCC_P8: _bindgen_ty_3 = 41;
+pub const MFX_FOURCC_P8_TEXTURE: _bindgen_ty_3 = 1412446288;
+pub const MFX_FOURCC_P010: _bindgen_ty_3 = 808530000;
+pub const MFX_FOURCC_P210: _bindgen_ty_3 = 808530512;
+pub const MFX_FOURCC_BGR4: _bindgen_ty_3 = 877807426;
+pub const MFX_FOURCC_A2RGB10: _bindgen_ty_3 = 808535890;
+pub const MFX_FOURCC_ARGB16: _bindgen_ty_3 = 909199186;
+pub const MFX_FOURCC_ABGR16: _bindgen_ty_3 = 909198913;
+pub const MFX_FOURCC_R16: _bindgen_ty_3 = 540422482;
+pub const MFX_FOURCC_AYUV: _bindgen_ty_3 = 1448433985;
+pub const MFX_FOURCC_AYUV_RGB4: _bindgen_ty_3 = 1096238418;
+pub const MFX_FOURCC_UYVY: _bindgen_ty_3 = 1498831189;
+pub const MFX_FOURCC_Y210: _bindgen_ty_3 = 808530521;
+pub const MFX_FOURCC_Y410: _bindgen_ty_3 = 808531033;
+pub type _bindgen_ty_3 = ::std::os::raw::c_uint;
+pub const MFX_PICSTRUCT_UNKNOWN: _bindgen_ty_4 = 0;
+pub const MFX_PICSTRUCT_PROGRESSIVE: _bindgen_ty_4 = 1;
+pub const MFX_PICSTRUCT_FIELD_TFF: _bindgen_ty_4 = 2;
+pub const MFX_PICSTRUCT_FIELD_BFF: _bindgen_ty_4 = 4;
+pub const MFX_PICSTRUCT_FIELD_REPEATED: _bindgen_ty_4 = 16;
+pub const MFX_PICSTRUCT_FRAME_DOUBLING: _bindgen_ty_4 = 32;
+pub const MFX_PICSTRUCT_FRAME_TRIPLING: _bindgen_ty_4 = 64;
+pub const MFX_PICSTRUCT_FIELD_SINGLE: _bindgen_ty_4 = 256;
+pub const MFX_PICSTRUCT_FIELD_TOP: _bindgen_ty_4 = 258;
+pub const MFX_PICSTRUCT_FIELD_BOTTOM: _bindgen_ty_4 = 260;
+pub const MFX_PICSTRUCT_FIELD_PAIRED_PREV: _bindgen_ty_4 = 512;
+pub const MFX_PICSTRUCT_FIELD_PAIRED_NEXT: _bindgen_ty_4 = 1024;
+pub type _bindgen_ty_4 = ::std::os::raw::c_uint;
+pub const MFX_CHROMAFORMAT_MONOCHROME: _bindgen_ty_5 = 0;
+pub const MFX_CHROMAFORMAT_YUV420: _bindgen_ty_5 = 1;
+pub const MFX_CHROMAFORMAT_YUV422: _bindgen_ty_5 = 2;
+pub const MFX_CHROMAFORMAT_YUV444: _bindgen_ty_5 = 3;
+pub const MFX_CHROMAFORMAT_YUV400: _bindgen_ty_5 = 0;
+pub const MFX_CHROMAFORMAT_YUV411: _bindgen_ty_5 = 4;
+pub const MFX_CHROMAFORMAT_YUV422H: _bindgen_ty_5 = 2;
+pub const MFX_CHROMAFORMAT_YUV422V: _bindgen_ty_5 = 5;
+pub const MFX_CHROMAFORMAT_RESERVED1: _bindgen_ty_5 = 6;
+pub type _bindgen_ty_5 = ::std::os::raw::c_uint;
+pub const MFX_TIMESTAMP_UNKNOWN: _bindgen_ty_6 = -1;
+pub type _bindgen_ty_6 = ::std::os::raw::c_int;
+pub const MFX_FRAMEORDER_UNKNOWN: _bindgen_ty_7 = -1;
+pub type _bindgen_ty_7 = ::std::os::raw::c_int;
+pub const MFX_FRAMEDATA_ORIGINAL_TIMESTAMP: _bindgen_ty_8 = 1;
+pub type _bindgen_ty_8 = ::std::os::raw::c_uint;
+pub const MFX_CORRUPTION_MINOR: _bindgen_ty_9 = 1;
+pub const MFX_CORRUPTION_MAJOR: _bindgen_ty_9 = 2;
+pub const MFX_CORRUPTION_ABSENT_TOP_FIELD: _bindgen_ty_9 = 4;
+pub const MFX_CORRUPTION_ABSENT_BOTTOM_FIELD: _bindgen_ty_9 = 8;
+pub const MFX_CORRUPTION_REFERENCE_FRAME: _bindgen_ty_9 = 16;
+pub const MFX_CORRUPTION_REFERENCE_LIST: _bindgen_ty_9 = 32;
+pub type _bindgen_ty_9 = ::std::os::raw::c_uint;
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct mfxFrameData {
+    pub __bindgen_anon_1: mfxFrameData__bindgen_ty_1,
+    pub NumExtParam: mfxU16,
+    pub reserved: [mfxU16; 9usize],
+    pub MemType: mfxU16,
+    pub PitchHigh: mfxU16,
+    pub TimeStamp: mfxU64,
+    pub FrameOrder: mfxU32,
+    pub Locked: mfxU16,
+    pub __bindgen_anon_2: mfxFrameData__bindgen_ty_2,
+    pub __bindgen_anon_3: mfxFrameData__bindgen_ty_3,
+    pub __bindgen_anon_4: mfxFrameData__bindgen_ty_4,
+    pub __bindgen_anon_5: mfxFrameData__bindgen_ty_5,
+    pub A: *mut mfxU8,
+    pub MemId: mfxMemId,
+    pub Corrupted: mfxU16,
+    pub DataFlag: mfxU16,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union mfxFrameData__bindgen_ty_1 {
+    pub ExtParam: *mut *mut mfxExtBuffer,
+    pub reserved2: mfxU64,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union mfxFrameData__bindgen_ty_2 {
+    pub Pitch: mfxU16,
+    pub PitchLow: mfxU16,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union mfxFrameData__bindgen_ty_3 {
+    pub Y410: *mut mfxY410,
+    pub Y16: *mut mfxU16,
+    pub Y: *mut mfxU8,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union mfxFrameData__bindgen_ty_4 {
+    pub UV: *mut mfxU8,
+    pub VU: *mut mfxU8,
+    pub CbCr: *mut mfxU8,
+    pub CrCb: *mut mfxU8,
+    pub Cb: *mut mfxU8,
+    pub U: *mut mfxU8,
+    pub U16: *mut mfxU16,
+    pub G: *mut mfxU8,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union mfxFrameData__bindgen_ty_5 {
+    pub Cr: *mut mfxU8,
+    pub V: *mut mfxU8,
+    pub B: *mut mfxU8,
+    pub A2RGB10: *mut mfxA2RGB10,
+    pub V16: *mut mfxU16,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct mfxFrameSurface1 {
+    pub reserved: [mfxU32; 4usize],
+    pub Info: mfxFrameInfo,
+    pub Data: mfxFrameData,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct mfxInfoMFX {
+    pub reserved: [mfxU32; 7usize],
+    pub LowPower: mfxU16,
+    pub BRCParamMultiplier: mfxU16,
+    pub FrameInfo: mfxFrameInfo,
+    pub CodecId: mfxU32,
+    pub CodecProfile: mfxU16,
+    pub CodecLevel: mfxU16,
+    pub NumThread: mfxU16,
+    pub __bindgen_anon_1: mfxInfoMFX__bindgen_ty_1,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union mfxInfoMFX__bindgen_ty_1 {
+    pub __bindgen_anon_1: mfxInfoMFX__bindgen_ty_1__bindgen_ty_1,
+    pub __bindgen_anon_2: mfxInfoMFX__bindgen_ty_1__bindgen_ty_2,
+    pub __bindgen_anon_3: mfxInfoMFX__bindgen_ty_1__bindgen_ty_3,
+    pub __bindgen_anon_4: mfxInfoMFX__bindgen_ty_1__bindgen_ty_4,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct mfxInfoMFX__bindgen_ty_1__bindgen_ty_1 {
+    pub TargetUsage: mfxU16,
+    pub GopPicSize: mfxU16,
+    pub GopRefDist: mfxU16,
+    pub GopOptFlag: mfxU16,
+    pub IdrInterval: mfxU16,
+    pub RateControlMethod: mfxU16,
+    pub __bindgen_anon_1: mfxInfoMFX__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1,
+    pub BufferSizeInKB: mfxU16,
+    pub __bindgen_anon_2: mfxInfoMFX__bindgen_ty_1__bindgen_ty_1__bindgen_ty_2,
+    pub __bindgen_anon_3: mfxInfoMFX__bindgen_ty_1__bindgen_ty_1__bindgen_ty_3,
+    pub NumSlice: mfxU16,
+    pub NumRefFrame: mfxU16,
+    pub EncodedOrder: mfxU16,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union mfxInfoMFX__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1 {
+    pub InitialDelayInKB: mfxU16,
+    pub QPI: mfxU16,
+    pub Accuracy: mfxU16,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union mfxInfoMFX__bindgen_ty_1__bindgen_ty_1__bindgen_ty_2 {
+    pub TargetKbps: mfxU16,
+    pub QPP: mfxU16,
+    pub ICQQuality: mfxU16,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union mfxInfoMFX__bindgen_ty_1__bindgen_ty_1__bindgen_ty_3 {
+    pub MaxKbps: mfxU16,
+    pub QPB: mfxU16,
+    pub Convergence: mfxU16,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mfxInfoMFX__bindgen_ty_1__bindgen_ty_2 {
+    pub DecodedOrder: mfxU16,
+    pub ExtendedPicStruct: mfxU16,
+    pub TimeStampCalc: mfxU16,
+    pub SliceGroupsPresent: mfxU16,
+    pub MaxDecFrameBuffering: mfxU16,
+    pub EnableReallocRequest: mfxU16,
+    pub reserved2: [mfxU16; 7usize],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mfxInfoMFX__bindgen_ty_1__bindgen_ty_3 {
+    pub JPEGChromaFormat: mfxU16,
+    pub Rotation: mfxU16,
+    pub JPEGColorFormat: mfxU32,
+    pub InterleavedDec: mfxU16,
+    pub SamplingFactorH: [mfxU8; 4usize],
+    pub SamplingFactorV: [mfxU8; 4usize],
+    pub reserved3: [mfxU16; 5usize],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mfxInfoMFX__bindgen_ty_1__bindgen_ty_4 {
+    pub Interleaved: mfxU16,
+    pub Quality: mfxU16,
+    pub RestartInterval: mfxU16,
+    pub reserved5: [mfxU16; 10usize],
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct mfxInfoVPP {
+    pub reserved: [mfxU32; 8usize],
+    pub In: mfxFrameInfo,
+    pub Out: mfxFrameInfo,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct mfxVideoParam {
+    pub AllocId: mfxU32,
+    pub reserved: [mfxU32; 2usize],
+    pub reserved3: mfxU16,
+    pub AsyncDepth: mfxU16,
+    pub __bindgen_anon_1: mfxVideoParam__bindgen_ty_1,
+    pub Protected: mfxU16,
+    pub IOPattern: mfxU16,
+    pub ExtParam: *mut *mut mfxExtBuffer,
+    pub NumExtParam: mfxU16,
+    pub reserved2: mfxU16,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union mfxVideoParam__bindgen_ty_1 {
+    pub mfx: mfxInfoMFX,
+    pub vpp: mfxInfoVPP,
+}
+pub const MFX_IOPATTERN_IN_VIDEO_MEMORY: _bindgen_ty_10 = 1;
+pub const MFX_IOPATTERN_IN_SYSTEM_MEMORY: _bindgen_ty_10 = 2;
+pub const MFX_IOPATTERN_IN_OPAQUE_MEMORY: _bindgen_ty_10 = 4;
+pub const MFX_IOPATTERN_OUT_VIDEO_MEMORY: _bindgen_ty_10 = 16;
+pub const MFX_IOPATTERN_OUT_SYSTEM_MEMORY: _bindgen_ty_10 = 32;
+pub const MFX_IOPATTERN_OUT_OPAQUE_MEMORY: _bindgen_ty_10 = 64;
+pub type _bindgen_ty_10 = ::std::os::raw::c_uint;
+pub const MFX_CODEC_AVC: _bindgen_ty_11 = 541283905;
+pub const MFX_CODEC_HEVC: _bindgen_ty_11 = 1129727304;
+pub const MFX_CODEC_MPEG2: _bindgen_ty_11 = 843534413;
+pub const MFX_CODEC_VC1: _bindgen_ty_11 = 540099414;
+pub const MFX_CODEC_CAPTURE: _bindgen_ty_11 = 1414547779;
+pub const MFX_CODEC_VP9: _bindgen_ty_11 = 540102742;
+pub const MFX_CODEC_AV1: _bindgen_ty_11 = 540104257;
+pub type _bindgen_ty_11 = ::std::os::raw::c_uint;
+pub const MFX_CODEC_JPEG: _bindgen_ty_12 = 1195724874;
+pub type _bindgen_ty_12 = ::std::os::raw::c_uint;
+pub const MFX_TARGETUSAGE_1: _bindgen_ty_13 = 1;
+pub const MFX_TARGETUSAGE_2: _bindgen_ty_13 = 2;
+pub const MFX_TARGETUSAGE_3: _bindgen_ty_13 = 3;
+pub const MFX_TARGETUSAGE_4: _bindgen_ty_13 = 4;
+pub const MFX_TARGETUSAGE_5: _bindgen_ty_13 = 5;
+pub const MFX_TARGETUSAGE_6: _bindgen_ty_13 = 6;
+pub const MFX_TARGETUSAGE_7: _bindgen_ty_13 = 7;
+pub const MFX_TARGETUSAGE_UNKNOWN: _bindgen_ty_13 = 0;
+pub const MFX_TARGETUSAGE_BEST_QUALITY: _bindgen_ty_13 = 1;
+pub const MFX_TARGETUSAGE_BALANCED: _bindgen_ty_13 = 4;
+pub const MFX_TARGETUSAGE_BEST_SPEED: _bindgen_ty_13 = 7;
+pub type _bindgen_ty_13 = ::std::os::raw::c_uint;
+pub const MFX_RATECONTROL_CBR: _bindgen_ty_14 = 1;
+pub const MFX_RATECONTROL_VBR: _bindgen_ty_14 = 2;
+pub const MFX_RATECONTROL_CQP: _bindgen_ty_14 = 3;
+pub const MFX_RATECONTROL_AVBR: _bindgen_ty_14 = 4;
+pub const MFX_RATECONTROL_RESERVED1: _bindgen_ty_14 = 5;
+pub const MFX_RATECONTROL_RESERVED2: _bindgen_ty_14 = 6;
+pub const MFX_RATECONTROL_RESERVED3: _bindgen_ty_14 = 100;
+pub const MFX_RATECONTROL_RESERVED4: _bindgen_ty_14 = 7;
+pub const MFX_RATECONTROL_LA: _bindgen_ty_14 = 8;
+pub const MFX_RATECONTROL_ICQ: _bindgen_ty_14 = 9;
+pub const MFX_RATECONTROL_VCM: _bindgen_ty_14 = 10;
+pub const MFX_RATECONTROL_LA_ICQ: _bindgen_ty_14 = 11;
+pub const MFX_RATECONTROL_LA_EXT: _bindgen_ty_14 = 12;
+pub const MFX_RATECONTROL_LA_HRD: _bindgen_ty_14 = 13;
+pub const MFX_RATECONTROL_QVBR: _bindgen_ty_14 = 14;
+pub type _bindgen_ty_14 = ::std::os::raw::c_uint;
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct mfxFrameAllocRequest {
+    pub __bindgen_anon_1: mfxFrameAllocRequest__bindgen_ty_1,
+    pub reserved3: [mfxU32; 3usize],
+    pub Info: mfxFrameInfo,
+    pub Type: mfxU16,
+    pub NumFrameMin: mfxU16,
+    pub NumFrameSuggested: mfxU16,
+    pub reserved2: mfxU16,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union mfxFrameAllocRequest__bindgen_ty_1 {
+    pub AllocId: mfxU32,
+    pub reserved: [mfxU32; 1usize],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _mfxEncryptedData {
+    _unused: [u8; 0],
+}
+pub type mfxEncryptedData = _mfxEncryptedData;
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct mfxBitstream {
+    pub __bindgen_anon_1: mfxBitstream__bindgen_ty_1,
+    pub DecodeTimeStamp: mfxI64,
+    pub TimeStamp: mfxU64,
+    pub Data: *mut mfxU8,
+    pub DataOffset: mfxU32,
+    pub DataLength: mfxU32,
+    pub MaxLength: mfxU32,
+    pub PicStruct: mfxU16,
+    pub FrameType: mfxU16,
+    pub DataFlag: mfxU16,
+    pub reserved2: mfxU16,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union mfxBitstream__bindgen_ty_1 {
+    pub __bindgen_anon_1: mfxBitstream__bindgen_ty_1__bindgen_ty_1,
+    pub reserved: [mfxU32; 6usize],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mfxBitstream__bindgen_ty_1__bindgen_ty_1 {
+    pub EncryptedData: *mut mfxEncryptedData,
+    pub ExtParam: *mut *mut mfxExtBuffer,
+    pub NumExtParam: mfxU16,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mfxPayload {
+    pub CtrlFlags: mfxU32,
+    pub reserved: [mfxU32; 3usize],
+    pub Data: *mut mfxU8,
+    pub NumBit: mfxU32,
+    pub Type: mfxU16,
+    pub BufSize: mfxU16,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mfxEncodeCtrl {
+    pub Header: mfxExtBuffer,
+    pub reserved: [mfxU32; 5usize],
+    pub MfxNalUnitType: mfxU16,
+    pub SkipFrame: mfxU16,
+    pub QP: mfxU16,
+    pub FrameType: mfxU16,
+    pub NumExtParam: mfxU16,
+    pub NumPayload: mfxU16,
+    pub reserved2: mfxU16,
+    pub ExtParam: *mut *mut mfxExtBuffer,
+    pub Payload: *mut *mut mfxPayload,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct mfxExtVppAuxData {
+    pub Header: mfxExtBuffer,
+    pub __bindgen_anon_1: mfxExtVppAuxData__bindgen_ty_1,
+    pub SceneChangeRate: mfxU16,
+    pub RepeatedFrame: mfxU16,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union mfxExtVppAuxData__bindgen_ty_1 {
+    pub __bindgen_anon_1: mfxExtVppAuxData__bindgen_ty_1__bindgen_ty_1,
+    pub __bindgen_anon_2: mfxExtVppAuxData__bindgen_ty_1__bindgen_ty_2,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mfxExtVppAuxData__bindgen_ty_1__bindgen_ty_1 {
+    pub SpatialComplexity: mfxU32,
+    pub TemporalComplexity: mfxU32,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mfxExtVppAuxData__bindgen_ty_1__bindgen_ty_2 {
+    pub PicStruct: mfxU16,
+    pub reserved: [mfxU16; 3usize],
+}
+pub const MFX_CODINGOPTION_UNKNOWN: _bindgen_ty_15 = 0;
+pub const MFX_CODINGOPTION_ON: _bindgen_ty_15 = 16;
+pub const MFX_CODINGOPTION_OFF: _bindgen_ty_15 = 32;
+pub const MFX_CODINGOPTION_ADAPTIVE: _bindgen_ty_15 = 48;
+pub type _bindgen_ty_15 = ::std::os::raw::c_uint;
+pub const MFX_EXTBUFF_CODING_OPTION: _bindgen_ty_16 = 1347372099;
+pub const MFX_EXTBUFF_CODING_OPTION2: _bindgen_ty_16 = 844055619;
+pub const MFX_EXTBUFF_CODING_OPTION3: _bindgen_ty_16 = 860832835;
+pub const MFX_EXTBUFF_CODING_OPTION_SPSPPS: _bindgen_ty_16 = 1347637059;
+pub const MFX_EXTBUFF_VIDEO_SIGNAL_INFO: _bindgen_ty_16 = 1313428310;
+pub const MFX_EXTBUFF_ENCODER_RESET_OPTION: _bindgen_ty_16 = 1330794053;
+pub type _bindgen_ty_16 = ::std::os::raw::c_uint;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mfxExtCodingOption {
+    pub Header: mfxExtBuffer,
+    pub reserved1: mfxU16,
+    pub RateDistortionOpt: mfxU16,
+    pub MECostType: mfxU16,
+    pub MESearchType: mfxU16,
+    pub MVSearchWindow: mfxI16Pair,
+    pub EndOfSequence: mfxU16,
+    pub FramePicture: mfxU16,
+    pub CAVLC: mfxU16,
+    pub reserved2: [mfxU16; 2usize],
+    pub RecoveryPointSEI: mfxU16,
+    pub ViewOutput: mfxU16,
+    pub NalHrdConformance: mfxU16,
+    pub SingleSeiNalUnit: mfxU16,
+    pub VuiVclHrdParameters: mfxU16,
+    pub RefPicListReordering: mfxU16,
+    pub ResetRefList: mfxU16,
+    pub RefPicMarkRep: mfxU16,
+    pub FieldOutput: mfxU16,
+    pub IntraPredBlockSize: mfxU16,
+    pub InterPredBlockSize: mfxU16,
+    pub MVPrecision: mfxU16,
+    pub MaxDecFrameBuffering: mfxU16,
+    pub AUDelimiter: mfxU16,
+    pub EndOfStream: mfxU16,
+    pub PicTimingSEI: mfxU16,
+    pub VuiNalHrdParameters: mfxU16,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mfxExtCodingOption2 {
+    pub Header: mfxExtBuffer,
+    pub IntRefType: mfxU16,
+    pub IntRefCycleSize: mfxU16,
+    pub IntRefQPDelta: mfxI16,
+    pub MaxFrameSize: mfxU32,
+    pub MaxSliceSize: mfxU32,
+    pub BitrateLimit: mfxU16,
+    pub MBBRC: mfxU16,
+    pub ExtBRC: mfxU16,
+    pub LookAheadDepth: mfxU16,
+    pub Trellis: mfxU16,
+    pub RepeatPPS: mfxU16,
+    pub BRefType: mfxU16,
+    pub AdaptiveI: mfxU16,
+    pub AdaptiveB: mfxU16,
+    pub LookAheadDS: mfxU16,
+    pub NumMbPerSlice: mfxU16,
+    pub SkipFrame: mfxU16,
+    pub MinQPI: mfxU8,
+    pub MaxQPI: mfxU8,
+    pub MinQPP: mfxU8,
+    pub MaxQPP: mfxU8,
+    pub MinQPB: mfxU8,
+    pub MaxQPB: mfxU8,
+    pub FixedFrameRate: mfxU16,
+    pub DisableDeblockingIdc: mfxU16,
+    pub DisableVUI: mfxU16,
+    pub BufferingPeriodSEI: mfxU16,
+    pub EnableMAD: mfxU16,
+    pub UseRawRef: mfxU16,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct mfxExtCodingOption3 {
+    pub Header: mfxExtBuffer,
+    pub NumSliceI: mfxU16,
+    pub NumSliceP: mfxU16,
+    pub NumSliceB: mfxU16,
+    pub WinBRCMaxAvgKbps: mfxU16,
+    pub WinBRCSize: mfxU16,
+    pub QVBRQuality: mfxU16,
+    pub EnableMBQP: mfxU16,
+    pub IntRefCycleDist: mfxU16,
+    pub DirectBiasAdjustment: mfxU16,
+    pub GlobalMotionBiasAdjustment: mfxU16,
+    pub MVCostScalingFactor: mfxU16,
+    pub MBDisableSkipMap: mfxU16,
+    pub WeightedPred: mfxU16,
+    pub WeightedBiPred: mfxU16,
+    pub AspectRatioInfoPresent: mfxU16,
+    pub OverscanInfoPresent: mfxU16,
+    pub OverscanAppropriate: mfxU16,
+    pub TimingInfoPresent: mfxU16,
+    pub BitstreamRestriction: mfxU16,
+    pub LowDelayHrd: mfxU16,
+    pub MotionVectorsOverPicBoundaries: mfxU16,
+    pub reserved1: [mfxU16; 2usize],
+    pub ScenarioInfo: mfxU16,
+    pub ContentInfo: mfxU16,
+    pub PRefType: mfxU16,
+    pub FadeDetection: mfxU16,
+    pub reserved2: [mfxU16; 2usize],
+    pub GPB: mfxU16,
+    pub MaxFrameSizeI: mfxU32,
+    pub MaxFrameSizeP: mfxU32,
+    pub reserved3: [mfxU32; 3usize],
+    pub EnableQPOffset: mfxU16,
+    pub QPOffset: [mfxI16; 8usize],
+    pub NumRefActiveP: [mfxU16; 8usize],
+    pub NumRefActiveBL0: [mfxU16; 8usize],
+    pub NumRefActiveBL1: [mfxU16; 8usize],
+    pub reserved6: mfxU16,
+    pub TransformSkip: mfxU16,
+    pub TargetChromaFormatPlus1: mfxU16,
+    pub TargetBitDepthLuma: mfxU16,
+    pub TargetBitDepthChroma: mfxU16,
+    pub BRCPanicMode: mfxU16,
+    pub LowDelayBRC: mfxU16,
+    pub EnableMBForceIntra: mfxU16,
+    pub AdaptiveMaxFrameSize: mfxU16,
+    pub RepartitionCheckEnable: mfxU16,
+    pub reserved5: [mfxU16; 3usize],
+    pub EncodedUnitsInfo: mfxU16,
+    pub EnableNalUnitType: mfxU16,
+    pub ExtBrcAdaptiveLTR: mfxU16,
+    pub reserved: [mfxU16; 163usize],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mfxExtCodingOptionSPSPPS {
+    pub Header: mfxExtBuffer,
+    pub SPSBuffer: *mut mfxU8,
+    pub PPSBuffer: *mut mfxU8,
+    pub SPSBufSize: mfxU16,
+    pub PPSBufSize: mfxU16,
+    pub SPSId: mfxU16,
+    pub PPSId: mfxU16,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mfxExtVideoSignalInfo {
+    pub Header: mfxExtBuffer,
+    pub VideoFormat: mfxU16,
+    pub VideoFullRange: mfxU16,
+    pub ColourDescriptionPresent: mfxU16,
+    pub ColourPrimaries: mfxU16,
+    pub TransferCharacteristics: mfxU16,
+    pub MatrixCoefficients: mfxU16,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mfxExtEncoderResetOption {
+    pub Header: mfxExtBuffer,
+    pub StartNewSequence: mfxU16,
+    pub reserved: [mfxU16; 11usize],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mfxExtAVCEncodedFrameInfo {
+    pub Header: mfxExtBuffer,
+    pub FrameOrder: mfxU32,
+    pub PicStruct: mfxU16,
+    pub LongTermIdx: mfxU16,
+    pub MAD: mfxU32,
+    pub BRCPanicMode: mfxU16,
+    pub QP: mfxU16,
+    pub SecondFieldOffset: mfxU32,
+    pub reserved: [mfxU16; 2usize],
+    pub UsedRefListL0: [mfxExtAVCEncodedFrameInfo__bindgen_ty_1; 32usize],
+    pub UsedRefListL1: [mfxExtAVCEncodedFrameInfo__bindgen_ty_1; 32usize],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mfxExtAVCEncodedFrameInfo__bindgen_ty_1 {
+    pub FrameOrder: mfxU32,
+    pub PicStruct: mfxU16,
+    pub LongTermIdx: mfxU16,
+    pub reserved: [mfxU16; 4usize],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mfxFrameAllocResponse {
+    pub AllocId: mfxU32,
+    pub reserved: [mfxU32; 3usize],
+    pub mids: *mut mfxMemId,
+    pub NumFrameActual: mfxU16,
+    pub reserved2: mfxU16,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mfxFrameAllocator {
+    pub reserved: [mfxU32; 4usize],
+    pub pthis: mfxHDL,
+    pub Alloc: ::std::option::Option<
+        unsafe extern "C" fn(
+            pthis: mfxHDL,
+            request: *mut mfxFrameAllocRequest,
+            response: *mut mfxFrameAllocResponse,
+        ) -> mfxStatus,
+    >,
+    pub Lock: ::std::option::Option<
+        unsafe extern "C" fn(pthis: mfxHDL, mid: mfxMemId, ptr: *mut mfxFrameData) -> mfxStatus,
+    >,
+    pub Unlock: ::std::option::Option<
+        unsafe extern "C" fn(pthis: mfxHDL, mid: mfxMemId, ptr: *mut mfxFrameData) -> mfxStatus,
+    >,
+    pub GetHDL: ::std::option::Option<
+        unsafe extern "C" fn(pthis: mfxHDL, mid: mfxMemId, handle: *mut mfxHDL) -> mfxStatus,
+    >,
+    pub Free: ::std::option::Option<
+        unsafe extern "C" fn(pthis: mfxHDL, response: *mut mfxFrameAllocResponse) -> mfxStatus,
+    >,
+}
+extern "C" {
+    pub fn MFXInit(impl_: mfxIMPL, ver: *mut mfxVersion, session: *mut mfxSession) -> mfxStatus;
+}
+extern "C" {
+    pub fn MFXInitEx(par: mfxInitParam, session: *mut mfxSession) -> mfxStatus;
+}
+extern "C" {
+    pub fn MFXClose(session: mfxSession) -> mfxStatus;
+}
+extern "C" {
+    pub fn MFXQueryIMPL(session: mfxSession, impl_: *mut mfxIMPL) -> mfxStatus;
+}
+extern "C" {
+    pub fn MFXQueryVersion(session: mfxSession, version: *mut mfxVersion) -> mfxStatus;
+}
+extern "C" {
+    pub fn MFXVideoCORE_SetFrameAllocator(
+        session: mfxSession,
+        allocator: *mut mfxFrameAllocator,
+    ) -> mfxStatus;
+}
+extern "C" {
+    pub fn MFXVideoCORE_SyncOperation(
+        session: mfxSession,
+        syncp: mfxSyncPoint,
+        wait: mfxU32,
+    ) -> mfxStatus;
+}
+extern "C" {
+    pub fn MFXVideoENCODE_Query(
+        session: mfxSession,
+        in_: *mut mfxVideoParam,
+        out: *mut mfxVideoParam,
+    ) -> mfxStatus;
+}
+extern "C" {
+    pub fn MFXVideoENCODE_QueryIOSurf(
+        session: mfxSession,
+        par: *mut mfxVideoParam,
+        request: *mut mfxFrameAllocRequest,
+    ) -> mfxStatus;
+}
+extern "C" {
+    pub fn MFXVideoENCODE_Init(session: mfxSession, par: *mut mfxVideoParam) -> mfxStatus;
+}
+extern "C" {
+    pub fn MFXVideoENCODE_Reset(session: mfxSession, par: *mut mfxVideoParam) -> mfxStatus;
+}
+extern "C" {
+    pub fn MFXVideoENCODE_Close(session: mfxSession) -> mfxStatus;
+}
+extern "C" {
+    pub fn MFXVideoENCODE_GetVideoParam(session: mfxSession, par: *mut mfxVideoParam) -> mfxStatus;
+}
+extern "C" {
+    pub fn MFXVideoENCODE_EncodeFrameAsync(
+        session: mfxSession,
+        ctrl: *mut mfxEncodeCtrl,
+        surface: *mut mfxFrameSurface1,
+        bs: *mut mfxBitstream,
+        syncp: *mut mfxSyncPoint,
+    ) -> mfxStatus;
+}
+extern "C" {
+    pub fn MFXVideoVPP_Query(
+        session: mfxSession,
+        in_: *mut mfxVideoParam,
+        out: *mut mfxVideoParam,
+    ) -> mfxStatus;
+}
+extern "C" {
+    pub fn MFXVideoVPP_QueryIOSurf(
+        session: mfxSession,
+        par: *mut mfxVideoParam,
+        request: *mut mfxFrameAllocRequest,
+    ) -> mfxStatus;
+}
+extern "C" {
+    pub fn MFXVideoVPP_Init(session: mfxSession, par: *mut mfxVideoParam) -> mfxStatus;
+}
+extern "C" {
+    pub fn MFXVideoVPP_Reset(session: mfxSession, par: *mut mfxVideoParam) -> mfxStatus;
+}
+extern "C" {
+    pub fn MFXVideoVPP_Close(session: mfxSession) -> mfxStatus;
+}
+extern "C" {
+    pub fn MFXVideoVPP_GetVideoParam(session: mfxSession, par: *mut mfxVideoParam) -> mfxStatus;
+}
+extern "C" {
+    pub fn MFXVideoVPP_RunFrameVPPAsync(
+        session: mfxSession,
+        in_: *mut mfxFrameSurface1,
+        out: *mut mfxFrameSurface1,
+        aux: *mut mfxExtVppAuxData,
+        syncp: *mut mfxSyncPoint,
+    ) -> mfxStatus;
+}
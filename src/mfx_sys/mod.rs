@@ -0,0 +1,359 @@
+//! Raw bindings generated by bindgen from the installed SDK headers (`mfxvideo.h`, `mfxstructures.h` and `mfxvstructures.h`), see `build.rs`.
+//!
+//! With the `prebuilt-bindings` feature the checked-in `bindings.rs` is used instead and neither pkg-config nor bindgen run. That file is maintained by hand and only holds the items the crate uses: to add one, copy the item from the output of `scripts/generate-bindings.sh`, which runs bindgen on the API 1.27 headers the file was taken from.
+//!
+//! The rest of the crate uses the documented structures in [`crate::structures`]; the tests below check that their sizes and field offsets match the generated ones. They only run without `prebuilt-bindings`: the checked-in file is a transcription too, so comparing against it would not check anything against the installed SDK. CI runs them against the headers downloaded by `scripts/mfx-headers.sh`.
+#![allow(non_upper_case_globals, dead_code, clashing_extern_declarations, clippy::all)]
+
+#[cfg(feature = "prebuilt-bindings")]
+include!("bindings.rs");
+#[cfg(not(feature = "prebuilt-bindings"))]
+include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+
+#[cfg(all(test, not(feature = "prebuilt-bindings")))]
+mod test {
+    use std::mem::{offset_of, size_of};
+
+    use crate::structures;
+
+    macro_rules! assert_size {
+        ($name:ident) => {
+            assert_eq!(
+                size_of::<structures::$name>(),
+                size_of::<super::$name>(),
+                "size of {}",
+                stringify!($name)
+            );
+        };
+    }
+
+    macro_rules! assert_offset {
+        ($name:ident, $field:ident) => {
+            assert_offset!($name, $field, $field);
+        };
+        ($name:ident, ($($field:ident).+), $($sys_field:ident).+) => {
+            assert_eq!(
+                offset_of!(structures::$name, $($field).+),
+                offset_of!(super::$name, $($sys_field).+),
+                "offset of {}::{}",
+                stringify!($name),
+                stringify!($($field).+)
+            );
+        };
+        ($name:ident, $field:ident, $($sys_field:ident).+) => {
+            assert_offset!($name, ($field), $($sys_field).+);
+        };
+    }
+
+    #[test]
+    fn mfxVersion() {
+        assert_size!(mfxVersion);
+        assert_offset!(mfxVersion, Minor, __bindgen_anon_1.Minor);
+        assert_offset!(mfxVersion, Major, __bindgen_anon_1.Major);
+    }
+
+    #[test]
+    fn mfxFrameId() {
+        assert_size!(mfxFrameId);
+        assert_offset!(mfxFrameId, TemporalId);
+        assert_offset!(mfxFrameId, PriorityId);
+        assert_offset!(mfxFrameId, u, __bindgen_anon_1);
+    }
+
+    #[test]
+    fn mfxFrameInfo() {
+        assert_size!(mfxFrameInfo);
+        assert_offset!(mfxFrameInfo, BitDepthLuma);
+        assert_offset!(mfxFrameInfo, BitDepthChroma);
+        assert_offset!(mfxFrameInfo, Shift);
+        assert_offset!(mfxFrameInfo, FrameId);
+        assert_offset!(mfxFrameInfo, FourCC);
+        assert_offset!(mfxFrameInfo, Width, __bindgen_anon_1.__bindgen_anon_1.Width);
+        assert_offset!(mfxFrameInfo, Height, __bindgen_anon_1.__bindgen_anon_1.Height);
+        assert_offset!(mfxFrameInfo, CropX, __bindgen_anon_1.__bindgen_anon_1.CropX);
+        assert_offset!(mfxFrameInfo, CropY, __bindgen_anon_1.__bindgen_anon_1.CropY);
+        assert_offset!(mfxFrameInfo, CropW, __bindgen_anon_1.__bindgen_anon_1.CropW);
+        assert_offset!(mfxFrameInfo, CropH, __bindgen_anon_1.__bindgen_anon_1.CropH);
+        assert_offset!(mfxFrameInfo, FrameRateExtN);
+        assert_offset!(mfxFrameInfo, FrameRateExtD);
+        assert_offset!(mfxFrameInfo, AspectRatioW);
+        assert_offset!(mfxFrameInfo, AspectRatioH);
+        assert_offset!(mfxFrameInfo, PicStruct);
+        assert_offset!(mfxFrameInfo, ChromaFormat);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn mfxInfoMFX() {
+        assert_size!(mfxInfoMFX);
+        assert_offset!(mfxInfoMFX, LowPower);
+        assert_offset!(mfxInfoMFX, BRCParamMultiplier);
+        assert_offset!(mfxInfoMFX, FrameInfo);
+        assert_offset!(mfxInfoMFX, CodecId);
+        assert_offset!(mfxInfoMFX, CodecProfile);
+        assert_offset!(mfxInfoMFX, CodecLevel);
+        assert_offset!(mfxInfoMFX, NumThread);
+        assert_offset!(mfxInfoMFX, u, __bindgen_anon_1);
+        assert_offset!(mfxInfoMFX, (u.encode.TargetUsage), __bindgen_anon_1.__bindgen_anon_1.TargetUsage);
+        assert_offset!(mfxInfoMFX, (u.encode.GopPicSize), __bindgen_anon_1.__bindgen_anon_1.GopPicSize);
+        assert_offset!(mfxInfoMFX, (u.encode.GopRefDist), __bindgen_anon_1.__bindgen_anon_1.GopRefDist);
+        assert_offset!(mfxInfoMFX, (u.encode.GopOptFlag), __bindgen_anon_1.__bindgen_anon_1.GopOptFlag);
+        assert_offset!(mfxInfoMFX, (u.encode.IdrInterval), __bindgen_anon_1.__bindgen_anon_1.IdrInterval);
+        assert_offset!(mfxInfoMFX, (u.encode.RateControlMethod), __bindgen_anon_1.__bindgen_anon_1.RateControlMethod);
+        assert_offset!(mfxInfoMFX, (u.encode.u1), __bindgen_anon_1.__bindgen_anon_1.__bindgen_anon_1);
+        assert_offset!(mfxInfoMFX, (u.encode.BufferSizeInKB), __bindgen_anon_1.__bindgen_anon_1.BufferSizeInKB);
+        assert_offset!(mfxInfoMFX, (u.encode.u2), __bindgen_anon_1.__bindgen_anon_1.__bindgen_anon_2);
+        assert_offset!(mfxInfoMFX, (u.encode.u3), __bindgen_anon_1.__bindgen_anon_1.__bindgen_anon_3);
+        assert_offset!(mfxInfoMFX, (u.encode.NumSlice), __bindgen_anon_1.__bindgen_anon_1.NumSlice);
+        assert_offset!(mfxInfoMFX, (u.encode.NumRefFrame), __bindgen_anon_1.__bindgen_anon_1.NumRefFrame);
+        assert_offset!(mfxInfoMFX, (u.encode.EncodedOrder), __bindgen_anon_1.__bindgen_anon_1.EncodedOrder);
+
+        assert_offset!(mfxInfoMFX, (u.decode.DecodedOrder), __bindgen_anon_1.__bindgen_anon_2.DecodedOrder);
+        assert_offset!(mfxInfoMFX, (u.decode.ExtendedPicStruct), __bindgen_anon_1.__bindgen_anon_2.ExtendedPicStruct);
+        assert_offset!(mfxInfoMFX, (u.decode.TimeStampCalc), __bindgen_anon_1.__bindgen_anon_2.TimeStampCalc);
+        assert_offset!(mfxInfoMFX, (u.decode.SliceGroupsPresent), __bindgen_anon_1.__bindgen_anon_2.SliceGroupsPresent);
+        assert_offset!(mfxInfoMFX, (u.decode.MaxDecFrameBuffering), __bindgen_anon_1.__bindgen_anon_2.MaxDecFrameBuffering);
+        assert_offset!(mfxInfoMFX, (u.decode.EnableReallocRequest), __bindgen_anon_1.__bindgen_anon_2.EnableReallocRequest);
+
+        assert_offset!(mfxInfoMFX, (u.jpeg_decode.JPEGChromaFormat), __bindgen_anon_1.__bindgen_anon_3.JPEGChromaFormat);
+        assert_offset!(mfxInfoMFX, (u.jpeg_decode.Rotation), __bindgen_anon_1.__bindgen_anon_3.Rotation);
+        assert_offset!(mfxInfoMFX, (u.jpeg_decode.JPEGColorFormat), __bindgen_anon_1.__bindgen_anon_3.JPEGColorFormat);
+        assert_offset!(mfxInfoMFX, (u.jpeg_decode.InterleavedDec), __bindgen_anon_1.__bindgen_anon_3.InterleavedDec);
+        assert_offset!(mfxInfoMFX, (u.jpeg_decode.SamplingFactorH), __bindgen_anon_1.__bindgen_anon_3.SamplingFactorH);
+        assert_offset!(mfxInfoMFX, (u.jpeg_decode.SamplingFactorV), __bindgen_anon_1.__bindgen_anon_3.SamplingFactorV);
+
+        assert_offset!(mfxInfoMFX, (u.jpeg_encode.Interleaved), __bindgen_anon_1.__bindgen_anon_4.Interleaved);
+        assert_offset!(mfxInfoMFX, (u.jpeg_encode.Quality), __bindgen_anon_1.__bindgen_anon_4.Quality);
+        assert_offset!(mfxInfoMFX, (u.jpeg_encode.RestartInterval), __bindgen_anon_1.__bindgen_anon_4.RestartInterval);
+    }
+
+    #[test]
+    fn mfxExtBuffer() {
+        assert_size!(mfxExtBuffer);
+        assert_offset!(mfxExtBuffer, BufferId);
+        assert_offset!(mfxExtBuffer, BufferSz);
+    }
+
+    #[test]
+    fn mfxInfoVPP() {
+        assert_size!(mfxInfoVPP);
+        assert_offset!(mfxInfoVPP, In);
+        assert_offset!(mfxInfoVPP, Out);
+    }
+
+    #[test]
+    fn mfxVideoParam() {
+        assert_size!(mfxVideoParam);
+        assert_offset!(mfxVideoParam, AllocId);
+        assert_offset!(mfxVideoParam, AsyncDepth);
+        assert_offset!(mfxVideoParam, u, __bindgen_anon_1);
+        assert_offset!(mfxVideoParam, Protected);
+        assert_offset!(mfxVideoParam, IOPattern);
+        assert_offset!(mfxVideoParam, ExtParam);
+        assert_offset!(mfxVideoParam, NumExtParam);
+    }
+
+    #[test]
+    fn mfxFrameAllocRequest() {
+        assert_size!(mfxFrameAllocRequest);
+        assert_offset!(mfxFrameAllocRequest, AllocId, __bindgen_anon_1.AllocId);
+        assert_offset!(mfxFrameAllocRequest, Info);
+        assert_offset!(mfxFrameAllocRequest, Type);
+        assert_offset!(mfxFrameAllocRequest, NumFrameMin);
+        assert_offset!(mfxFrameAllocRequest, NumFrameSuggested);
+    }
+
+    #[test]
+    fn mfxFrameAllocResponse() {
+        assert_size!(mfxFrameAllocResponse);
+        assert_offset!(mfxFrameAllocResponse, AllocId);
+        assert_offset!(mfxFrameAllocResponse, mids);
+        assert_offset!(mfxFrameAllocResponse, NumFrameActual);
+    }
+
+    #[test]
+    fn mfxFrameAllocator() {
+        assert_size!(mfxFrameAllocator);
+        assert_offset!(mfxFrameAllocator, pthis);
+        assert_offset!(mfxFrameAllocator, Alloc);
+        assert_offset!(mfxFrameAllocator, Lock);
+        assert_offset!(mfxFrameAllocator, Unlock);
+        assert_offset!(mfxFrameAllocator, GetHDL);
+        assert_offset!(mfxFrameAllocator, Free);
+    }
+
+    #[test]
+    fn mfxFrameData() {
+        assert_size!(mfxFrameData);
+        assert_offset!(mfxFrameData, reserved2, __bindgen_anon_1.reserved2);
+        assert_offset!(mfxFrameData, NumExtParam);
+        assert_offset!(mfxFrameData, reserved);
+        assert_offset!(mfxFrameData, MemType);
+        assert_offset!(mfxFrameData, PitchHigh);
+        assert_offset!(mfxFrameData, TimeStamp);
+        assert_offset!(mfxFrameData, FrameOrder);
+        assert_offset!(mfxFrameData, Locked);
+        assert_offset!(mfxFrameData, PitchLow, __bindgen_anon_2.PitchLow);
+        assert_offset!(mfxFrameData, Y, __bindgen_anon_3.Y);
+        assert_offset!(mfxFrameData, UV, __bindgen_anon_4.UV);
+        assert_offset!(mfxFrameData, V, __bindgen_anon_5.V);
+        assert_offset!(mfxFrameData, A);
+        assert_offset!(mfxFrameData, MemId);
+        assert_offset!(mfxFrameData, Corrupted);
+        assert_offset!(mfxFrameData, DataFlag);
+    }
+
+    #[test]
+    fn mfxFrameSurface1() {
+        assert_size!(mfxFrameSurface1);
+        assert_offset!(mfxFrameSurface1, Info);
+        assert_offset!(mfxFrameSurface1, Data);
+    }
+
+    #[test]
+    fn mfxBitstream() {
+        assert_size!(mfxBitstream);
+        assert_offset!(mfxBitstream, ExtParam, __bindgen_anon_1.__bindgen_anon_1.ExtParam);
+        assert_offset!(mfxBitstream, NumExtParam, __bindgen_anon_1.__bindgen_anon_1.NumExtParam);
+        assert_offset!(mfxBitstream, DecodeTimeStamp);
+        assert_offset!(mfxBitstream, TimeStamp);
+        assert_offset!(mfxBitstream, Data);
+        assert_offset!(mfxBitstream, DataOffset);
+        assert_offset!(mfxBitstream, DataLength);
+        assert_offset!(mfxBitstream, MaxLength);
+        assert_offset!(mfxBitstream, PicStruct);
+        assert_offset!(mfxBitstream, FrameType);
+        assert_offset!(mfxBitstream, DataFlag);
+    }
+
+    #[test]
+    fn mfxEncodeCtrl() {
+        assert_size!(mfxEncodeCtrl);
+        assert_offset!(mfxEncodeCtrl, Header);
+        assert_offset!(mfxEncodeCtrl, reserved);
+        assert_offset!(mfxEncodeCtrl, MfxNalUnitType);
+        assert_offset!(mfxEncodeCtrl, SkipFrame);
+        assert_offset!(mfxEncodeCtrl, QP);
+        assert_offset!(mfxEncodeCtrl, FrameType);
+        assert_offset!(mfxEncodeCtrl, NumExtParam);
+        assert_offset!(mfxEncodeCtrl, NumPayload);
+        assert_offset!(mfxEncodeCtrl, ExtParam);
+        assert_offset!(mfxEncodeCtrl, Payload);
+    }
+
+    #[test]
+    fn mfxPayload() {
+        assert_size!(mfxPayload);
+        assert_offset!(mfxPayload, CtrlFlags);
+        assert_offset!(mfxPayload, Data);
+        assert_offset!(mfxPayload, NumBit);
+        assert_offset!(mfxPayload, Type);
+        assert_offset!(mfxPayload, BufSize);
+    }
+
+    #[test]
+    fn mfxExtVppAuxData() {
+        assert_size!(mfxExtVppAuxData);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn mfxExtCodingOption() {
+        assert_size!(mfxExtCodingOption);
+        assert_size!(mfxI16Pair);
+        assert_offset!(mfxExtCodingOption, RateDistortionOpt);
+        assert_offset!(mfxExtCodingOption, MVSearchWindow);
+        assert_offset!(mfxExtCodingOption, EndOfSequence);
+        assert_offset!(mfxExtCodingOption, CAVLC);
+        assert_offset!(mfxExtCodingOption, RecoveryPointSEI);
+        assert_offset!(mfxExtCodingOption, NalHrdConformance);
+        assert_offset!(mfxExtCodingOption, MaxDecFrameBuffering);
+        assert_offset!(mfxExtCodingOption, AUDelimiter);
+        assert_offset!(mfxExtCodingOption, PicTimingSEI);
+        assert_offset!(mfxExtCodingOption, VuiNalHrdParameters);
+    }
+
+    #[test]
+    fn mfxExtCodingOption2() {
+        assert_size!(mfxExtCodingOption2);
+        assert_offset!(mfxExtCodingOption2, IntRefType);
+        assert_offset!(mfxExtCodingOption2, IntRefQPDelta);
+        assert_offset!(mfxExtCodingOption2, MaxFrameSize);
+        assert_offset!(mfxExtCodingOption2, MaxSliceSize);
+        assert_offset!(mfxExtCodingOption2, BitrateLimit);
+        assert_offset!(mfxExtCodingOption2, MBBRC);
+        assert_offset!(mfxExtCodingOption2, LookAheadDepth);
+        assert_offset!(mfxExtCodingOption2, SkipFrame);
+        assert_offset!(mfxExtCodingOption2, MinQPI);
+        assert_offset!(mfxExtCodingOption2, MaxQPB);
+        assert_offset!(mfxExtCodingOption2, FixedFrameRate);
+        assert_offset!(mfxExtCodingOption2, UseRawRef);
+    }
+
+    #[test]
+    fn mfxExtCodingOption3() {
+        assert_size!(mfxExtCodingOption3);
+        assert_offset!(mfxExtCodingOption3, NumSliceI);
+        assert_offset!(mfxExtCodingOption3, WinBRCMaxAvgKbps);
+        assert_offset!(mfxExtCodingOption3, QVBRQuality);
+        assert_offset!(mfxExtCodingOption3, MotionVectorsOverPicBoundaries);
+        assert_offset!(mfxExtCodingOption3, ScenarioInfo);
+        assert_offset!(mfxExtCodingOption3, GPB);
+        assert_offset!(mfxExtCodingOption3, MaxFrameSizeI);
+        assert_offset!(mfxExtCodingOption3, MaxFrameSizeP);
+        assert_offset!(mfxExtCodingOption3, EnableQPOffset);
+        assert_offset!(mfxExtCodingOption3, QPOffset);
+        assert_offset!(mfxExtCodingOption3, NumRefActiveP);
+        assert_offset!(mfxExtCodingOption3, NumRefActiveBL1);
+        assert_offset!(mfxExtCodingOption3, TransformSkip);
+        assert_offset!(mfxExtCodingOption3, RepartitionCheckEnable);
+        assert_offset!(mfxExtCodingOption3, EncodedUnitsInfo);
+        assert_offset!(mfxExtCodingOption3, ExtBrcAdaptiveLTR);
+    }
+
+    #[test]
+    fn mfxExtCodingOptionSPSPPS() {
+        assert_size!(mfxExtCodingOptionSPSPPS);
+        assert_offset!(mfxExtCodingOptionSPSPPS, SPSBuffer);
+        assert_offset!(mfxExtCodingOptionSPSPPS, PPSBuffer);
+        assert_offset!(mfxExtCodingOptionSPSPPS, SPSBufSize);
+        assert_offset!(mfxExtCodingOptionSPSPPS, PPSBufSize);
+        assert_offset!(mfxExtCodingOptionSPSPPS, SPSId);
+        assert_offset!(mfxExtCodingOptionSPSPPS, PPSId);
+    }
+
+    #[test]
+    fn mfxExtEncoderResetOption() {
+        assert_size!(mfxExtEncoderResetOption);
+        assert_offset!(mfxExtEncoderResetOption, StartNewSequence);
+    }
+
+    #[test]
+    fn mfxExtAVCEncodedFrameInfo() {
+        assert_size!(mfxExtAVCEncodedFrameInfo);
+        assert_offset!(mfxExtAVCEncodedFrameInfo, FrameOrder);
+        assert_offset!(mfxExtAVCEncodedFrameInfo, PicStruct);
+        assert_offset!(mfxExtAVCEncodedFrameInfo, MAD);
+        assert_offset!(mfxExtAVCEncodedFrameInfo, BRCPanicMode);
+        assert_offset!(mfxExtAVCEncodedFrameInfo, QP);
+        assert_offset!(mfxExtAVCEncodedFrameInfo, SecondFieldOffset);
+        assert_offset!(mfxExtAVCEncodedFrameInfo, UsedRefListL0);
+        assert_offset!(mfxExtAVCEncodedFrameInfo, UsedRefListL1);
+        assert_eq!(
+            size_of::<structures::mfxExtAVCEncodedFrameInfoStruct1>(),
+            size_of::<super::mfxExtAVCEncodedFrameInfo__bindgen_ty_1>()
+        );
+        assert_eq!(size_of::<structures::mfxExtAVCEncodedFrameInfoStruct1>(), 16);
+    }
+
+    #[test]
+    fn mfxExtVideoSignalInfo() {
+        assert_size!(mfxExtVideoSignalInfo);
+        assert_offset!(mfxExtVideoSignalInfo, VideoFormat);
+        assert_offset!(mfxExtVideoSignalInfo, VideoFullRange);
+        assert_offset!(mfxExtVideoSignalInfo, ColourDescriptionPresent);
+        assert_offset!(mfxExtVideoSignalInfo, ColourPrimaries);
+        assert_offset!(mfxExtVideoSignalInfo, TransferCharacteristics);
+        assert_offset!(mfxExtVideoSignalInfo, MatrixCoefficients);
+    }
+}
@@ -0,0 +1,126 @@
+//! Timestamps of frames and encoded packets.
+//!
+//! The SDK carries presentation timestamps in 90 kHz units from mfxFrameData::TimeStamp of the input surfaces to mfxBitstream::TimeStamp of the packets, and derives the decode timestamps from them and the frame rate. [`Packet`] is what a muxer needs to know about each encoded frame.
+use std::fmt;
+use std::io::{self, BufRead, Error, ErrorKind};
+
+use crate::constants::*;
+use crate::enums::FrameType;
+use crate::structures::*;
+
+/// Clock rate of the SDK timestamps, 90 kHz like MPEG-2 TS and RTP video.
+pub const TIMESTAMP_FREQUENCY: u64 = 90_000;
+
+/// The presentation timestamp of frame number `frame` at a frame rate of `frame_rate_n / frame_rate_d`, in 90 kHz units.
+pub fn frame_timestamp(frame: u64, frame_rate_n: u32, frame_rate_d: u32) -> mfxU64 {
+    let ticks = frame as u128 * TIMESTAMP_FREQUENCY as u128 * frame_rate_d as u128;
+    (ticks / frame_rate_n.max(1) as u128) as mfxU64
+}
+
+/// Reads presentation timestamps in 90 kHz units, one per line in frame order, e.g. from a demuxer. Blank lines are
+/// skipped.
+pub fn read_timestamps(reader: impl BufRead) -> io::Result<Vec<mfxU64>> {
+    let mut timestamps = Vec::new();
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let timestamp = line.parse().map_err(|err| {
+            Error::new(ErrorKind::InvalidData, format!("line {}: invalid timestamp {line:?}: {err}", number + 1))
+        })?;
+        timestamps.push(timestamp);
+    }
+    Ok(timestamps)
+}
+
+/// An encoded frame, as described by its mfxBitstream once its sync point completed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Packet {
+    /// Presentation timestamp in 90 kHz units, `None` if the input frame had none.
+    pub pts: Option<u64>,
+    /// Decode timestamp in 90 kHz units; lower than the PTS when frames are reordered, e.g. for B-frames.
+    pub dts: Option<i64>,
+    pub frame_type: FrameType,
+    /// Size in bytes.
+    pub size: usize,
+}
+
+impl Packet {
+    pub fn from_bitstream(bs: &mfxBitstream) -> Self {
+        Packet {
+            pts: Some(bs.TimeStamp).filter(|&pts| pts != MFX_TIMESTAMP_UNKNOWN),
+            dts: Some(bs.DecodeTimeStamp).filter(|&dts| dts != MFX_TIMESTAMP_UNKNOWN as i64),
+            frame_type: bs.frame_type(),
+            size: bs.DataLength as usize,
+        }
+    }
+
+    /// Whether a decoder can start from this packet.
+    pub fn is_key_frame(&self) -> bool {
+        self.frame_type.is_key_frame()
+    }
+}
+
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.pts {
+            Some(pts) => write!(f, "pts {pts}")?,
+            None => f.write_str("pts -")?,
+        }
+        match self.dts {
+            Some(dts) => write!(f, ", dts {dts}")?,
+            None => f.write_str(", dts -")?,
+        }
+        if self.is_key_frame() {
+            f.write_str(", key")?;
+        }
+        write!(f, ", {} bytes", self.size)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn timestamps_from_frame_rate() {
+        assert_eq!(frame_timestamp(0, 30, 1), 0);
+        assert_eq!(frame_timestamp(1, 30, 1), 3000);
+        assert_eq!(frame_timestamp(1, 30000, 1001), 3003);
+        assert_eq!(frame_timestamp(60, 60000, 1001), 90090);
+        assert_eq!(frame_timestamp(u32::MAX as u64, 25, 1), u32::MAX as u64 * 3600);
+    }
+
+    #[test]
+    fn reads_timestamps() {
+        let timestamps = read_timestamps("0\n3003\n\n 6006 \n".as_bytes()).unwrap();
+        assert_eq!(timestamps, [0, 3003, 6006]);
+
+        let err = read_timestamps("0\n-1\n".as_bytes()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(err.to_string().starts_with("line 2: invalid timestamp \"-1\""), "{}", err);
+    }
+
+    #[test]
+    fn reads_bitstream() {
+        let mut bs = mfxBitstream::new();
+        bs.TimeStamp = 6000;
+        bs.DecodeTimeStamp = 3000;
+        bs.FrameType = MFX_FRAMETYPE_I | MFX_FRAMETYPE_REF | MFX_FRAMETYPE_IDR;
+        bs.DataLength = 1200;
+        let packet = Packet::from_bitstream(&bs);
+        assert_eq!(packet.pts, Some(6000));
+        assert_eq!(packet.dts, Some(3000));
+        assert!(packet.is_key_frame());
+        assert_eq!(packet.to_string(), "pts 6000, dts 3000, key, 1200 bytes");
+
+        bs.TimeStamp = MFX_TIMESTAMP_UNKNOWN;
+        bs.DecodeTimeStamp = -1;
+        bs.FrameType = MFX_FRAMETYPE_P | MFX_FRAMETYPE_REF;
+        let packet = Packet::from_bitstream(&bs);
+        assert_eq!((packet.pts, packet.dts), (None, None));
+        assert_eq!(packet.to_string(), "pts -, dts -, 1200 bytes");
+    }
+}
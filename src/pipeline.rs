@@ -0,0 +1,980 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io;
+use std::io::prelude::*;
+use std::io::{BufReader, Error, ErrorKind};
+use std::ptr;
+use std::slice;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::constants::*;
+use crate::encoder_config::{EncoderConfig, QueryReport};
+use crate::enums::*;
+use crate::frame_control::{FrameControl, KeyFrames};
+use crate::packet::{frame_timestamp, Packet};
+use crate::rate_control::{BrcParams, RateControl};
+use crate::stats::{FrameStats, StatsFormat, StatsWriter};
+use crate::status::{MfxStatus, StatusClass};
+use crate::structures::*;
+use crate::surface_pool::SurfacePool;
+use crate::utils::check_error;
+use crate::Session;
+
+/// Command line parameters of the `simple_3_encode` pipeline.
+#[derive(Debug)]
+pub struct Params {
+    pub input: String,
+    pub output: String,
+    pub width: usize,
+    pub height: usize,
+    /// Target bitrate in Kbps, ignored by the methods driven by QP or quality. Above 65535 Kbps it is scaled with BRCParamMultiplier.
+    pub bitrate: u32,
+    /// Bitrate control method and its parameters; its target bitrate is `bitrate`.
+    pub rate_control: RateControl,
+    /// Number of frames VPP and the encoder work on before the oldest one is waited for, 1 for the lowest latency.
+    pub async_depth: u16,
+    /// Layout of the raw frames: NV12 goes straight to the encoder, YV12 (the Y, U and V planes, like the SDK
+    /// tutorial reads them) is converted by VPP.
+    pub input_format: ColorFourCC,
+    /// Frames to encode as IDR frames on top of the GOP structure.
+    pub force_key_frames: KeyFrames,
+    /// Writes per-frame statistics in this format next to the output, see [`StatsFormat::stats_path`].
+    pub stats: Option<StatsFormat>,
+    /// Presentation timestamps of the input frames in 90 kHz units, in input order, e.g. from the demuxer of the source.
+    /// Without them the frames are stamped from their number and the encoder frame rate.
+    pub timestamps: Option<Vec<mfxU64>>,
+}
+
+/// `AsyncDepth` used when none is given on the command line.
+pub const DEFAULT_ASYNC_DEPTH: u16 = 4;
+
+/// Reads `h` rows of `w` bytes into `plane`, `pitch` bytes apart.
+fn LoadRawPlane(plane: *mut mfxU8, w: usize, h: usize, pitch: usize, file: &mut impl Read) -> io::Result<()> {
+    for row in 0..h {
+        let slice = unsafe { slice::from_raw_parts_mut(plane.add(row * pitch), w) };
+        file.read_exact(slice)?;
+    }
+    Ok(())
+}
+
+/// Reads a frame without padding into `surface`: the Y plane followed by the interleaved UV plane for NV12, or by the U
+/// and V planes otherwise.
+///
+/// Returns `false` if the file ended before the frame; a file ending inside the frame is an error.
+fn LoadRawFrame(surface: &mut mfxFrameSurface1, file: &mut impl BufRead) -> io::Result<bool> {
+    if file.fill_buf()?.is_empty() {
+        return Ok(false);
+    }
+    let pInfo = &surface.Info;
+    let pData = &surface.Data;
+    let w = pInfo.CropW as usize;
+    let h = pInfo.CropH as usize;
+    let pitch = pData.pitch() as usize;
+
+    let mut load = || {
+        LoadRawPlane(pData.Y, w, h, pitch, file)?;
+        if pInfo.FourCC == MFX_FOURCC_NV12 {
+            LoadRawPlane(pData.UV, w, h / 2, pitch, file)
+        } else {
+            LoadRawPlane(pData.UV, w / 2, h / 2, pitch / 2, file)?;
+            LoadRawPlane(pData.V, w / 2, h / 2, pitch / 2, file)
+        }
+    };
+    load().map_err(|err| match err.kind() {
+        ErrorKind::UnexpectedEof => Error::new(ErrorKind::UnexpectedEof, "the input ends inside a frame"),
+        _ => err,
+    })?;
+    Ok(true)
+}
+
+fn WriteBitStreamFrame(pMfxBitstream: &mut mfxBitstream, file: &mut File) -> io::Result<()> {
+    let buffer = unsafe {
+        slice::from_raw_parts(
+            pMfxBitstream.Data.offset(pMfxBitstream.DataOffset as isize),
+            pMfxBitstream.DataLength as usize,
+        )
+    };
+    let nBytesWritten = file.write(buffer)?;
+    if nBytesWritten != (pMfxBitstream.DataLength as usize) {
+        return Err(Error::from(ErrorKind::InvalidData));
+    }
+    pMfxBitstream.DataLength = 0;
+    Ok(())
+}
+
+/// Longest time to wait for a device that keeps returning `MFX_WRN_DEVICE_BUSY`.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+/// Longest sleep between two retries, the sleep doubles from 1 ms up to it.
+const MAX_BUSY_SLEEP: Duration = Duration::from_millis(64);
+
+/// Calls `f` again while it returns `MFX_WRN_DEVICE_BUSY` or another status asking to retry, backing off exponentially.
+/// Fails with the last status once the device has been busy for [`BUSY_TIMEOUT`].
+fn RetryWhileBusy(mut f: impl FnMut() -> mfxStatus) -> io::Result<mfxStatus> {
+    let mut sleep = Duration::from_millis(1);
+    let mut waited = Duration::ZERO;
+    loop {
+        let sts = f();
+        let status = MfxStatus::from_raw(sts);
+        if status.class() != StatusClass::Retry {
+            return Ok(sts);
+        }
+        if waited >= BUSY_TIMEOUT {
+            return Err(crate::Error::Status(status).into());
+        }
+        thread::sleep(sleep);
+        waited += sleep;
+        sleep = (sleep * 2).min(MAX_BUSY_SLEEP);
+    }
+}
+
+/// Reallocates the buffer of `pMfxBitstream` with `size` bytes, keeping the data that has not been written yet.
+fn ExtendMfxBitstream(pMfxBitstream: &mut mfxBitstream, buffer: &mut Vec<u8>, size: usize) {
+    let start = pMfxBitstream.DataOffset as usize;
+    let end = start + pMfxBitstream.DataLength as usize;
+    buffer.copy_within(start..end, 0);
+    buffer.resize(size, 0);
+    pMfxBitstream.Data = buffer.as_mut_ptr();
+    pMfxBitstream.DataOffset = 0;
+    pMfxBitstream.MaxLength = size as mfxU32;
+}
+
+/// mfxExtAVCEncodedFrameInfo::QP before the encoder fills it in, an encoder that does not support the buffer leaves it.
+const QP_NOT_REPORTED: mfxU16 = mfxU16::MAX;
+
+/// An encode task: its own bitstream and the sync point to wait on before writing it.
+///
+/// Tasks are boxed: the SDK keeps writing to `bs` until the sync point completes, so it must not move while in flight.
+struct Task {
+    bs: mfxBitstream,
+    buffer: Vec<u8>,
+    syncp: mfxSyncPoint,
+    /// Attached to `bs` through ExtParam while the QP is requested, boxed so the pointers stay valid when the task is
+    /// built and boxed.
+    frame_info: Box<mfxExtAVCEncodedFrameInfo>,
+    ext_param: Box<*mut mfxExtBuffer>,
+}
+
+impl Task {
+    fn new(size: usize, frame_info: bool) -> Box<Self> {
+        let mut buffer = vec![0; size];
+        let mut bs = mfxBitstream::new();
+        bs.Data = buffer.as_mut_ptr();
+        bs.MaxLength = size as mfxU32;
+        let mut frame_info_buffer = Box::new(mfxExtAVCEncodedFrameInfo::new());
+        let ext_param = Box::new(&mut *frame_info_buffer as *mut mfxExtAVCEncodedFrameInfo as *mut mfxExtBuffer);
+        let mut task = Box::new(Task {
+            bs,
+            buffer,
+            syncp: ptr::null_mut(),
+            frame_info: frame_info_buffer,
+            ext_param,
+        });
+        task.attach_frame_info(frame_info);
+        task
+    }
+
+    /// Attaches mfxExtAVCEncodedFrameInfo to the bitstream, or detaches it.
+    fn attach_frame_info(&mut self, attach: bool) {
+        if attach {
+            self.bs.ExtParam = &*self.ext_param;
+            self.bs.NumExtParam = 1;
+        } else {
+            self.bs.ExtParam = ptr::null();
+            self.bs.NumExtParam = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+impl Drop for Task {
+    fn drop(&mut self) {
+        test::check_closed_before_free();
+    }
+}
+
+/// A frame given to the encoder, matched to its packet by the timestamp.
+struct Submission {
+    frame: mfxU32,
+    time_stamp: mfxU64,
+    at: Instant,
+    /// The controls of the frame, which the SDK may read until its packet is synced, even after returning
+    /// `MFX_ERR_MORE_DATA` for it. Boxed so they stay in place when the submission moves.
+    _ctrl: Option<Box<FrameControl>>,
+}
+
+#[cfg(test)]
+impl Drop for Submission {
+    fn drop(&mut self) {
+        if let Some(ctrl) = &self._ctrl {
+            test::check_ctrl_released(ctrl.as_ptr());
+        }
+    }
+}
+
+/// Up to `AsyncDepth` encode tasks in flight, written to the output in submission order. The statistics of each
+/// written frame are reported to `on_frame`.
+struct EncodeQueue<'a> {
+    // Boxed so the tasks keep their address when moved between the queues.
+    #[allow(clippy::vec_box)]
+    free: Vec<Box<Task>>,
+    in_flight: VecDeque<Box<Task>>,
+    /// Frames given to the encoder whose packet has not been written yet, oldest first.
+    pending: VecDeque<Submission>,
+    /// Frames given to the encoder, the number of the next one.
+    submitted: mfxU32,
+    written: mfxU32,
+    /// Whether the tasks ask the encoder for the QP through mfxExtAVCEncodedFrameInfo, cleared once it rejects the buffer.
+    frame_info: bool,
+    on_frame: &'a mut dyn FnMut(&FrameStats) -> io::Result<()>,
+}
+
+impl<'a> EncodeQueue<'a> {
+    fn new(
+        depth: u16,
+        bitstream_size: usize,
+        frame_info: bool,
+        on_frame: &'a mut dyn FnMut(&FrameStats) -> io::Result<()>,
+    ) -> Self {
+        EncodeQueue {
+            free: (0..depth.max(1)).map(|_| Task::new(bitstream_size, frame_info)).collect(),
+            in_flight: VecDeque::new(),
+            pending: VecDeque::new(),
+            submitted: 0,
+            written: 0,
+            frame_info,
+            on_frame,
+        }
+    }
+
+    /// Submits `surface` to the encoder with the controls `ctrl`, a NULL `surface` retrieves the frames buffered by the
+    /// encoder. Waits for the oldest task and writes it to `file` first when all tasks are in flight.
+    ///
+    /// Retries while the device is busy and grows the bitstream while the frame does not fit. An encoder rejecting
+    /// mfxExtAVCEncodedFrameInfo is retried without it, and the QP of the following frames is not reported. Returns
+    /// `false` when the encoder needs more input before it outputs a frame.
+    fn submit(
+        &mut self,
+        session: &Session,
+        surface: *const mfxFrameSurface1,
+        ctrl: Option<FrameControl>,
+        file: &mut File,
+    ) -> io::Result<bool> {
+        if self.free.is_empty() {
+            self.write_oldest(session, file)?;
+        }
+        let mut task = self.free.pop().expect("a task was written above");
+        let ctrl = ctrl.map(Box::new);
+        let ctrl_ptr = ctrl.as_deref().map_or(ptr::null(), FrameControl::as_ptr);
+        task.attach_frame_info(self.frame_info);
+        task.frame_info.QP = QP_NOT_REPORTED;
+        if let Some(surface) = unsafe { surface.as_ref() } {
+            self.pending.push_back(Submission {
+                frame: self.submitted,
+                time_stamp: surface.Data.TimeStamp,
+                at: Instant::now(),
+                _ctrl: ctrl,
+            });
+            self.submitted += 1;
+        }
+
+        if let Err(err) = self.encode_task(session, &mut task, ctrl_ptr, surface) {
+            // The SDK may still hold the bitstream, keep the task until the encoder is closed
+            self.free.push(task);
+            return Err(err);
+        }
+        if task.syncp.is_null() {
+            self.free.push(task);
+            return Ok(false);
+        }
+        self.in_flight.push_back(task);
+        Ok(true)
+    }
+
+    /// Calls EncodeFrameAsync for `task` until it does not ask to retry, see [`Self::submit`].
+    fn encode_task(
+        &mut self,
+        session: &Session,
+        task: &mut Task,
+        ctrl: *const mfxEncodeCtrl,
+        surface: *const mfxFrameSurface1,
+    ) -> io::Result<()> {
+        let mfx = session.library();
+        let handle = session.as_ptr();
+        let sts = loop {
+            let sts = RetryWhileBusy(|| unsafe {
+                (mfx.MFXVideoENCODE_EncodeFrameAsync)(handle, ctrl, surface, &mut task.bs, &mut task.syncp)
+            })?;
+            if self.frame_info && (sts == MFX_ERR_INVALID_VIDEO_PARAM || sts == MFX_ERR_UNDEFINED_BEHAVIOR) {
+                self.frame_info = false;
+                task.attach_frame_info(false);
+                continue;
+            }
+            if sts != MFX_ERR_NOT_ENOUGH_BUFFER {
+                break sts;
+            }
+            let size = 2 * task.bs.MaxLength.max(1024) as usize;
+            ExtendMfxBitstream(&mut task.bs, &mut task.buffer, size);
+        };
+
+        if sts != MFX_ERR_MORE_DATA {
+            check_error(sts)?;
+        }
+        Ok(())
+    }
+
+    /// Waits for the oldest task in flight, reports its statistics and writes its frame to `file`.
+    fn write_oldest(&mut self, session: &Session, file: &mut File) -> io::Result<()> {
+        let mfx = session.library();
+        let handle = session.as_ptr();
+        let Some(mut task) = self.in_flight.pop_front() else {
+            return Ok(());
+        };
+
+        let synced = RetryWhileBusy(|| unsafe { (mfx.MFXVideoCORE_SyncOperation)(handle, task.syncp, 6000) })
+            .and_then(|sts| Ok(check_error(sts)?));
+        if let Err(err) = synced {
+            // The frame may still be encoding into the bitstream, keep the task until the encoder is closed
+            self.in_flight.push_front(task);
+            return Err(err);
+        }
+
+        let packet = Packet::from_bitstream(&task.bs);
+        // The encoder may reorder frames, find the one with the timestamp of the packet. Without a timestamp assume the
+        // encoder kept the submission order.
+        let index = match packet.pts {
+            Some(pts) => self.pending.iter().position(|submission| submission.time_stamp == pts).ok_or_else(|| {
+                Error::new(ErrorKind::InvalidData, format!("the encoder returned a packet with unknown pts {pts}"))
+            })?,
+            None => 0,
+        };
+        let (frame, latency) = self
+            .pending
+            .remove(index)
+            .map_or((self.written, Duration::ZERO), |submission| (submission.frame, submission.at.elapsed()));
+        (self.on_frame)(&FrameStats {
+            frame,
+            packet,
+            pic_struct: task.bs.pic_struct(),
+            latency,
+            avg_qp: Some(task.frame_info.QP).filter(|&qp| qp != QP_NOT_REPORTED),
+        })?;
+        WriteBitStreamFrame(&mut task.bs, file)?;
+        task.syncp = ptr::null_mut();
+        self.free.push(task);
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Writes every task in flight.
+    fn flush(&mut self, session: &Session, file: &mut File) -> io::Result<()> {
+        while !self.in_flight.is_empty() {
+            self.write_oldest(session, file)?;
+        }
+        Ok(())
+    }
+}
+
+/// Closes VPP when dropped, on every way out of the pipeline once VPP was initialized.
+struct VppGuard<'a> {
+    session: &'a Session,
+}
+
+impl Drop for VppGuard<'_> {
+    fn drop(&mut self) {
+        unsafe { (self.session.library().MFXVideoVPP_Close)(self.session.as_ptr()) };
+    }
+}
+
+/// Whether frames described by `input` have to go through VPP before the encoder accepts frames described by `output`.
+fn NeedsVpp(input: &mfxFrameInfo, output: &mfxFrameInfo) -> bool {
+    input.FourCC != output.FourCC
+        || input.ChromaFormat != output.ChromaFormat
+        || (input.Width, input.Height) != (output.Width, output.Height)
+        || (input.CropX, input.CropY) != (output.CropX, output.CropY)
+        || (input.CropW, input.CropH) != (output.CropW, output.CropH)
+        || input.PicStruct != output.PicStruct
+        || (input.FrameRateExtN, input.FrameRateExtD) != (output.FrameRateExtN, output.FrameRateExtD)
+}
+
+/// Outcome of a pipeline run.
+#[derive(Debug, Clone)]
+pub struct Summary {
+    /// The encoder parameters corrected by Query.
+    pub report: QueryReport,
+    /// Whether the input went through VPP to match the encoder format.
+    pub vpp: bool,
+    /// Number of frames written to the output.
+    pub frames: u32,
+}
+
+/// Encodes the raw file `params.input` to the H.264 elementary stream `params.output`, converting it to NV12 with VPP
+/// unless it already is.
+///
+/// The statistics of every frame written to the output are reported to `on_frame`, and written to the statistics file
+/// if `params.stats` is set. Nothing is printed.
+pub fn run(
+    session: &Session,
+    params: &Params,
+    on_frame: &mut dyn FnMut(&FrameStats) -> io::Result<()>,
+) -> io::Result<Summary> {
+    let mut stats = match params.stats {
+        Some(format) => Some(StatsWriter::create(format.stats_path(&params.output), format)?),
+        None => None,
+    };
+    let summary = encode(session, params, &mut |frame| {
+        on_frame(frame)?;
+        stats.as_mut().map_or(Ok(()), |stats| stats.write(frame))
+    })?;
+    stats.as_mut().map_or(Ok(()), StatsWriter::flush)?;
+    Ok(summary)
+}
+
+/// Runs the pipeline, reporting the statistics of every frame written to the output to `on_frame`.
+///
+/// The input frames are stamped with `params.timestamps`, or with presentation timestamps derived from their number and
+/// the encoder frame rate. An input with more frames than `params.timestamps` is an error.
+fn encode(
+    session: &Session,
+    params: &Params,
+    on_frame: &mut dyn FnMut(&FrameStats) -> io::Result<()>,
+) -> io::Result<Summary> {
+    let mfx = session.library();
+    let handle = session.as_ptr();
+    let mut sts: mfxStatus;
+
+    if !matches!(params.input_format, ColorFourCC::Yv12 | ColorFourCC::Nv12) {
+        return Err(crate::Error::UnsupportedFourCC(params.input_format.into()).into());
+    }
+
+    // The SDK uses the surfaces, the bitstreams and the frame controls until VPP and the encoder are closed. Locals are
+    // dropped in reverse order, so declaring these before `encoder` and `vpp` frees them after both are closed, on
+    // every way out.
+    let mut vpp_surfaces_in: Option<SurfacePool>;
+    let mut shared_surfaces: SurfacePool;
+    let mut encode_queue: EncodeQueue;
+
+    let configured = EncoderConfig::new(CodecId::Avc, params.width as u16, params.height as u16)
+        .frame_rate(30, 1)
+        .target_usage(TargetUsage::Balanced)
+        .rate_control_config(params.rate_control.with_target_kbps(params.bitrate))
+        .async_depth(params.async_depth)
+        .init(session)?;
+    // mfxExtAVCEncodedFrameInfo is only read for the statistics, and only H.264 encoders may support it
+    let frame_info = params.stats.is_some() && matches!(configured.params.mfx().codec_id(), Ok(CodecId::Avc));
+    let encoder = configured.encoder;
+    let encRequest = configured.request;
+    let report = configured.report;
+    let EncInfo = configured.params.mfx().FrameInfo;
+
+    let mut InputInfo = EncInfo;
+    InputInfo.set_fourcc(params.input_format);
+
+    let mut sharedRequest = encRequest;
+    let mut vpp = if NeedsVpp(&InputInfo, &EncInfo) {
+        let mut configurability = mfxVideoParam::new();
+        sts = RetryWhileBusy(|| unsafe { (mfx.MFXVideoVPP_Query)(handle, ptr::null(), &mut configurability) })?;
+        check_error(sts)?;
+
+        let mut VppParams = mfxVideoParam::new();
+        VppParams.vpp_mut().In = InputInfo;
+        VppParams.vpp_mut().Out = EncInfo;
+        VppParams.set_io_pattern(IOPattern::IN_SYSTEM_MEMORY | IOPattern::OUT_SYSTEM_MEMORY);
+        VppParams.AsyncDepth = params.async_depth;
+
+        let mut VPPRequest = [mfxFrameAllocRequest::new(), mfxFrameAllocRequest::new()];
+        sts = unsafe { (mfx.MFXVideoVPP_QueryIOSurf)(handle, &VppParams, &mut VPPRequest) };
+        check_error(sts)?;
+
+        vpp_surfaces_in = Some(SurfacePool::new(&VPPRequest[0])?);
+
+        sts = RetryWhileBusy(|| unsafe { (mfx.MFXVideoVPP_Init)(handle, &mut VppParams) })?;
+        check_error(sts)?;
+
+        // VPP writes into the surfaces the encoder reads, so the pool has to cover what both of them keep locked
+        sharedRequest.NumFrameMin += VPPRequest[1].NumFrameMin;
+        sharedRequest.NumFrameSuggested += VPPRequest[1].NumFrameSuggested;
+        Some(VppGuard { session })
+    } else {
+        vpp_surfaces_in = None;
+        None
+    };
+
+    shared_surfaces = SurfacePool::new(&sharedRequest)?;
+
+    let par = encoder.video_param()?;
+    let bufferSizeInKB = BrcParams::read(par.mfx()).buffer_size_kb;
+
+    encode_queue = EncodeQueue::new(params.async_depth, 1000 * bufferSizeInKB as usize, frame_info, on_frame);
+    let key_frames = params.force_key_frames.frame_numbers(EncInfo.FrameRateExtN, EncInfo.FrameRateExtD);
+    // Controls of the next frame given to the encoder
+    let frame_control = |frame: mfxU32| key_frames.binary_search(&frame).is_ok().then(FrameControl::key_frame);
+
+    let mut syncp_vpp: mfxSyncPoint = ptr::null_mut();
+
+    let mut file_in = BufReader::new(File::open(&params.input)?);
+    // Number of frames read from the input, and their PTS in 90 kHz units
+    let mut frames_read: u64 = 0;
+    let mut next_timestamp = || {
+        let timestamp = match &params.timestamps {
+            Some(timestamps) => *timestamps.get(frames_read as usize).ok_or_else(|| {
+                Error::new(ErrorKind::InvalidInput, format!("no timestamp for input frame {frames_read}"))
+            })?,
+            None => frame_timestamp(frames_read, EncInfo.FrameRateExtN, EncInfo.FrameRateExtD),
+        };
+        frames_read += 1;
+        io::Result::Ok(timestamp)
+    };
+    let mut file_out = File::create(&params.output)?;
+
+    // Stage 1: Main encoding loop
+    if let Some(vpp_surfaces_in) = vpp_surfaces_in.as_mut() {
+        // Once the input file has ended, VPP is called with NULL input to retrieve the frames it buffered.
+        let mut file_ended = false;
+        loop {
+            let mut vpp_in: *const mfxFrameSurface1 = ptr::null();
+            if !file_ended {
+                let Some(surface) = vpp_surfaces_in.free_surface() else {
+                    return Err(Error::other("no free VPP input surface"));
+                };
+                if LoadRawFrame(surface, &mut file_in)? {
+                    // VPP passes the timestamp on to its output
+                    surface.Data.TimeStamp = next_timestamp()?;
+                    vpp_in = surface;
+                } else {
+                    file_ended = true;
+                }
+            }
+
+            // MFX_ERR_MORE_SURFACE: the same input makes another output frame, e.g. with frame rate conversion
+            let vpp_sts = loop {
+                let Some(vpp_out) = shared_surfaces.free_surface() else {
+                    return Err(Error::other("no free VPP output surface"));
+                };
+                let vpp_out: *mut mfxFrameSurface1 = vpp_out;
+
+                let vpp_sts = RetryWhileBusy(|| unsafe {
+                    (mfx.MFXVideoVPP_RunFrameVPPAsync)(handle, vpp_in, vpp_out, ptr::null(), &mut syncp_vpp)
+                })?;
+
+                if vpp_sts == MFX_ERR_MORE_DATA {
+                    break vpp_sts;
+                }
+                if vpp_sts != MFX_ERR_MORE_SURFACE {
+                    check_error(vpp_sts)?;
+                }
+
+                // No need to wait for VPP: the SDK starts encoding the surface once VPP is done with it
+                let ctrl = frame_control(encode_queue.submitted);
+                encode_queue.submit(session, vpp_out, ctrl, &mut file_out)?;
+
+                if vpp_sts != MFX_ERR_MORE_SURFACE {
+                    break vpp_sts;
+                }
+            };
+
+            // MFX_ERR_MORE_DATA after the input file has ended means that VPP has returned all frames
+            if vpp_sts == MFX_ERR_MORE_DATA && file_ended {
+                break;
+            }
+        }
+        drop(vpp.take());
+    } else {
+        loop {
+            let Some(surface) = shared_surfaces.free_surface() else {
+                return Err(Error::other("no free encoder surface"));
+            };
+            if !LoadRawFrame(surface, &mut file_in)? {
+                break;
+            }
+            surface.Data.TimeStamp = next_timestamp()?;
+            let ctrl = frame_control(encode_queue.submitted);
+            encode_queue.submit(session, surface, ctrl, &mut file_out)?;
+        }
+    }
+
+    // Stage 2: Retrieve the frames buffered by the encoder (B-frames, lookahead)
+    while encode_queue.submit(session, ptr::null(), None, &mut file_out)? {}
+    encode_queue.flush(session, &mut file_out)?;
+
+    Ok(Summary {
+        report,
+        vpp: vpp_surfaces_in.is_some(),
+        frames: encode_queue.written,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::loader::stub;
+    use std::cell::Cell;
+    use std::env;
+    use std::fs;
+
+    thread_local! {
+        /// Encode tasks and frame controls freed while the stub encoder or VPP was still open and could use them.
+        static BUFFERS_FREED_WHILE_OPEN: Cell<u32> = const { Cell::new(0) };
+    }
+
+    /// Counts the freed task if the stub encoder or VPP is still open.
+    pub(super) fn check_closed_before_free() {
+        let vpp_open = stub::VPP_INITS.with(Cell::get) != stub::VPP_CLOSES.with(Cell::get);
+        if stub::OPEN_ENCODERS.with(Cell::get) > 0 || vpp_open {
+            BUFFERS_FREED_WHILE_OPEN.with(|freed| freed.set(freed.get() + 1));
+        }
+    }
+
+    /// Counts the freed frame controls if the stub encoder still holds their frame.
+    pub(super) fn check_ctrl_released(ctrl: *const mfxEncodeCtrl) {
+        if stub::holds_ctrl(ctrl) {
+            BUFFERS_FREED_WHILE_OPEN.with(|freed| freed.set(freed.get() + 1));
+        }
+    }
+
+    /// Runs the pipeline on `frames` gray frames with the stub library, returns the encoded stream.
+    fn encode_with_stub(frames: usize, async_depth: u16, input_format: ColorFourCC) -> Vec<u8> {
+        encode_with_stub_key_frames(frames, async_depth, input_format, KeyFrames::default()).0
+    }
+
+    /// Frame size of the test input.
+    const WIDTH: usize = 64;
+    const HEIGHT: usize = 48;
+    const FRAME_BYTES: usize = WIDTH * HEIGHT * 3 / 2;
+
+    /// Like [`encode_with_stub`], also returns the frame statistics reported by the pipeline.
+    fn encode_with_stub_key_frames(
+        frames: usize,
+        async_depth: u16,
+        input_format: ColorFourCC,
+        force_key_frames: KeyFrames,
+    ) -> (Vec<u8>, Vec<FrameStats>) {
+        let input = vec![0x80; FRAME_BYTES * frames];
+        let (encoded, stats) = encode_input_with_stub(&input, async_depth, input_format, force_key_frames);
+        (encoded.unwrap(), stats)
+    }
+
+    /// Runs the pipeline on the raw `input` with the stub library, returns the encoded stream and the frame statistics.
+    fn encode_input_with_stub(
+        input_bytes: &[u8],
+        async_depth: u16,
+        input_format: ColorFourCC,
+        force_key_frames: KeyFrames,
+    ) -> (io::Result<Vec<u8>>, Vec<FrameStats>) {
+        encode_params_with_stub(input_bytes, |params| {
+            params.async_depth = async_depth;
+            params.input_format = input_format;
+            params.force_key_frames = force_key_frames;
+        })
+    }
+
+    /// Like [`encode_input_with_stub`], with NV12 input and the parameters changed by `configure`.
+    fn encode_params_with_stub(
+        input_bytes: &[u8],
+        configure: impl FnOnce(&mut Params),
+    ) -> (io::Result<Vec<u8>>, Vec<FrameStats>) {
+        let name = format!("qsv-pipeline-{}-{:?}", std::process::id(), std::thread::current().id());
+        let input = env::temp_dir().join(name.replace(|c: char| !c.is_ascii_alphanumeric(), "-") + ".yuv");
+        let output = input.with_extension("h264");
+        fs::write(&input, input_bytes).unwrap();
+
+        let library = stub::pipeline_library();
+        let session = Session::with_library(library, MFX_IMPL_HARDWARE_ANY, mfxVersion::new(1, 0)).unwrap();
+        let mut params = Params {
+            input: input.to_string_lossy().into_owned(),
+            output: output.to_string_lossy().into_owned(),
+            width: WIDTH,
+            height: HEIGHT,
+            bitrate: 1000,
+            rate_control: RateControl::default(),
+            async_depth: DEFAULT_ASYNC_DEPTH,
+            input_format: ColorFourCC::Nv12,
+            force_key_frames: KeyFrames::default(),
+            stats: None,
+            timestamps: None,
+        };
+        configure(&mut params);
+        let mut stats = Vec::new();
+        let result = encode(&session, &params, &mut |frame| {
+            stats.push(*frame);
+            Ok(())
+        });
+        let encoded = fs::read(&output);
+        let _ = fs::remove_file(&input);
+        let _ = fs::remove_file(&output);
+        (result.and(encoded), stats)
+    }
+
+    /// Frame numbers written by the stub encoder.
+    fn frame_numbers(encoded: &[u8], frame_size: usize) -> Vec<u8> {
+        assert_eq!(encoded.len() % frame_size, 0);
+        encoded.chunks(frame_size).map(|packet| packet[7]).collect()
+    }
+
+    #[test]
+    fn drains_buffered_frames() {
+        let encoded = encode_with_stub(5, 1, ColorFourCC::Yv12);
+        assert_eq!(frame_numbers(&encoded, stub::PACKET_SIZE), [0, 1, 2, 3, 4]);
+        assert_eq!(stub::PEAK_ENCODES_IN_FLIGHT.with(|peak| peak.get()), 1);
+    }
+
+    #[test]
+    fn recovers_from_busy_device_and_small_bitstream() {
+        let frame_size = 100_000;
+        stub::FRAME_SIZE.with(|size| size.set(frame_size));
+        stub::BUSY_CALLS.with(|calls| calls.set(3));
+        stub::VPP_OUTPUTS_PER_INPUT.with(|outputs| outputs.set(2));
+
+        let encoded = encode_with_stub(3, DEFAULT_ASYNC_DEPTH, ColorFourCC::Yv12);
+        assert_eq!(frame_numbers(&encoded, frame_size), [0, 1, 2, 3, 4, 5]);
+        assert_eq!(stub::BUSY_CALLS.with(|calls| calls.get()), 0);
+    }
+
+    #[test]
+    fn retries_busy_vpp_setup() {
+        stub::VPP_SETUP_BUSY_CALLS.with(|calls| calls.set(3));
+        let encoded = encode_with_stub(2, DEFAULT_ASYNC_DEPTH, ColorFourCC::Yv12);
+        assert_eq!(frame_numbers(&encoded, stub::PACKET_SIZE), [0, 1]);
+        assert_eq!(stub::VPP_SETUP_BUSY_CALLS.with(|calls| calls.get()), 0);
+    }
+
+    #[test]
+    fn rejects_truncated_input() {
+        for input_format in [ColorFourCC::Nv12, ColorFourCC::Yv12] {
+            let input = vec![0x80; FRAME_BYTES * 2 + FRAME_BYTES / 2];
+            let (result, stats) = encode_input_with_stub(&input, 1, input_format, KeyFrames::default());
+            let err = result.unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::UnexpectedEof, "{input_format}");
+            assert_eq!(err.to_string(), "the input ends inside a frame");
+            assert!(stats.len() <= 2);
+            assert_eq!(stub::VPP_CLOSES.with(Cell::get), stub::VPP_INITS.with(Cell::get), "{input_format}");
+        }
+    }
+
+    #[test]
+    fn reports_vpp_surface_query_errors() {
+        stub::VPP_QUERY_IO_SURF_STATUS.with(|status| status.set(MFX_ERR_UNSUPPORTED));
+        let input = vec![0x80; FRAME_BYTES];
+        let (result, _) = encode_input_with_stub(&input, 1, ColorFourCC::Yv12, KeyFrames::default());
+        let err = result.unwrap_err();
+        let err = err.get_ref().and_then(|err| err.downcast_ref::<crate::Error>()).unwrap();
+        assert_eq!(err.status(), Some(MfxStatus::Unsupported));
+        assert_eq!(stub::VPP_INITS.with(|inits| inits.get()), 0);
+    }
+
+    #[test]
+    fn closes_components_before_freeing_buffers_on_errors() {
+        for input_format in [ColorFourCC::Nv12, ColorFourCC::Yv12] {
+            stub::FAILING_FRAME.with(|frame| frame.set(4));
+            let input = vec![0x80; FRAME_BYTES * 8];
+            let (result, stats) = encode_params_with_stub(&input, |params| {
+                params.input_format = input_format;
+                params.async_depth = 2;
+                params.force_key_frames = "3,4".parse().unwrap();
+            });
+            let err = result.unwrap_err();
+            let err = err.get_ref().and_then(|err| err.downcast_ref::<crate::Error>()).unwrap();
+            assert_eq!(err.status(), Some(MfxStatus::DeviceFailed), "{input_format}");
+            assert!(!stats.is_empty() && stats.len() < 8, "{}", input_format);
+            assert_eq!(stub::OPEN_ENCODERS.with(Cell::get), 0, "{input_format}");
+            assert_eq!(BUFFERS_FREED_WHILE_OPEN.with(Cell::get), 0, "{input_format}");
+        }
+    }
+
+    #[test]
+    fn pipelines_async_depth_tasks() {
+        let encoded = encode_with_stub(10, 3, ColorFourCC::Yv12);
+        assert_eq!(frame_numbers(&encoded, stub::PACKET_SIZE), (0..10).collect::<Vec<_>>());
+        assert_eq!(stub::PEAK_ENCODES_IN_FLIGHT.with(|peak| peak.get()), 3);
+    }
+
+    #[test]
+    fn encodes_vpp_output_without_copy() {
+        encode_with_stub(4, DEFAULT_ASYNC_DEPTH, ColorFourCC::Yv12);
+        assert_eq!(stub::FRAMES_FROM_VPP.with(|frames| frames.get()), 4);
+        assert_eq!(stub::VPP_CLOSES.with(Cell::get), 1);
+    }
+
+    #[test]
+    fn skips_vpp_for_nv12_input() {
+        let encoded = encode_with_stub(3, DEFAULT_ASYNC_DEPTH, ColorFourCC::Nv12);
+        assert_eq!(frame_numbers(&encoded, stub::PACKET_SIZE), [0, 1, 2]);
+        assert_eq!(stub::VPP_INITS.with(|inits| inits.get()), 0);
+    }
+
+    #[test]
+    fn forces_key_frames() {
+        for input_format in [ColorFourCC::Nv12, ColorFourCC::Yv12] {
+            let key_frames = "2,0.1s".parse().unwrap();
+            let (encoded, _) = encode_with_stub_key_frames(6, 2, input_format, key_frames);
+            let nal_types: Vec<u8> = encoded.chunks(stub::PACKET_SIZE).map(|packet| packet[4] & 0x1f).collect();
+            assert_eq!(nal_types, [5, 1, 5, 5, 1, 1], "{input_format}");
+        }
+    }
+
+    #[test]
+    fn keeps_controls_of_buffered_frames() {
+        // The stub returns MFX_ERR_MORE_DATA for the first frames and reads their controls once it encodes them.
+        let library = stub::encoder_library();
+        let session = Session::with_library(library, MFX_IMPL_HARDWARE_ANY, mfxVersion::new(1, 0)).unwrap();
+        let name = format!("qsv-controls-{}-{:?}", std::process::id(), thread::current().id());
+        let path = env::temp_dir().join(name.replace(|c: char| !c.is_ascii_alphanumeric(), "-") + ".h264");
+        let mut file = File::create(&path).unwrap();
+        let mut key_frames = Vec::new();
+        let mut on_frame = |frame: &FrameStats| {
+            key_frames.push(frame.packet.is_key_frame());
+            Ok(())
+        };
+        let mut queue = EncodeQueue::new(1, 1024, false, &mut on_frame);
+        let controls = vec![None, Some(FrameControl::key_frame()), Some(FrameControl::new().qp(30)), None];
+        for (frame, ctrl) in controls.into_iter().enumerate() {
+            let mut surface = mfxFrameSurface1::new();
+            surface.Data.TimeStamp = frame as mfxU64;
+            queue.submit(&session, &surface, ctrl, &mut file).unwrap();
+        }
+        while queue.submit(&session, ptr::null(), None, &mut file).unwrap() {}
+        queue.flush(&session, &mut file).unwrap();
+        drop(queue);
+        let _ = fs::remove_file(&path);
+        assert_eq!(key_frames, [true, true, false, false]);
+    }
+
+    #[test]
+    fn reports_packet_timestamps() {
+        for input_format in [ColorFourCC::Nv12, ColorFourCC::Yv12] {
+            let (_, stats) = encode_with_stub_key_frames(4, 2, input_format, "2".parse().unwrap());
+            let packets: Vec<_> = stats.iter().map(|frame| frame.packet).collect();
+            let pts: Vec<_> = packets.iter().map(|packet| packet.pts.unwrap()).collect();
+            assert_eq!(pts, [0, 3000, 6000, 9000], "{input_format}");
+            assert!(packets.iter().all(|packet| packet.dts == Some(packet.pts.unwrap() as i64)));
+            let key_frames: Vec<_> = packets.iter().map(Packet::is_key_frame).collect();
+            assert_eq!(key_frames, [true, false, true, false]);
+            assert!(packets.iter().all(|packet| packet.size == stub::PACKET_SIZE));
+        }
+    }
+
+    #[test]
+    fn reports_source_timestamps() {
+        let timestamps = vec![1000, 4003, 7006, 12000];
+        for input_format in [ColorFourCC::Nv12, ColorFourCC::Yv12] {
+            let input = vec![0x80; FRAME_BYTES * timestamps.len()];
+            let (result, stats) = encode_params_with_stub(&input, |params| {
+                params.input_format = input_format;
+                params.timestamps = Some(timestamps.clone());
+            });
+            result.unwrap();
+            let pts: Vec<_> = stats.iter().map(|frame| frame.packet.pts.unwrap()).collect();
+            assert_eq!(pts, timestamps, "{input_format}");
+            let frames: Vec<_> = stats.iter().map(|frame| frame.frame).collect();
+            assert_eq!(frames, [0, 1, 2, 3], "{input_format}");
+        }
+    }
+
+    #[test]
+    fn rejects_input_without_source_timestamps() {
+        let input = vec![0x80; FRAME_BYTES * 3];
+        let (result, _) = encode_params_with_stub(&input, |params| params.timestamps = Some(vec![0, 3000]));
+        let err = result.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert_eq!(err.to_string(), "no timestamp for input frame 2");
+    }
+
+    #[test]
+    fn rejects_packets_with_unknown_timestamps() {
+        stub::PACKET_TIME_STAMP_SHIFT.with(|shift| shift.set(1));
+        let input = vec![0x80; FRAME_BYTES * 3];
+        let (result, stats) = encode_input_with_stub(&input, 2, ColorFourCC::Nv12, KeyFrames::default());
+        let err = result.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "the encoder returned a packet with unknown pts 1");
+        assert!(stats.is_empty());
+    }
+
+    #[test]
+    fn reports_frame_stats() {
+        let input = vec![0x80; FRAME_BYTES * 5];
+        let (result, stats) = encode_params_with_stub(&input, |params| {
+            params.async_depth = 3;
+            params.stats = Some(StatsFormat::Csv);
+        });
+        result.unwrap();
+        let frames: Vec<_> = stats.iter().map(|frame| frame.frame).collect();
+        assert_eq!(frames, [0, 1, 2, 3, 4]);
+        for frame in &stats {
+            assert_eq!(frame.pic_struct, PicStruct::PROGRESSIVE);
+            assert_eq!(frame.avg_qp, Some(stub::ENCODED_QP));
+        }
+        let frame_types: Vec<_> = stats.iter().map(|frame| frame.packet.frame_type).collect();
+        assert_eq!(frame_types[0], FrameType::I | FrameType::REF | FrameType::IDR);
+        assert!(frame_types[1..].iter().all(|&frame_type| frame_type == FrameType::P | FrameType::REF));
+    }
+
+    #[test]
+    fn requests_qp_only_for_stats() {
+        let (_, stats) = encode_with_stub_key_frames(3, 2, ColorFourCC::Nv12, KeyFrames::default());
+        assert_eq!(stats.len(), 3);
+        assert!(stats.iter().all(|frame| frame.avg_qp.is_none()));
+    }
+
+    #[test]
+    fn encodes_without_qp_when_frame_info_is_rejected() {
+        for status in [MFX_ERR_INVALID_VIDEO_PARAM, MFX_ERR_UNDEFINED_BEHAVIOR] {
+            stub::ENCODED_FRAME_INFO_STATUS.with(|frame_info_status| frame_info_status.set(status));
+            let input = vec![0x80; FRAME_BYTES * 4];
+            let (result, stats) = encode_params_with_stub(&input, |params| params.stats = Some(StatsFormat::JsonLines));
+            assert_eq!(frame_numbers(&result.unwrap(), stub::PACKET_SIZE), [0, 1, 2, 3]);
+            assert!(stats.iter().all(|frame| frame.avg_qp.is_none()));
+        }
+    }
+
+    #[test]
+    fn run_reports_frames_and_summary() {
+        let name = format!("qsv-run-{}-{:?}", std::process::id(), thread::current().id());
+        let input = env::temp_dir().join(name.replace(|c: char| !c.is_ascii_alphanumeric(), "-") + ".yuv");
+        let output = input.with_extension("h264");
+        fs::write(&input, vec![0x80; FRAME_BYTES * 3]).unwrap();
+
+        let library = stub::pipeline_library();
+        let session = Session::with_library(library, MFX_IMPL_HARDWARE_ANY, mfxVersion::new(1, 0)).unwrap();
+        let params = Params {
+            input: input.to_string_lossy().into_owned(),
+            output: output.to_string_lossy().into_owned(),
+            width: WIDTH,
+            height: HEIGHT,
+            bitrate: 1000,
+            rate_control: RateControl::default(),
+            async_depth: DEFAULT_ASYNC_DEPTH,
+            input_format: ColorFourCC::Yv12,
+            force_key_frames: KeyFrames::default(),
+            stats: None,
+            timestamps: None,
+        };
+        let mut pts = Vec::new();
+        let summary = run(&session, &params, &mut |frame| {
+            pts.push(frame.packet.pts);
+            Ok(())
+        });
+        let _ = fs::remove_file(&input);
+        let _ = fs::remove_file(&output);
+        let summary = summary.unwrap();
+        assert_eq!(pts, [Some(0), Some(3000), Some(6000)]);
+        assert_eq!(summary.frames, 3);
+        assert!(summary.vpp);
+        assert!(summary.report.is_unchanged());
+    }
+
+    #[test]
+    fn needs_vpp_for_other_formats() {
+        let mut nv12 = mfxFrameInfo::new();
+        nv12.set_fourcc(ColorFourCC::Nv12);
+        nv12.Width = 64;
+        let mut yv12 = nv12;
+        yv12.set_fourcc(ColorFourCC::Yv12);
+        let mut scaled = nv12;
+        scaled.Width = 128;
+
+        assert!(!NeedsVpp(&nv12, &nv12));
+        assert!(NeedsVpp(&yv12, &nv12));
+        assert!(NeedsVpp(&scaled, &nv12));
+    }
+}
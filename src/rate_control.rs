@@ -0,0 +1,597 @@
+//! Typed bitrate control configurations.
+//!
+//! Each [`RateControl`] variant holds the parameters of one RateControlMethod. The SDK shares the mfxInfoMFX fields between the methods through unions (InitialDelayInKB, QPI and Accuracy are the same field), so setting them by hand for the wrong method silently configures something else; [`RateControl::apply`] writes exactly the fields of its method, and [`RateControl::validate`] rejects values the encoder would clamp or ignore before `Query` and `Init` see them.
+//!
+//! Bitrates and buffer sizes are 32-bit. The 16-bit fields of mfxInfoMFX reach them through BRCParamMultiplier, which [`BrcParams`] picks when writing and applies when reading.
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::constants::*;
+use crate::enums::RateControlMethod;
+use crate::ext_buffer::VideoParam;
+use crate::structures::*;
+
+/// Highest QP and quality factor of H.264.
+pub const MAX_QP: u16 = 51;
+/// Range of mfxExtCodingOption2::LookAheadDepth; zero lets the encoder choose.
+pub const LOOKAHEAD_DEPTH: (u16, u16) = (10, 100);
+/// Largest bitrate in Kbps or buffer size in KB: the largest 16-bit field times the largest BRCParamMultiplier.
+pub const MAX_BRC_VALUE: u32 = mfxU16::MAX as u32 * mfxU16::MAX as u32;
+
+/// The mfxInfoMFX fields scaled by BRCParamMultiplier: InitialDelayInKB, BufferSizeInKB, TargetKbps and MaxKbps.
+///
+/// The union fields are only bitrates and buffer sizes for the methods that use them; TargetKbps for example shares its field with QPP and ICQQuality.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BrcParams {
+    pub initial_delay_kb: u32,
+    pub buffer_size_kb: u32,
+    pub target_kbps: u32,
+    pub max_kbps: u32,
+}
+
+impl BrcParams {
+    /// The values of an encoder's `mfx`, e.g. as returned by `GetVideoParam`, multiplied by BRCParamMultiplier.
+    pub fn read(mfx: &mfxInfoMFX) -> Self {
+        let multiplier = mfx.BRCParamMultiplier.max(1) as u32;
+        let encode = mfx.as_encode();
+        unsafe {
+            BrcParams {
+                initial_delay_kb: encode.u1.InitialDelayInKB as u32 * multiplier,
+                buffer_size_kb: encode.BufferSizeInKB as u32 * multiplier,
+                target_kbps: encode.u2.TargetKbps as u32 * multiplier,
+                max_kbps: encode.u3.MaxKbps as u32 * multiplier,
+            }
+        }
+    }
+
+    /// The smallest BRCParamMultiplier with which every value fits its 16-bit field, 0 (no multiplier) if they all fit already.
+    pub fn multiplier(&self) -> mfxU16 {
+        let values = [self.initial_delay_kb, self.buffer_size_kb, self.target_kbps, self.max_kbps];
+        let largest = values.iter().copied().max().unwrap_or_default();
+        match largest.div_ceil(mfxU16::MAX as u32) {
+            0 | 1 => 0,
+            multiplier => multiplier.min(mfxU16::MAX as u32) as mfxU16,
+        }
+    }
+
+    /// Sets the fields and BRCParamMultiplier of an encoder's `mfx`. With a multiplier, values are rounded up to a multiple of it; values above [`MAX_BRC_VALUE`] saturate.
+    pub fn write(&self, mfx: &mut mfxInfoMFX) {
+        let multiplier = self.multiplier();
+        mfx.BRCParamMultiplier = multiplier;
+        let scale = |value: u32| value.div_ceil(multiplier.max(1) as u32).min(mfxU16::MAX as u32) as mfxU16;
+        let encode = mfx.as_encode_mut();
+        encode.u1.InitialDelayInKB = scale(self.initial_delay_kb);
+        encode.BufferSizeInKB = scale(self.buffer_size_kb);
+        encode.u2.TargetKbps = scale(self.target_kbps);
+        encode.u3.MaxKbps = scale(self.max_kbps);
+    }
+}
+
+/// Hypothetical reference decoder buffer of the HRD-compliant methods, in KB of 1000 bytes. Zero values are derived by the encoder from the bitrate, frame rate, profile and level.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Hrd {
+    /// BufferSizeInKB.
+    pub buffer_size_kb: u32,
+    /// InitialDelayInKB, how much of the buffer is filled before decoding starts.
+    pub initial_delay_kb: u32,
+}
+
+/// A bitrate control method with its parameters. Bitrates are in Kbps of 1000 bits per second.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateControl {
+    /// Constant bitrate.
+    Cbr { target_kbps: u32, hrd: Hrd },
+    /// Variable bitrate capped at `max_kbps`, zero for the encoder default.
+    Vbr { target_kbps: u32, max_kbps: u32, hrd: Hrd },
+    /// Constant QP per frame type; zero QPs are chosen by the encoder.
+    Cqp { qpi: u16, qpp: u16, qpb: u16 },
+    /// Average variable bitrate: within `accuracy` tenths of a percent of the target after `convergence` hundred frames, without HRD.
+    Avbr { target_kbps: u32, accuracy: u16, convergence: u16 },
+    /// Intelligent constant quality, `quality` from 1 (best) to 51.
+    Icq { quality: u16 },
+    /// VBR with lookahead analysis of `lookahead_depth` frames.
+    La { target_kbps: u32, lookahead_depth: u16 },
+    /// ICQ with lookahead analysis of `lookahead_depth` frames.
+    LaIcq { quality: u16, lookahead_depth: u16 },
+    /// Quality-defined VBR: `quality` from 1 (best) to 51 within the bitrate limits.
+    Qvbr {
+        target_kbps: u32,
+        max_kbps: u32,
+        quality: u16,
+        hrd: Hrd,
+    },
+}
+
+impl Default for RateControl {
+    /// VBR at the bitrate set later with [`RateControl::with_target_kbps`].
+    fn default() -> Self {
+        RateControl::Vbr {
+            target_kbps: 0,
+            max_kbps: 0,
+            hrd: Hrd::default(),
+        }
+    }
+}
+
+/// Error returned when parsing or validating a [`RateControl`] fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidRateControl(pub String);
+
+impl fmt::Display for InvalidRateControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid rate control: {}", self.0)
+    }
+}
+
+impl error::Error for InvalidRateControl {}
+
+fn invalid(message: impl Into<String>) -> InvalidRateControl {
+    InvalidRateControl(message.into())
+}
+
+impl RateControl {
+    pub fn method(&self) -> RateControlMethod {
+        match self {
+            RateControl::Cbr { .. } => RateControlMethod::Cbr,
+            RateControl::Vbr { .. } => RateControlMethod::Vbr,
+            RateControl::Cqp { .. } => RateControlMethod::Cqp,
+            RateControl::Avbr { .. } => RateControlMethod::Avbr,
+            RateControl::Icq { .. } => RateControlMethod::Icq,
+            RateControl::La { .. } => RateControlMethod::La,
+            RateControl::LaIcq { .. } => RateControlMethod::LaIcq,
+            RateControl::Qvbr { .. } => RateControlMethod::Qvbr,
+        }
+    }
+
+    /// The target bitrate, `None` for the methods driven by QP or quality.
+    pub fn target_kbps(&self) -> Option<u32> {
+        match *self {
+            RateControl::Cbr { target_kbps, .. }
+            | RateControl::Vbr { target_kbps, .. }
+            | RateControl::Avbr { target_kbps, .. }
+            | RateControl::La { target_kbps, .. }
+            | RateControl::Qvbr { target_kbps, .. } => Some(target_kbps),
+            RateControl::Cqp { .. } | RateControl::Icq { .. } | RateControl::LaIcq { .. } => None,
+        }
+    }
+
+    /// Sets the target bitrate of the methods that have one, the others are returned unchanged.
+    pub fn with_target_kbps(mut self, kbps: u32) -> Self {
+        match &mut self {
+            RateControl::Cbr { target_kbps, .. }
+            | RateControl::Vbr { target_kbps, .. }
+            | RateControl::Avbr { target_kbps, .. }
+            | RateControl::La { target_kbps, .. }
+            | RateControl::Qvbr { target_kbps, .. } => *target_kbps = kbps,
+            RateControl::Cqp { .. } | RateControl::Icq { .. } | RateControl::LaIcq { .. } => {}
+        }
+        self
+    }
+
+    /// Checks the parameters against the ranges of the SDK manual.
+    pub fn validate(&self) -> Result<(), InvalidRateControl> {
+        if self.target_kbps() == Some(0) {
+            return Err(invalid(format!("{:?} needs a target bitrate", self.method())));
+        }
+        let brc = self.brc_params();
+        for (name, value) in [
+            ("target bitrate", brc.target_kbps),
+            ("max bitrate", brc.max_kbps),
+            ("HRD buffer", brc.buffer_size_kb),
+            ("initial delay", brc.initial_delay_kb),
+        ] {
+            if value > MAX_BRC_VALUE {
+                return Err(invalid(format!("{name} {value} above {MAX_BRC_VALUE}")));
+            }
+        }
+        let check_max = |target_kbps: u32, max_kbps: u32| {
+            if max_kbps != 0 && max_kbps < target_kbps {
+                return Err(invalid(format!("max bitrate {max_kbps} below the target bitrate {target_kbps}")));
+            }
+            Ok(())
+        };
+        let check_hrd = |hrd: &Hrd| {
+            if hrd.buffer_size_kb != 0 && hrd.initial_delay_kb > hrd.buffer_size_kb {
+                return Err(invalid(format!(
+                    "initial delay {} KB exceeds the HRD buffer of {} KB",
+                    hrd.initial_delay_kb, hrd.buffer_size_kb
+                )));
+            }
+            Ok(())
+        };
+        let check_quality = |quality: u16| {
+            if !(1..=MAX_QP).contains(&quality) {
+                return Err(invalid(format!("quality {quality} outside 1..={MAX_QP}")));
+            }
+            Ok(())
+        };
+        let check_lookahead = |depth: u16| {
+            let (min, max) = LOOKAHEAD_DEPTH;
+            if depth != 0 && !(min..=max).contains(&depth) {
+                return Err(invalid(format!("lookahead depth {depth} outside {min}..={max}")));
+            }
+            Ok(())
+        };
+
+        match *self {
+            RateControl::Cbr { hrd, .. } => check_hrd(&hrd),
+            RateControl::Vbr {
+                target_kbps,
+                max_kbps,
+                hrd,
+            } => {
+                check_max(target_kbps, max_kbps)?;
+                check_hrd(&hrd)
+            }
+            RateControl::Cqp { qpi, qpp, qpb } => {
+                for (name, qp) in [("I", qpi), ("P", qpp), ("B", qpb)] {
+                    if qp > MAX_QP {
+                        return Err(invalid(format!("{name} frame QP {qp} above {MAX_QP}")));
+                    }
+                }
+                Ok(())
+            }
+            RateControl::Avbr { accuracy, .. } => {
+                if accuracy > 1000 {
+                    return Err(invalid(format!("accuracy {accuracy} above 1000 tenths of a percent")));
+                }
+                Ok(())
+            }
+            RateControl::Icq { quality } => check_quality(quality),
+            RateControl::La { lookahead_depth, .. } => check_lookahead(lookahead_depth),
+            RateControl::LaIcq {
+                quality,
+                lookahead_depth,
+            } => {
+                check_quality(quality)?;
+                check_lookahead(lookahead_depth)
+            }
+            RateControl::Qvbr {
+                target_kbps,
+                max_kbps,
+                quality,
+                hrd,
+            } => {
+                check_max(target_kbps, max_kbps)?;
+                check_quality(quality)?;
+                check_hrd(&hrd)
+            }
+        }
+    }
+
+    /// The bitrates and buffer sizes of this method; zero for the ones it does not use.
+    pub fn brc_params(&self) -> BrcParams {
+        let mut brc = BrcParams::default();
+        match *self {
+            RateControl::Cbr { target_kbps, hrd } => {
+                brc.target_kbps = target_kbps;
+                (brc.buffer_size_kb, brc.initial_delay_kb) = (hrd.buffer_size_kb, hrd.initial_delay_kb);
+            }
+            RateControl::Vbr {
+                target_kbps,
+                max_kbps,
+                hrd,
+            }
+            | RateControl::Qvbr {
+                target_kbps,
+                max_kbps,
+                hrd,
+                ..
+            } => {
+                (brc.target_kbps, brc.max_kbps) = (target_kbps, max_kbps);
+                (brc.buffer_size_kb, brc.initial_delay_kb) = (hrd.buffer_size_kb, hrd.initial_delay_kb);
+            }
+            RateControl::Avbr { target_kbps, .. } | RateControl::La { target_kbps, .. } => {
+                brc.target_kbps = target_kbps
+            }
+            RateControl::Cqp { .. } | RateControl::Icq { .. } | RateControl::LaIcq { .. } => {}
+        }
+        brc
+    }
+
+    /// Sets RateControlMethod and the fields of this method in `params`, with the BRCParamMultiplier its bitrates need, attaching mfxExtCodingOption2 for the lookahead depth and mfxExtCodingOption3 for the QVBR quality.
+    ///
+    /// The rate control fields of mfxInfoMFX are cleared first, so switching methods leaves nothing behind.
+    pub fn apply(&self, params: &mut VideoParam) {
+        if let Some(option) = params.ext_buffers_mut().get_mut::<mfxExtCodingOption2>() {
+            option.LookAheadDepth = 0;
+        }
+        if let Some(option) = params.ext_buffers_mut().get_mut::<mfxExtCodingOption3>() {
+            option.QVBRQuality = 0;
+        }
+        let mfx = params.mfx_mut();
+        mfx.as_encode_mut().set_rate_control_method(self.method());
+        // Also clears the QP and quality fields, which share the unions with the bitrates.
+        self.brc_params().write(mfx);
+
+        let encode = mfx.as_encode_mut();
+        match *self {
+            RateControl::Cbr { .. } | RateControl::Vbr { .. } => {}
+            RateControl::Cqp { qpi, qpp, qpb } => {
+                encode.u1.QPI = qpi;
+                encode.u2.QPP = qpp;
+                encode.u3.QPB = qpb;
+            }
+            RateControl::Avbr {
+                accuracy, convergence, ..
+            } => {
+                encode.u1.Accuracy = accuracy;
+                encode.u3.Convergence = convergence;
+            }
+            RateControl::Icq { quality } => encode.u2.ICQQuality = quality,
+            RateControl::La { lookahead_depth, .. } => {
+                params.ext_buffers_mut().get_or_default::<mfxExtCodingOption2>().LookAheadDepth = lookahead_depth;
+            }
+            RateControl::LaIcq {
+                quality,
+                lookahead_depth,
+            } => {
+                encode.u2.ICQQuality = quality;
+                params.ext_buffers_mut().get_or_default::<mfxExtCodingOption2>().LookAheadDepth = lookahead_depth;
+            }
+            RateControl::Qvbr { quality, .. } => {
+                params.ext_buffers_mut().get_or_default::<mfxExtCodingOption3>().QVBRQuality = quality;
+            }
+        }
+    }
+
+    /// Sets the parameter `key` of this method from `value`, see [`RateControl::from_str`].
+    fn set(&mut self, key: &str, value: &str) -> Result<(), InvalidRateControl> {
+        let method = self.method();
+        // Bitrates and buffer sizes
+        let kbps = match (&mut *self, key) {
+            (RateControl::Vbr { max_kbps, .. } | RateControl::Qvbr { max_kbps, .. }, "max") => Some(max_kbps),
+            (
+                RateControl::Cbr { hrd, .. } | RateControl::Vbr { hrd, .. } | RateControl::Qvbr { hrd, .. },
+                "buffer",
+            ) => Some(&mut hrd.buffer_size_kb),
+            (
+                RateControl::Cbr { hrd, .. } | RateControl::Vbr { hrd, .. } | RateControl::Qvbr { hrd, .. },
+                "delay",
+            ) => Some(&mut hrd.initial_delay_kb),
+            _ => None,
+        };
+        if let Some(field) = kbps {
+            *field = parse_number(key, value)?;
+            return Ok(());
+        }
+
+        let field = match (self, key) {
+            (RateControl::Cqp { qpi, qpp, qpb }, "qp") => {
+                let qps: Vec<u16> = value
+                    .split(':')
+                    .map(|qp| parse_number(key, qp))
+                    .collect::<Result<_, _>>()?;
+                // One QP for all frame types, or I:P:B.
+                (*qpi, *qpp, *qpb) = match qps[..] {
+                    [qp] => (qp, qp, qp),
+                    [i, p, b] => (i, p, b),
+                    _ => return Err(invalid(format!("qp={value}: expected QP or I:P:B"))),
+                };
+                return Ok(());
+            }
+            (
+                RateControl::Icq { quality } | RateControl::LaIcq { quality, .. } | RateControl::Qvbr { quality, .. },
+                "quality",
+            ) => quality,
+            (
+                RateControl::La { lookahead_depth, .. } | RateControl::LaIcq { lookahead_depth, .. },
+                "lookahead",
+            ) => lookahead_depth,
+            (RateControl::Avbr { accuracy, .. }, "accuracy") => accuracy,
+            (RateControl::Avbr { convergence, .. }, "convergence") => convergence,
+            _ => return Err(invalid(format!("{method:?} has no parameter {key:?}"))),
+        };
+        *field = parse_number(key, value)?;
+        Ok(())
+    }
+}
+
+fn parse_number<T: FromStr>(key: &str, value: &str) -> Result<T, InvalidRateControl>
+where
+    T::Err: fmt::Display,
+{
+    value
+        .parse()
+        .map_err(|err| invalid(format!("{key}={value}: {err}")))
+}
+
+impl FromStr for RateControl {
+    type Err = InvalidRateControl;
+
+    /// A method followed by comma-separated parameters, e.g. `cbr,buffer=2000,delay=1000`, `vbr,max=8000`,
+    /// `cqp,qp=22:24:26`, `avbr,accuracy=10,convergence=2`, `icq,quality=23`, `la,lookahead=40`,
+    /// `la_icq,quality=23,lookahead=40` or `qvbr,quality=23,max=8000`. The target bitrate is set separately with
+    /// [`RateControl::with_target_kbps`]; the quality of ICQ, LA_ICQ and QVBR defaults to 23.
+    fn from_str(s: &str) -> Result<Self, InvalidRateControl> {
+        let mut parts = s.split(',').map(str::trim);
+        let name = parts.next().unwrap_or_default();
+        let quality = 23;
+        let mut rate_control = match name.to_ascii_lowercase().as_str() {
+            "cbr" => RateControl::Cbr {
+                target_kbps: 0,
+                hrd: Hrd::default(),
+            },
+            "vbr" => RateControl::default(),
+            "cqp" => RateControl::Cqp { qpi: 0, qpp: 0, qpb: 0 },
+            "avbr" => RateControl::Avbr {
+                target_kbps: 0,
+                accuracy: 0,
+                convergence: 0,
+            },
+            "icq" => RateControl::Icq { quality },
+            "la" => RateControl::La {
+                target_kbps: 0,
+                lookahead_depth: 0,
+            },
+            "la_icq" => RateControl::LaIcq {
+                quality,
+                lookahead_depth: 0,
+            },
+            "qvbr" => RateControl::Qvbr {
+                target_kbps: 0,
+                max_kbps: 0,
+                quality,
+                hrd: Hrd::default(),
+            },
+            _ => return Err(invalid(format!("unknown method {name:?}"))),
+        };
+        for part in parts.filter(|part| !part.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| invalid(format!("expected key=value, got {part:?}")))?;
+            rate_control.set(key.trim(), value.trim())?;
+        }
+        Ok(rate_control)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_methods() {
+        assert_eq!("vbr".parse(), Ok(RateControl::default()));
+        assert_eq!(
+            "CBR, buffer=2000,delay=1000".parse::<RateControl>().unwrap().with_target_kbps(4000),
+            RateControl::Cbr {
+                target_kbps: 4000,
+                hrd: Hrd {
+                    buffer_size_kb: 2000,
+                    initial_delay_kb: 1000,
+                },
+            }
+        );
+        assert_eq!("cqp,qp=22:24:26".parse(), Ok(RateControl::Cqp { qpi: 22, qpp: 24, qpb: 26 }));
+        assert_eq!("cqp,qp=30".parse(), Ok(RateControl::Cqp { qpi: 30, qpp: 30, qpb: 30 }));
+        assert_eq!(
+            "la_icq,lookahead=40".parse(),
+            Ok(RateControl::LaIcq {
+                quality: 23,
+                lookahead_depth: 40,
+            })
+        );
+        let qvbr: RateControl = "qvbr,quality=18,max=8000".parse().unwrap();
+        assert_eq!(qvbr.method(), RateControlMethod::Qvbr);
+        assert_eq!(qvbr.with_target_kbps(5000).target_kbps(), Some(5000));
+        assert_eq!("icq".parse::<RateControl>().unwrap().with_target_kbps(5000).target_kbps(), None);
+
+        assert!("abr".parse::<RateControl>().is_err());
+        assert!("cqp,qp=1:2".parse::<RateControl>().is_err());
+        assert!("cbr,max=9000".parse::<RateControl>().is_err());
+        assert!("vbr,max".parse::<RateControl>().is_err());
+    }
+
+    #[test]
+    fn validates_ranges() {
+        let vbr = |max: &str| format!("vbr,{max}").parse::<RateControl>().unwrap().with_target_kbps(5000);
+        assert_eq!(vbr("max=8000").validate(), Ok(()));
+        assert!(vbr("max=4000").validate().is_err());
+        assert!(vbr("buffer=1000,delay=2000").validate().is_err());
+        assert!(RateControl::default().validate().is_err());
+
+        assert_eq!(RateControl::Cqp { qpi: 0, qpp: 51, qpb: 0 }.validate(), Ok(()));
+        assert!(RateControl::Cqp { qpi: 52, qpp: 0, qpb: 0 }.validate().is_err());
+        assert!(RateControl::Icq { quality: 0 }.validate().is_err());
+        assert_eq!(
+            RateControl::La { target_kbps: 5000, lookahead_depth: 5 }.validate().unwrap_err().to_string(),
+            "invalid rate control: lookahead depth 5 outside 10..=100"
+        );
+        assert!(RateControl::Avbr {
+            target_kbps: 5000,
+            accuracy: 2000,
+            convergence: 0,
+        }
+        .validate()
+        .is_err());
+        assert_eq!(vbr("max=200000").validate(), Ok(()));
+        assert!(RateControl::Avbr {
+            target_kbps: MAX_BRC_VALUE + 1,
+            accuracy: 0,
+            convergence: 0,
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn scales_with_multiplier() {
+        let mut mfx = mfxInfoMFX::new();
+        let brc = BrcParams {
+            initial_delay_kb: 1000,
+            buffer_size_kb: 100_000,
+            target_kbps: 200_000,
+            max_kbps: 300_001,
+        };
+        assert_eq!(brc.multiplier(), 5);
+        brc.write(&mut mfx);
+        assert_eq!(mfx.BRCParamMultiplier, 5);
+        let encode = mfx.as_encode();
+        let kbps = unsafe { (encode.u1.InitialDelayInKB, encode.u2.TargetKbps, encode.u3.MaxKbps) };
+        assert_eq!(kbps, (200, 40000, 60001));
+        assert_eq!(encode.BufferSizeInKB, 20000);
+        // Rounded up to a multiple of the multiplier
+        assert_eq!(BrcParams::read(&mfx), BrcParams { max_kbps: 300_005, ..brc });
+
+        let brc = BrcParams {
+            target_kbps: 65535,
+            ..BrcParams::default()
+        };
+        assert_eq!(brc.multiplier(), 0);
+        brc.write(&mut mfx);
+        assert_eq!(mfx.BRCParamMultiplier, 0);
+        assert_eq!(BrcParams::read(&mfx), brc);
+        assert_eq!(BrcParams { max_kbps: MAX_BRC_VALUE, ..brc }.multiplier(), 65535);
+
+        let mut params = VideoParam::new();
+        "vbr,max=400000,buffer=100000".parse::<RateControl>().unwrap().with_target_kbps(200_000).apply(&mut params);
+        assert_eq!(params.mfx().BRCParamMultiplier, 7);
+        assert_eq!(BrcParams::read(params.mfx()).max_kbps, 400_001);
+    }
+
+    #[test]
+    fn applies_method_fields() {
+        let mut params = VideoParam::new();
+        RateControl::Vbr {
+            target_kbps: 5000,
+            max_kbps: 8000,
+            hrd: Hrd {
+                buffer_size_kb: 2000,
+                initial_delay_kb: 1000,
+            },
+        }
+        .apply(&mut params);
+        let encode = params.mfx().as_encode();
+        assert_eq!(encode.rate_control_method(), Ok(RateControlMethod::Vbr));
+        let kbps = unsafe { (encode.u1.InitialDelayInKB, encode.u2.TargetKbps, encode.u3.MaxKbps) };
+        assert_eq!(kbps, (1000, 5000, 8000));
+        assert_eq!(encode.BufferSizeInKB, 2000);
+
+        RateControl::Cqp { qpi: 22, qpp: 24, qpb: 26 }.apply(&mut params);
+        let encode = params.mfx().as_encode();
+        assert_eq!(unsafe { (encode.u1.QPI, encode.u2.QPP, encode.u3.QPB) }, (22, 24, 26));
+        assert_eq!(encode.BufferSizeInKB, 0);
+        assert!(params.ext_buffers().get::<mfxExtCodingOption2>().is_none());
+
+        RateControl::LaIcq {
+            quality: 20,
+            lookahead_depth: 40,
+        }
+        .apply(&mut params);
+        assert_eq!(unsafe { params.mfx().as_encode().u2.ICQQuality }, 20);
+        assert_eq!(params.ext_buffers().get::<mfxExtCodingOption2>().unwrap().LookAheadDepth, 40);
+
+        RateControl::Qvbr {
+            target_kbps: 5000,
+            max_kbps: 0,
+            quality: 18,
+            hrd: Hrd::default(),
+        }
+        .apply(&mut params);
+        assert_eq!(params.mfx().as_encode().rate_control_method(), Ok(RateControlMethod::Qvbr));
+        assert_eq!(params.ext_buffers().get::<mfxExtCodingOption3>().unwrap().QVBRQuality, 18);
+        assert_eq!(params.ext_buffers().get::<mfxExtCodingOption2>().unwrap().LookAheadDepth, 0);
+    }
+}
@@ -0,0 +1,180 @@
+use std::ptr;
+use std::sync::Arc;
+
+use crate::allocator::{FrameAllocator, RegisteredAllocator};
+use crate::constants::*;
+use crate::error::Result;
+use crate::dispatcher::{Implementation, ImplementationFilter, Loader};
+use crate::loader::{self, Library};
+use crate::structures::mfxVersion;
+use crate::utils::check_error;
+
+/// An SDK session, created through the oneVPL dispatcher or with `MFXInit`, and closed with `MFXClose` when dropped.
+///
+/// Every encoder, decoder and VPP component runs inside a session; the session must outlive all of them.
+pub struct Session {
+    library: Arc<Library>,
+    handle: *mut mfxSession,
+    // Only dropped, which unloads the dispatcher, after `drop` has closed the session.
+    _loader: Option<Loader>,
+    description: Option<Implementation>,
+    // Called back by the SDK until `drop` has closed the session.
+    allocator: Option<Box<RegisteredAllocator>>,
+}
+
+impl Session {
+    /// Creates a session on the first implementation matching `filter`, using the library found by [`loader::default_library`].
+    pub fn open(filter: &ImplementationFilter) -> Result<Self> {
+        Session::open_with_library(loader::default_library()?, filter)
+    }
+
+    /// Creates a session on the first implementation matching `filter`.
+    ///
+    /// With a oneVPL dispatcher the implementations are enumerated with `MFXEnumImplementations` and the session is created with `MFXCreateSession`. If the library is a Media SDK 1.x library without the dispatcher entry points, or the dispatcher finds no matching implementation, this falls back to `MFXInit` with [`ImplementationFilter::legacy_init`]. Errors of the dispatcher, e.g. `MFXCreateSession` failing on an implementation it found, are returned.
+    pub fn open_with_library(library: Arc<Library>, filter: &ImplementationFilter) -> Result<Self> {
+        if let Some(session) = Session::open_dispatched(&library, filter)? {
+            return Ok(session);
+        }
+        let (implementation, version) = filter.legacy_init();
+        Session::with_library(library, implementation, version)
+    }
+
+    fn open_dispatched(library: &Arc<Library>, filter: &ImplementationFilter) -> Result<Option<Self>> {
+        let Some(loader) = Loader::new(library.clone(), filter)? else {
+            return Ok(None);
+        };
+        let Some(description) = loader.implementations()?.into_iter().next() else {
+            return Ok(None);
+        };
+        let handle = loader.create_session(description.index)?;
+        Ok(Some(Session {
+            library: library.clone(),
+            handle,
+            _loader: Some(loader),
+            description: Some(description),
+            allocator: None,
+        }))
+    }
+
+    /// Creates and initializes an SDK session using the library found by [`loader::default_library`].
+    ///
+    /// # Arguments
+    /// * `implementation` - mfxIMPL enumerator that indicates the desired SDK implementation
+    /// * `version` - The minimum library version
+    pub fn new(implementation: mfxIMPL, version: mfxVersion) -> Result<Self> {
+        Session::with_library(loader::default_library()?, implementation, version)
+    }
+
+    /// Creates and initializes an SDK session using an already loaded library.
+    pub fn with_library(library: Arc<Library>, implementation: mfxIMPL, version: mfxVersion) -> Result<Self> {
+        let mut handle: *mut mfxSession = ptr::null_mut();
+        check_error(unsafe { (library.MFXInit)(implementation, &version, &mut handle) })?;
+        Ok(Session {
+            library,
+            handle,
+            _loader: None,
+            description: None,
+            allocator: None,
+        })
+    }
+
+    /// Returns the implementation type of the session, see `MFXQueryIMPL`.
+    pub fn implementation(&self) -> Result<mfxIMPL> {
+        let mut implementation: mfxIMPL = MFX_IMPL_AUTO;
+        check_error(unsafe { (self.library.MFXQueryIMPL)(self.handle, &mut implementation) })?;
+        Ok(implementation)
+    }
+
+    /// The implementation chosen by the oneVPL dispatcher, `None` for sessions created with `MFXInit`.
+    pub fn description(&self) -> Option<&Implementation> {
+        self.description.as_ref()
+    }
+
+    /// Makes the SDK allocate frames with `allocator`, see `MFXVideoCORE_SetFrameAllocator`.
+    ///
+    /// Must be called before initializing the components that should use it. The allocator lives as long as the session; frames the SDK has not freed when the session is closed are freed then.
+    pub fn set_frame_allocator(&mut self, allocator: impl FrameAllocator + 'static) -> Result<()> {
+        let allocator = RegisteredAllocator::new(allocator);
+        check_error(unsafe { (self.library.MFXVideoCORE_SetFrameAllocator)(self.handle, allocator.as_ptr()) })?;
+        self.allocator = Some(allocator);
+        Ok(())
+    }
+
+    /// The library the session was created with.
+    pub fn library(&self) -> &Library {
+        &self.library
+    }
+
+    /// Raw session handle to pass to the SDK functions.
+    pub fn as_ptr(&self) -> *const mfxSession {
+        self.handle
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        unsafe { (self.library.MFXClose)(self.handle) };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dispatcher::{PROPERTY_ENCODER_CODEC_ID, PROPERTY_IMPL};
+    use crate::enums::CodecId;
+    use crate::loader::stub;
+    use crate::status::MfxStatus;
+
+    #[test]
+    fn closes_on_drop() {
+        let session = Session::with_library(stub::library(), MFX_IMPL_HARDWARE_ANY, mfxVersion::new(1, 0)).unwrap();
+        assert_eq!(session.implementation().unwrap(), MFX_IMPL_HARDWARE);
+        assert_eq!(stub::OPEN_SESSIONS.with(|open| open.get()), 1);
+
+        drop(session);
+        assert_eq!(stub::OPEN_SESSIONS.with(|open| open.get()), 0);
+    }
+
+    #[test]
+    fn opens_through_dispatcher() {
+        let filter = ImplementationFilter::hardware_encoder(CodecId::Avc);
+        let session = Session::open_with_library(stub::dispatcher_library(), &filter).unwrap();
+        assert_eq!(session.description().unwrap().name, "stub");
+        assert_eq!(stub::OPEN_SESSIONS.with(|open| open.get()), 1);
+        assert_eq!(stub::LOADERS.with(|loaders| loaders.get()), 1);
+        stub::FILTERS.with(|filters| {
+            let filters = filters.borrow();
+            assert!(filters.contains(&(PROPERTY_IMPL.to_string(), MFX_IMPL_TYPE_HARDWARE)));
+            assert!(filters.contains(&(PROPERTY_ENCODER_CODEC_ID.to_string(), MFX_CODEC_AVC)));
+        });
+
+        drop(session);
+        assert_eq!(stub::OPEN_SESSIONS.with(|open| open.get()), 0);
+        assert_eq!(stub::LOADERS.with(|loaders| loaders.get()), 0);
+    }
+
+    #[test]
+    fn falls_back_to_mfx_init() {
+        let filter = ImplementationFilter::hardware_encoder(CodecId::Hevc);
+        let session = Session::open_with_library(stub::dispatcher_library(), &filter).unwrap();
+        assert!(session.description().is_none());
+        assert_eq!(stub::OPEN_SESSIONS.with(|open| open.get()), 1);
+        assert_eq!(stub::LOADERS.with(|loaders| loaders.get()), 0);
+        drop(session);
+
+        let session = Session::open_with_library(stub::library(), &filter).unwrap();
+        assert!(session.description().is_none());
+        assert_eq!(session.implementation().unwrap(), MFX_IMPL_HARDWARE);
+    }
+
+    #[test]
+    fn reports_dispatcher_errors() {
+        let library = stub::dispatcher_library();
+        stub::CREATE_SESSION_STATUS.with(|status| status.set(MFX_ERR_DEVICE_FAILED));
+        let filter = ImplementationFilter::hardware_encoder(CodecId::Avc);
+        let err = Session::open_with_library(library, &filter).err().unwrap();
+        assert_eq!(err.status(), Some(MfxStatus::DeviceFailed));
+        assert_eq!(stub::OPEN_SESSIONS.with(|open| open.get()), 0);
+        assert_eq!(stub::LOADERS.with(|loaders| loaders.get()), 0);
+    }
+}
@@ -0,0 +1,243 @@
+//! Per-frame encoding statistics.
+//!
+//! [`FrameStats`] describes one encoded frame once its sync point completed: the packet, how long the encoder took and the QP it used. [`StatsWriter`] writes one record per frame as CSV or JSON Lines, e.g. next to the bitstream for quality dashboards.
+use std::error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::enums::{FrameType, PicStruct};
+use crate::packet::Packet;
+
+/// Statistics of one encoded frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameStats {
+    /// Number of the frame, counting the frames given to the encoder from 0.
+    pub frame: u32,
+    /// PTS, DTS, frame type and DataLength of the bitstream.
+    pub packet: Packet,
+    pub pic_struct: PicStruct,
+    /// Time from submitting the frame to the encoder until its sync point completed.
+    pub latency: Duration,
+    /// Luma QP reported through mfxExtAVCEncodedFrameInfo, `None` if the encoder did not report it.
+    pub avg_qp: Option<u16>,
+}
+
+/// Columns of the CSV output, also the keys of the JSON objects.
+pub const STATS_COLUMNS: [&str; 8] =
+    ["frame", "pts", "dts", "frame_type", "pic_struct", "data_length", "latency_us", "avg_qp"];
+
+/// "IDR", "I", "P" or "B" for the frame or its first field, empty if the type is unknown.
+fn frame_type_name(frame_type: FrameType) -> &'static str {
+    if frame_type.contains(FrameType::IDR) {
+        "IDR"
+    } else if frame_type.contains(FrameType::I) {
+        "I"
+    } else if frame_type.contains(FrameType::P) {
+        "P"
+    } else if frame_type.contains(FrameType::B) {
+        "B"
+    } else {
+        ""
+    }
+}
+
+/// "progressive", "tff" or "bff", empty if the picture structure is unknown.
+fn pic_struct_name(pic_struct: PicStruct) -> &'static str {
+    if pic_struct.contains(PicStruct::FIELD_TFF) {
+        "tff"
+    } else if pic_struct.contains(PicStruct::FIELD_BFF) {
+        "bff"
+    } else if pic_struct.contains(PicStruct::PROGRESSIVE) {
+        "progressive"
+    } else {
+        ""
+    }
+}
+
+/// Output format of [`StatsWriter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsFormat {
+    /// A header line with [`STATS_COLUMNS`], then one line per frame; unknown values are empty.
+    Csv,
+    /// One JSON object per line; unknown values are `null`.
+    JsonLines,
+}
+
+impl StatsFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            StatsFormat::Csv => "csv",
+            StatsFormat::JsonLines => "jsonl",
+        }
+    }
+
+    /// The statistics file next to the bitstream `output`: `out.h264` gets `out.stats.csv`.
+    pub fn stats_path(self, output: impl AsRef<Path>) -> PathBuf {
+        output.as_ref().with_extension(format!("stats.{}", self.extension()))
+    }
+}
+
+impl fmt::Display for StatsFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+/// Error returned when parsing a [`StatsFormat`] fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownStatsFormat(pub String);
+
+impl fmt::Display for UnknownStatsFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown statistics format {:?}, expected csv or jsonl", self.0)
+    }
+}
+
+impl error::Error for UnknownStatsFormat {}
+
+impl FromStr for StatsFormat {
+    type Err = UnknownStatsFormat;
+
+    fn from_str(s: &str) -> Result<Self, UnknownStatsFormat> {
+        match s.to_ascii_lowercase().as_str() {
+            "csv" => Ok(StatsFormat::Csv),
+            "jsonl" => Ok(StatsFormat::JsonLines),
+            _ => Err(UnknownStatsFormat(s.to_string())),
+        }
+    }
+}
+
+/// Writes [`FrameStats`] to `out`, one record per line.
+pub struct StatsWriter<W: Write> {
+    out: W,
+    format: StatsFormat,
+    header_written: bool,
+}
+
+impl StatsWriter<BufWriter<File>> {
+    /// Creates or truncates the file at `path`.
+    pub fn create(path: impl AsRef<Path>, format: StatsFormat) -> io::Result<Self> {
+        Ok(StatsWriter::new(BufWriter::new(File::create(path)?), format))
+    }
+}
+
+impl<W: Write> StatsWriter<W> {
+    pub fn new(out: W, format: StatsFormat) -> Self {
+        StatsWriter {
+            out,
+            format,
+            header_written: false,
+        }
+    }
+
+    pub fn write(&mut self, stats: &FrameStats) -> io::Result<()> {
+        let packet = &stats.packet;
+        let frame_type = frame_type_name(packet.frame_type);
+        let pic_struct = pic_struct_name(stats.pic_struct);
+        let latency = stats.latency.as_micros();
+        match self.format {
+            StatsFormat::Csv => {
+                if !self.header_written {
+                    writeln!(self.out, "{}", STATS_COLUMNS.join(","))?;
+                    self.header_written = true;
+                }
+                writeln!(
+                    self.out,
+                    "{},{},{},{frame_type},{pic_struct},{},{latency},{}",
+                    stats.frame,
+                    csv_value(packet.pts),
+                    csv_value(packet.dts),
+                    packet.size,
+                    csv_value(stats.avg_qp),
+                )
+            }
+            StatsFormat::JsonLines => writeln!(
+                self.out,
+                "{{\"frame\":{},\"pts\":{},\"dts\":{},\"frame_type\":\"{frame_type}\",\"pic_struct\":\"{pic_struct}\",\
+                 \"data_length\":{},\"latency_us\":{latency},\"avg_qp\":{}}}",
+                stats.frame,
+                json_value(packet.pts),
+                json_value(packet.dts),
+                packet.size,
+                json_value(stats.avg_qp),
+            ),
+        }
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+fn csv_value(value: Option<impl fmt::Display>) -> String {
+    value.map_or_else(String::new, |value| value.to_string())
+}
+
+fn json_value(value: Option<impl fmt::Display>) -> String {
+    value.map_or_else(|| "null".to_string(), |value| value.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn stats(frame: u32, frame_type: FrameType, avg_qp: Option<u16>) -> FrameStats {
+        FrameStats {
+            frame,
+            packet: Packet {
+                pts: Some(3000 * frame as u64),
+                dts: None,
+                frame_type,
+                size: 1200,
+            },
+            pic_struct: PicStruct::PROGRESSIVE,
+            latency: Duration::from_micros(1500),
+            avg_qp,
+        }
+    }
+
+    fn write(format: StatsFormat) -> String {
+        let mut writer = StatsWriter::new(Vec::new(), format);
+        writer.write(&stats(0, FrameType::I | FrameType::REF | FrameType::IDR, Some(24))).unwrap();
+        writer.write(&stats(1, FrameType::B, None)).unwrap();
+        String::from_utf8(writer.into_inner()).unwrap()
+    }
+
+    #[test]
+    fn writes_csv() {
+        assert_eq!(
+            write(StatsFormat::Csv),
+            "frame,pts,dts,frame_type,pic_struct,data_length,latency_us,avg_qp\n\
+             0,0,,IDR,progressive,1200,1500,24\n\
+             1,3000,,B,progressive,1200,1500,\n"
+        );
+    }
+
+    #[test]
+    fn writes_json_lines() {
+        assert_eq!(
+            write(StatsFormat::JsonLines),
+            "{\"frame\":0,\"pts\":0,\"dts\":null,\"frame_type\":\"IDR\",\"pic_struct\":\"progressive\",\
+             \"data_length\":1200,\"latency_us\":1500,\"avg_qp\":24}\n\
+             {\"frame\":1,\"pts\":3000,\"dts\":null,\"frame_type\":\"B\",\"pic_struct\":\"progressive\",\
+             \"data_length\":1200,\"latency_us\":1500,\"avg_qp\":null}\n"
+        );
+    }
+
+    #[test]
+    fn parses_format() {
+        assert_eq!("CSV".parse(), Ok(StatsFormat::Csv));
+        assert_eq!("jsonl".parse(), Ok(StatsFormat::JsonLines));
+        assert_eq!("xml".parse::<StatsFormat>(), Err(UnknownStatsFormat("xml".into())));
+        assert_eq!(StatsFormat::Csv.stats_path("out/video.h264"), Path::new("out/video.stats.csv"));
+        assert_eq!(StatsFormat::JsonLines.stats_path("video"), Path::new("video.stats.jsonl"));
+    }
+}
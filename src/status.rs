@@ -0,0 +1,309 @@
+//! Typed SDK status codes.
+//!
+//! Every SDK function returns an `mfxStatus`: zero on success, a negative error or a positive warning. [`MfxStatus`] names every code and [`MfxStatus::class`] sorts it into what the caller has to do next.
+use std::fmt;
+
+use crate::constants::*;
+use crate::error::{Error, Result};
+
+/// An `mfxStatus` returned by the SDK.
+///
+/// See <https://github.com/Intel-Media-SDK/MediaSDK/blob/master/api/include/mfxdefs.h> for the meaning of each code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MfxStatus {
+    /// `MFX_ERR_NONE`
+    None,
+
+    /// `MFX_ERR_UNKNOWN`
+    Unknown,
+    /// `MFX_ERR_NULL_PTR`
+    NullPtr,
+    /// `MFX_ERR_UNSUPPORTED`
+    Unsupported,
+    /// `MFX_ERR_MEMORY_ALLOC`
+    MemoryAlloc,
+    /// `MFX_ERR_NOT_ENOUGH_BUFFER`
+    NotEnoughBuffer,
+    /// `MFX_ERR_INVALID_HANDLE`
+    InvalidHandle,
+    /// `MFX_ERR_LOCK_MEMORY`
+    LockMemory,
+    /// `MFX_ERR_NOT_INITIALIZED`
+    NotInitialized,
+    /// `MFX_ERR_NOT_FOUND`
+    NotFound,
+    /// `MFX_ERR_MORE_DATA`
+    MoreData,
+    /// `MFX_ERR_MORE_SURFACE`
+    MoreSurface,
+    /// `MFX_ERR_ABORTED`
+    Aborted,
+    /// `MFX_ERR_DEVICE_LOST`
+    DeviceLost,
+    /// `MFX_ERR_INCOMPATIBLE_VIDEO_PARAM`
+    IncompatibleVideoParam,
+    /// `MFX_ERR_INVALID_VIDEO_PARAM`
+    InvalidVideoParam,
+    /// `MFX_ERR_UNDEFINED_BEHAVIOR`
+    UndefinedBehavior,
+    /// `MFX_ERR_DEVICE_FAILED`
+    DeviceFailed,
+    /// `MFX_ERR_MORE_BITSTREAM`
+    MoreBitstream,
+    /// `MFX_ERR_INCOMPATIBLE_AUDIO_PARAM`
+    IncompatibleAudioParam,
+    /// `MFX_ERR_INVALID_AUDIO_PARAM`
+    InvalidAudioParam,
+    /// `MFX_ERR_GPU_HANG`
+    GpuHang,
+    /// `MFX_ERR_REALLOC_SURFACE`
+    ReallocSurface,
+    /// `MFX_ERR_MORE_DATA_SUBMIT_TASK`
+    MoreDataSubmitTask,
+
+    /// `MFX_WRN_IN_EXECUTION`
+    InExecution,
+    /// `MFX_WRN_DEVICE_BUSY`
+    DeviceBusy,
+    /// `MFX_WRN_VIDEO_PARAM_CHANGED`
+    VideoParamChanged,
+    /// `MFX_WRN_PARTIAL_ACCELERATION`
+    PartialAcceleration,
+    /// `MFX_WRN_INCOMPATIBLE_VIDEO_PARAM`
+    IncompatibleVideoParamWarning,
+    /// `MFX_WRN_VALUE_NOT_CHANGED`
+    ValueNotChanged,
+    /// `MFX_WRN_OUT_OF_RANGE`
+    OutOfRange,
+    /// `MFX_WRN_FILTER_SKIPPED`
+    FilterSkipped,
+    /// `MFX_WRN_INCOMPATIBLE_AUDIO_PARAM`
+    IncompatibleAudioParamWarning,
+    /// `MFX_ERR_NONE_PARTIAL_OUTPUT`
+    NonePartialOutput,
+    /// `MFX_TASK_WORKING`
+    TaskWorking,
+    /// `MFX_TASK_BUSY`
+    TaskBusy,
+
+    /// A code this crate does not know about.
+    Other(mfxStatus),
+}
+
+/// What the caller of an SDK function has to do after it returned a status, see [`MfxStatus::class`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatusClass {
+    /// The call succeeded.
+    Success,
+    /// The call succeeded, but the SDK adjusted or ignored part of the request.
+    Warning,
+    /// The device is busy; repeat the same call after a short wait.
+    Retry,
+    /// Submit more input before output is available; at the end of the stream this means the component is drained.
+    MoreInput,
+    /// Provide another output surface or a bigger bitstream buffer and repeat the call.
+    MoreOutput,
+    /// The call failed.
+    Fatal,
+}
+
+macro_rules! statuses {
+    ($($variant:ident = $code:ident),* $(,)?) => {
+        impl MfxStatus {
+            /// Converts a raw status code.
+            pub fn from_raw(status: mfxStatus) -> Self {
+                match status {
+                    $($code => MfxStatus::$variant,)*
+                    other => MfxStatus::Other(other),
+                }
+            }
+
+            /// The raw status code.
+            pub fn raw(self) -> mfxStatus {
+                match self {
+                    $(MfxStatus::$variant => $code,)*
+                    MfxStatus::Other(other) => other,
+                }
+            }
+
+            /// Name of the SDK constant, e.g. `MFX_ERR_MORE_DATA`.
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(MfxStatus::$variant => stringify!($code),)*
+                    MfxStatus::Other(status) if status < 0 => "MFX_ERR_UNKNOWN",
+                    MfxStatus::Other(_) => "MFX_WRN_UNKNOWN",
+                }
+            }
+        }
+    };
+}
+
+statuses! {
+    None = MFX_ERR_NONE,
+    Unknown = MFX_ERR_UNKNOWN,
+    NullPtr = MFX_ERR_NULL_PTR,
+    Unsupported = MFX_ERR_UNSUPPORTED,
+    MemoryAlloc = MFX_ERR_MEMORY_ALLOC,
+    NotEnoughBuffer = MFX_ERR_NOT_ENOUGH_BUFFER,
+    InvalidHandle = MFX_ERR_INVALID_HANDLE,
+    LockMemory = MFX_ERR_LOCK_MEMORY,
+    NotInitialized = MFX_ERR_NOT_INITIALIZED,
+    NotFound = MFX_ERR_NOT_FOUND,
+    MoreData = MFX_ERR_MORE_DATA,
+    MoreSurface = MFX_ERR_MORE_SURFACE,
+    Aborted = MFX_ERR_ABORTED,
+    DeviceLost = MFX_ERR_DEVICE_LOST,
+    IncompatibleVideoParam = MFX_ERR_INCOMPATIBLE_VIDEO_PARAM,
+    InvalidVideoParam = MFX_ERR_INVALID_VIDEO_PARAM,
+    UndefinedBehavior = MFX_ERR_UNDEFINED_BEHAVIOR,
+    DeviceFailed = MFX_ERR_DEVICE_FAILED,
+    MoreBitstream = MFX_ERR_MORE_BITSTREAM,
+    IncompatibleAudioParam = MFX_ERR_INCOMPATIBLE_AUDIO_PARAM,
+    InvalidAudioParam = MFX_ERR_INVALID_AUDIO_PARAM,
+    GpuHang = MFX_ERR_GPU_HANG,
+    ReallocSurface = MFX_ERR_REALLOC_SURFACE,
+    MoreDataSubmitTask = MFX_ERR_MORE_DATA_SUBMIT_TASK,
+    InExecution = MFX_WRN_IN_EXECUTION,
+    DeviceBusy = MFX_WRN_DEVICE_BUSY,
+    VideoParamChanged = MFX_WRN_VIDEO_PARAM_CHANGED,
+    PartialAcceleration = MFX_WRN_PARTIAL_ACCELERATION,
+    IncompatibleVideoParamWarning = MFX_WRN_INCOMPATIBLE_VIDEO_PARAM,
+    ValueNotChanged = MFX_WRN_VALUE_NOT_CHANGED,
+    OutOfRange = MFX_WRN_OUT_OF_RANGE,
+    FilterSkipped = MFX_WRN_FILTER_SKIPPED,
+    IncompatibleAudioParamWarning = MFX_WRN_INCOMPATIBLE_AUDIO_PARAM,
+    NonePartialOutput = MFX_ERR_NONE_PARTIAL_OUTPUT,
+    TaskWorking = MFX_TASK_WORKING,
+    TaskBusy = MFX_TASK_BUSY,
+}
+
+impl MfxStatus {
+    /// `MFX_ERR_NONE`.
+    pub fn is_ok(self) -> bool {
+        self == MfxStatus::None
+    }
+
+    /// Negative codes, including the flow-control ones like `MFX_ERR_MORE_DATA`.
+    pub fn is_error(self) -> bool {
+        self.raw() < 0
+    }
+
+    /// Positive codes.
+    pub fn is_warning(self) -> bool {
+        self.raw() > 0
+    }
+
+    /// Sorts the status by the action it requires from the caller.
+    pub fn class(self) -> StatusClass {
+        match self {
+            MfxStatus::None => StatusClass::Success,
+            MfxStatus::DeviceBusy | MfxStatus::InExecution | MfxStatus::TaskBusy | MfxStatus::TaskWorking => {
+                StatusClass::Retry
+            }
+            MfxStatus::MoreData | MfxStatus::MoreDataSubmitTask => StatusClass::MoreInput,
+            MfxStatus::MoreSurface | MfxStatus::NotEnoughBuffer | MfxStatus::MoreBitstream => StatusClass::MoreOutput,
+            status if status.is_warning() => StatusClass::Warning,
+            _ => StatusClass::Fatal,
+        }
+    }
+
+    /// `Ok` with the status for `MFX_ERR_NONE` and warnings, [`Error::Status`] for errors.
+    pub fn result(self) -> Result<MfxStatus> {
+        if self.is_error() {
+            Err(Error::Status(self))
+        } else {
+            Ok(self)
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            MfxStatus::None => "no error",
+            MfxStatus::Unknown => "unknown error",
+            MfxStatus::NullPtr => "null pointer",
+            MfxStatus::Unsupported => "unsupported",
+            MfxStatus::MemoryAlloc => "memory allocation failed",
+            MfxStatus::NotEnoughBuffer => "not enough buffer",
+            MfxStatus::InvalidHandle => "invalid handle",
+            MfxStatus::LockMemory => "failed to lock memory",
+            MfxStatus::NotInitialized => "not initialized",
+            MfxStatus::NotFound => "not found",
+            MfxStatus::MoreData => "more data",
+            MfxStatus::MoreSurface => "more surface",
+            MfxStatus::Aborted => "aborted",
+            MfxStatus::DeviceLost => "device lost",
+            MfxStatus::IncompatibleVideoParam => "incompatible video param",
+            MfxStatus::InvalidVideoParam => "invalid video param",
+            MfxStatus::UndefinedBehavior => "undefined behavior",
+            MfxStatus::DeviceFailed => "device failed",
+            MfxStatus::MoreBitstream => "more bitstream",
+            MfxStatus::IncompatibleAudioParam => "incompatible audio param",
+            MfxStatus::InvalidAudioParam => "invalid audio param",
+            MfxStatus::GpuHang => "GPU hang",
+            MfxStatus::ReallocSurface => "bigger surface required",
+            MfxStatus::MoreDataSubmitTask => "more data, task submitted",
+            MfxStatus::InExecution => "in execution",
+            MfxStatus::DeviceBusy => "device busy",
+            MfxStatus::VideoParamChanged => "video param changed",
+            MfxStatus::PartialAcceleration => "partial acceleration",
+            MfxStatus::IncompatibleVideoParamWarning => "incompatible video param resolved",
+            MfxStatus::ValueNotChanged => "value not changed",
+            MfxStatus::OutOfRange => "out of range",
+            MfxStatus::FilterSkipped => "filter skipped",
+            MfxStatus::IncompatibleAudioParamWarning => "incompatible audio param resolved",
+            MfxStatus::NonePartialOutput => "partial output",
+            MfxStatus::TaskWorking => "task working",
+            MfxStatus::TaskBusy => "task busy",
+            MfxStatus::Other(_) => "unknown status",
+        }
+    }
+}
+
+impl From<mfxStatus> for MfxStatus {
+    fn from(status: mfxStatus) -> Self {
+        MfxStatus::from_raw(status)
+    }
+}
+
+impl From<MfxStatus> for mfxStatus {
+    fn from(status: MfxStatus) -> Self {
+        status.raw()
+    }
+}
+
+impl fmt::Display for MfxStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({} = {})", self.description(), self.name(), self.raw())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for raw in (-22..=12).chain([MFX_ERR_MORE_DATA_SUBMIT_TASK, 42, -99]) {
+            assert_eq!(MfxStatus::from_raw(raw).raw(), raw);
+        }
+        assert_eq!(MfxStatus::from_raw(-99), MfxStatus::Other(-99));
+        assert_eq!(MfxStatus::from_raw(MFX_WRN_DEVICE_BUSY), MfxStatus::DeviceBusy);
+        assert_eq!(MfxStatus::MoreData.to_string(), "more data (MFX_ERR_MORE_DATA = -10)");
+    }
+
+    #[test]
+    fn classes() {
+        assert_eq!(MfxStatus::None.class(), StatusClass::Success);
+        assert_eq!(MfxStatus::PartialAcceleration.class(), StatusClass::Warning);
+        assert_eq!(MfxStatus::IncompatibleVideoParamWarning.class(), StatusClass::Warning);
+        assert_eq!(MfxStatus::DeviceBusy.class(), StatusClass::Retry);
+        assert_eq!(MfxStatus::MoreData.class(), StatusClass::MoreInput);
+        assert_eq!(MfxStatus::MoreSurface.class(), StatusClass::MoreOutput);
+        assert_eq!(MfxStatus::NotEnoughBuffer.class(), StatusClass::MoreOutput);
+        assert_eq!(MfxStatus::InvalidVideoParam.class(), StatusClass::Fatal);
+        assert_eq!(MfxStatus::Other(-99).class(), StatusClass::Fatal);
+
+        assert!(MfxStatus::PartialAcceleration.result().is_ok());
+        assert!(matches!(MfxStatus::DeviceLost.result(), Err(Error::Status(MfxStatus::DeviceLost))));
+    }
+}
@@ -0,0 +1,1843 @@
+use std::convert::TryFrom;
+use std::mem;
+use std::ptr;
+
+use crate::constants::*;
+use crate::enums::*;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct mfxVersion {
+    pub Minor: mfxU16,
+    pub Major: mfxU16,
+}
+
+impl mfxVersion {
+    pub const fn new(Major: mfxU16, Minor: mfxU16) -> Self {
+        mfxVersion { Major, Minor }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct mfxFrameIdStruct1 {
+    pub DependencyId: mfxU16,
+    pub QualityId: mfxU16,
+}
+
+impl Default for mfxFrameIdStruct1 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxFrameIdStruct1 {
+    pub fn new() -> Self {
+        mfxFrameIdStruct1 {
+            DependencyId: 0,
+            QualityId: 0,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct mfxFrameIdStruct2 {
+    pub ViewId: mfxU16,
+}
+
+impl Default for mfxFrameIdStruct2 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxFrameIdStruct2 {
+    pub fn new() -> Self {
+        mfxFrameIdStruct2 { ViewId: 0 }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union mfxFrameIdUnion {
+    pub s1: mfxFrameIdStruct1,
+    pub s2: mfxFrameIdStruct2,
+}
+
+impl Default for mfxFrameIdUnion {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxFrameIdUnion {
+    pub fn new() -> Self {
+        mfxFrameIdUnion {
+            s1: mfxFrameIdStruct1::new(),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct mfxFrameId {
+    pub TemporalId: mfxU16,
+    pub PriorityId: mfxU16,
+    pub u: mfxFrameIdUnion,
+}
+
+impl Default for mfxFrameId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxFrameId {
+    pub fn new() -> Self {
+        mfxFrameId {
+            TemporalId: 0,
+            PriorityId: 0,
+            u: mfxFrameIdUnion::new(),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct mfxFrameInfo {
+    pub reserved: [mfxU32; 4],
+    pub reserved4: mfxU16,
+    /// Number of bits used to represent luma samples.
+    /// Not all codecs and SDK implementations support this value. Use Query function to check if this feature is supported.
+    pub BitDepthLuma: mfxU16,
+    /// Number of bits used to represent chroma samples.
+    /// Not all codecs and SDK implementations support this value. Use Query function to check if this feature is supported.
+    pub BitDepthChroma: mfxU16,
+    /// When not zero indicates that values of luma and chroma samples are shifted. Use BitDepthLuma and BitDepthChroma to calculate shift size. Use zero value to indicate absence of shift.
+    /// Not all codecs and SDK implementations support this value. Use Query function to check if this feature is supported.
+    pub Shift: mfxU16,
+
+    pub FrameId: mfxFrameId,
+    /// FourCC code of the color format; see the ColorFourCC enumerator for details.
+    pub FourCC: mfxU32,
+
+    // TODO: union, frame paramaters, omit buffer parameters, both 96 bits
+    /// Width of the video frame in pixels; Width must be a multiple of 16.
+    pub Width: mfxU16,
+    /// Height of the video frame in pixels. Height must be a multiple of 16 for progressive frame sequence and a multiple of 32 otherwise.
+    pub Height: mfxU16,
+    /// Display the region of interest of the frame; specify the display width and height in mfxVideoParam.
+    pub CropX: mfxU16,
+    /// Display the region of interest of the frame; specify the display width and height in mfxVideoParam.
+    pub CropY: mfxU16,
+    /// Display the region of interest of the frame; specify the display width and height in mfxVideoParam.
+    pub CropW: mfxU16,
+    /// Display the region of interest of the frame; specify the display width and height in mfxVideoParam.
+    pub CropH: mfxU16,
+
+    /// Specify the frame rate by the formula: FrameRateExtN / FrameRateExtD.
+    /// For encoding, frame rate must be specified. For decoding, frame rate may be unspecified (FrameRateExtN and FrameRateExtD are all zeros.) In this case, the frame rate is default to 30 frames per second.
+    pub FrameRateExtN: mfxU32,
+    pub FrameRateExtD: mfxU32,
+    pub reserved3: mfxU16,
+
+    /// These parameters specify the sample aspect ratio. If sample aspect ratio is explicitly defined by the standards (see Table 6-3 in the MPEG-2 specification or Table E-1 in the H.264 specification), AspectRatioW and AspectRatioH should be the defined values. Otherwise, the sample aspect ratio can be derived as follows:
+    /// AspectRatioW=display_aspect_ratio_width*display_height;
+    /// For MPEG-2, the above display aspect ratio must be one of the defined values in Table 6-3. For H.264, there is no restriction on display aspect ratio values.
+    /// If both parameters are zero, the encoder uses default value of sample aspect ratio.
+    pub AspectRatioW: mfxU16,
+    /// AspectRatioH=display_aspect_ratio_height*display_width;
+    pub AspectRatioH: mfxU16,
+
+    /// Picture type as specified in the PicStruct enumerator
+    pub PicStruct: mfxU16,
+    /// Color sampling method; the value of ChromaFormat is the same as that of ChromaFormatIdc. ChromaFormat is not defined if FourCC is zero.
+    pub ChromaFormat: mfxU16,
+    pub reserved2: mfxU16,
+}
+
+impl Default for mfxFrameInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxFrameInfo {
+    pub fn new() -> Self {
+        mfxFrameInfo {
+            reserved: [0; 4],
+            reserved4: 0,
+            BitDepthLuma: 0,
+            BitDepthChroma: 0,
+            Shift: 0,
+            FrameId: mfxFrameId::new(),
+            FourCC: 0,
+            Width: 0,
+            Height: 0,
+            CropX: 0,
+            CropY: 0,
+            CropW: 0,
+            CropH: 0,
+            FrameRateExtN: 0,
+            FrameRateExtD: 0,
+            reserved3: 0,
+            AspectRatioW: 0,
+            AspectRatioH: 0,
+            PicStruct: 0,
+            ChromaFormat: 0,
+            reserved2: 0,
+        }
+    }
+
+    /// The FourCC field as a [`ColorFourCC`].
+    pub fn fourcc(&self) -> Result<ColorFourCC, UnknownValue> {
+        ColorFourCC::try_from(self.FourCC)
+    }
+
+    pub fn set_fourcc(&mut self, fourcc: ColorFourCC) {
+        self.FourCC = fourcc.raw();
+    }
+
+    /// The ChromaFormat field as a [`ChromaFormat`].
+    pub fn chroma_format(&self) -> Result<ChromaFormat, UnknownValue> {
+        ChromaFormat::try_from(self.ChromaFormat)
+    }
+
+    pub fn set_chroma_format(&mut self, chroma_format: ChromaFormat) {
+        self.ChromaFormat = chroma_format.raw();
+    }
+
+    /// The PicStruct field; unknown bits are kept.
+    pub fn pic_struct(&self) -> PicStruct {
+        PicStruct::from_bits_retain(self.PicStruct)
+    }
+
+    pub fn set_pic_struct(&mut self, pic_struct: PicStruct) {
+        self.PicStruct = pic_struct.bits();
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union mfxInfoMFXUnion1 {
+    /// InitialDelayInKB, TargetKbps, MaxKbps parameters are for the constant bitrate (CBR), variable bitrate control (VBR) and CQP HRD algorithms.
+    /// The SDK encoders follow the Hypothetical Reference Decoding (HRD) model. The HRD model assumes that data flows into a buffer of the fixed size BufferSizeInKB with a constant bitrate TargetKbps. (Estimate the targeted frame size by dividing the framerate by the bitrate.)
+    /// The decoder starts decoding after the buffer reaches the initial size InitialDelayInKB, which is equivalent to reaching an initial delay of InitialDelayInKB*8000/TargetKbpsms. Note: In this context, KB is 1000 bytes and Kbps is 1000 bps.
+    /// If InitialDelayInKB or BufferSizeInKB is equal to zero, the value is calculated using bitrate, frame rate, profile, level, and so on.
+    pub InitialDelayInKB: mfxU16,
+    /// Quantization Parameters (QP) for I, P and B frames for constant QP mode (CQP). Zero QP is not valid and means that default value is assigned by MediaSDK. Non-zero QPs might be clipped to supported QP range.
+    /// Note: Default QPI/QPP/QPB values are implementation dependent and subject to change without additional notice in this document.
+    pub QPI: mfxU16,
+    /// TargetKbps, Accuracy, Convergence parameters are for the average variable bitrate control (AVBR) algorithm. The algorithm focuses on overall encoding quality while meeting the specified bitrate, TargetKbps, within the accuracy range Accuracy, after a Convergence period. This method does not follow HRD and the instant bitrate is not capped or padded.
+    /// The Accuracy value is specified in the unit of tenth of percent.
+    pub Accuracy: mfxU16,
+}
+
+impl Default for mfxInfoMFXUnion1 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxInfoMFXUnion1 {
+    pub fn new() -> Self {
+        mfxInfoMFXUnion1 {
+            InitialDelayInKB: 0,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union mfxInfoMFXUnion2 {
+    /// The TargetKbps value is specified in the unit of 1000 bits per second.
+    pub TargetKbps: mfxU16,
+    /// Note: Default QPI/QPP/QPB values are implementation dependent and subject to change without additional notice in this document.
+    pub QPP: mfxU16,
+    /// This parameter is for Intelligent Constant Quality (ICQ) bitrate control algorithm. It is value in the 1…51 range, where 1 corresponds the best quality.
+    pub ICQQuality: mfxU16,
+}
+
+impl Default for mfxInfoMFXUnion2 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxInfoMFXUnion2 {
+    pub fn new() -> Self {
+        mfxInfoMFXUnion2 { TargetKbps: 0 }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union mfxInfoMFXUnion3 {
+    pub MaxKbps: mfxU16,
+    /// Note: Default QPI/QPP/QPB values are implementation dependent and subject to change without additional notice in this document.
+    pub QPB: mfxU16,
+    /// The Convergence value is specified in the unit of 100 frames.
+    pub Convergence: mfxU16,
+}
+
+impl Default for mfxInfoMFXUnion3 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxInfoMFXUnion3 {
+    pub fn new() -> Self {
+        mfxInfoMFXUnion3 { MaxKbps: 0 }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+/// Encoding options of mfxInfoMFX.
+pub struct mfxInfoMFXEncode {
+    /// Target usage model that guides the encoding process; see the TargetUsage enumerator for details.
+    pub TargetUsage: mfxU16,
+    /// Number of pictures within the current GOP (Group of Pictures); if GopPicSize = 0, then the GOP size is unspecified. If GopPicSize = 1, only I-frames are used. See Example 17 for pseudo-code that demonstrates how SDK uses this parameter.
+    pub GopPicSize: mfxU16,
+    /// Distance between I- or P (or GPB) - key frames; if it is zero, the GOP structure is unspecified. Note: If GopRefDist = 1, there are no regular B-frames used (only P or GPB); if mfxExtCodingOption3::GPB is ON, GPB frames (B without backward references) are used instead of P. See Example 17 for pseudo-code that demonstrates how SDK uses this parameter.
+    pub GopRefDist: mfxU16,
+    /// ORs of the GopOptFlag enumerator indicate the additional flags for the GOP specification; see Example 17 for an example of pseudo-code that demonstrates how to use this parameter.
+    pub GopOptFlag: mfxU16,
+    /// For H.264, IdrInterval specifies IDR-frame interval in terms of I-frames; if IdrInterval = 0, then every I-frame is an IDR-frame. If IdrInterval = 1, then every other I-frame is an IDR-frame, etc.
+    /// For HEVC, if IdrInterval = 0, then only first I-frame is an IDR-frame. If IdrInterval = 1, then every I-frame is an IDR-frame. If IdrInterval = 2, then every other I-frame is an IDR-frame, etc.
+    /// For MPEG2, IdrInterval defines sequence header interval in terms of I-frames. If IdrInterval = N, SDK inserts the sequence header before every Nth I-frame. If IdrInterval = 0 (default), SDK inserts the sequence header once at the beginning of the stream.
+    /// If GopPicSize or GopRefDist is zero, IdrInterval is undefined.
+    pub IdrInterval: mfxU16,
+    /// Rate control method; see the RateControlMethod enumerator for details.
+    pub RateControlMethod: mfxU16,
+    pub u1: mfxInfoMFXUnion1,
+    /// BufferSizeInKB represents the maximum possible size of any compressed frames.
+    pub BufferSizeInKB: mfxU16,
+    pub u2: mfxInfoMFXUnion2,
+    pub u3: mfxInfoMFXUnion3,
+    /// Number of slices in each video frame; each slice contains one or more macro-block rows. If NumSlice equals zero, the encoder may choose any slice partitioning allowed by the codec standard. See also mfxExtCodingOption2::NumMbPerSlice.
+    pub NumSlice: mfxU16,
+    /// Max number of all available reference frames (for AVC/HEVC NumRefFrame defines DPB size); if NumRefFrame = 0, this parameter is not specified.
+    /// See also mfxExtCodingOption3::NumRefActiveP, NumRefActiveBL0 and NumRefActiveBL1 which set a number of active references.
+    pub NumRefFrame: mfxU16,
+    /// If not zero, EncodedOrder specifies that ENCODE takes the input surfaces in the encoded order and uses explicit frame type control. Application still must provide GopRefDist and mfxExtCodingOption2::BRefType so SDK can pack headers and build reference lists correctly.
+    pub EncodedOrder: mfxU16,
+}
+
+impl Default for mfxInfoMFXEncode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxInfoMFXEncode {
+    pub fn new() -> Self {
+        mfxInfoMFXEncode {
+            TargetUsage: 0,
+            GopPicSize: 0,
+            GopRefDist: 0,
+            GopOptFlag: 0,
+            IdrInterval: 0,
+            RateControlMethod: 0,
+            u1: mfxInfoMFXUnion1::new(),
+            BufferSizeInKB: 0,
+            u2: mfxInfoMFXUnion2::new(),
+            u3: mfxInfoMFXUnion3::new(),
+            NumSlice: 0,
+            NumRefFrame: 0,
+            EncodedOrder: 0,
+        }
+    }
+
+    /// The TargetUsage field as a [`TargetUsage`].
+    pub fn target_usage(&self) -> Result<TargetUsage, UnknownValue> {
+        TargetUsage::try_from(self.TargetUsage)
+    }
+
+    pub fn set_target_usage(&mut self, target_usage: TargetUsage) {
+        self.TargetUsage = target_usage.raw();
+    }
+
+    /// The RateControlMethod field as a [`RateControlMethod`].
+    pub fn rate_control_method(&self) -> Result<RateControlMethod, UnknownValue> {
+        RateControlMethod::try_from(self.RateControlMethod)
+    }
+
+    pub fn set_rate_control_method(&mut self, rate_control_method: RateControlMethod) {
+        self.RateControlMethod = rate_control_method.raw();
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+/// Decoding options of mfxInfoMFX.
+pub struct mfxInfoMFXDecode {
+    /// For AVC and HEVC, used to instruct the decoder to return output frames in the decoded order. Must be zero for all other decoders. When enabled, correctness of mfxFrameData::TimeStamp and FrameOrder for output surface is not guaranteed, the application should ignore them.
+    pub DecodedOrder: mfxU16,
+    /// Instructs DECODE to output extended picture structure values for additional display attributes. See the PicStruct description for details.
+    pub ExtendedPicStruct: mfxU16,
+    /// Time stamp calculation method; see the TimeStampCalc description for details.
+    pub TimeStampCalc: mfxU16,
+    /// Nonzero value indicates that slice groups are present in the bitstream. Only AVC decoder uses this field.
+    pub SliceGroupsPresent: mfxU16,
+    /// Nonzero value specifies the maximum required size of the decoded picture buffer in frames for AVC and HEVC decoders.
+    pub MaxDecFrameBuffering: mfxU16,
+    /// For decoders supporting dynamic resolution change (VP9), set this option to ON to allow MFXVideoDECODE_DecodeFrameAsync return MFX_ERR_REALLOC_SURFACE. See the CodingOptionValue enumerator for values of this option. Use Query function to check if this feature is supported.
+    pub EnableReallocRequest: mfxU16,
+    reserved2: [mfxU16; 7],
+}
+
+impl Default for mfxInfoMFXDecode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxInfoMFXDecode {
+    pub fn new() -> Self {
+        mfxInfoMFXDecode {
+            DecodedOrder: 0,
+            ExtendedPicStruct: 0,
+            TimeStampCalc: 0,
+            SliceGroupsPresent: 0,
+            MaxDecFrameBuffering: 0,
+            EnableReallocRequest: 0,
+            reserved2: [0; 7],
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+/// JPEG decoding options of mfxInfoMFX.
+pub struct mfxInfoMFXJpegDecode {
+    /// Specify the chroma sampling format that has been used to encode JPEG picture. See the ChromaFormat enumerator.
+    pub JPEGChromaFormat: mfxU16,
+    /// Rotation option of the output JPEG picture; see the Rotation enumerator for details.
+    pub Rotation: mfxU16,
+    /// Specify the color format that has been used to encode JPEG picture. See the JPEGColorFormat enumerator for details.
+    pub JPEGColorFormat: mfxU32,
+    /// Specify JPEG scan type for decoder. See the JPEGScanType enumerator for details.
+    pub InterleavedDec: mfxU16,
+    /// Horizontal sampling factor.
+    pub SamplingFactorH: [mfxU8; 4],
+    /// Vertical sampling factor.
+    pub SamplingFactorV: [mfxU8; 4],
+    reserved3: [mfxU16; 5],
+}
+
+impl Default for mfxInfoMFXJpegDecode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxInfoMFXJpegDecode {
+    pub fn new() -> Self {
+        mfxInfoMFXJpegDecode {
+            JPEGChromaFormat: 0,
+            Rotation: 0,
+            JPEGColorFormat: 0,
+            InterleavedDec: 0,
+            SamplingFactorH: [0; 4],
+            SamplingFactorV: [0; 4],
+            reserved3: [0; 5],
+        }
+    }
+
+    /// The JPEGChromaFormat field as a [`ChromaFormat`].
+    pub fn chroma_format(&self) -> Result<ChromaFormat, UnknownValue> {
+        ChromaFormat::try_from(self.JPEGChromaFormat)
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+/// JPEG encoding options of mfxInfoMFX.
+pub struct mfxInfoMFXJpegEncode {
+    /// Non-interleaved or interleaved scans. If it is equal to MFX_SCANTYPE_INTERLEAVED then the image is encoded as interleaved, all components are encoded in one scan. See the JPEG Scan Type enumerator for details.
+    pub Interleaved: mfxU16,
+    /// Specifies the image quality if the application does not specified quantization table. This is the value from 1 to 100 inclusive. "100" is the best quality.
+    pub Quality: mfxU16,
+    /// Specifies the number of MCU in the restart interval. "0" means no restart interval.
+    pub RestartInterval: mfxU16,
+    reserved5: [mfxU16; 10],
+}
+
+impl Default for mfxInfoMFXJpegEncode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxInfoMFXJpegEncode {
+    pub fn new() -> Self {
+        mfxInfoMFXJpegEncode {
+            Interleaved: 0,
+            Quality: 0,
+            RestartInterval: 0,
+            reserved5: [0; 10],
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+/// Codec specific options of mfxInfoMFX; which member applies depends on the component the structure is passed to. Use the [`mfxInfoMFX::as_encode`] family of views instead of reading the members directly.
+pub union mfxInfoMFXUnion {
+    pub encode: mfxInfoMFXEncode,
+    pub decode: mfxInfoMFXDecode,
+    pub jpeg_decode: mfxInfoMFXJpegDecode,
+    pub jpeg_encode: mfxInfoMFXJpegEncode,
+}
+
+impl Default for mfxInfoMFXUnion {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxInfoMFXUnion {
+    pub fn new() -> Self {
+        // The largest member, so the whole union is zeroed.
+        mfxInfoMFXUnion {
+            jpeg_decode: mfxInfoMFXJpegDecode::new(),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+/// <https://github.com/Intel-Media-SDK/MediaSDK/blob/master/doc/mediasdk-man.md#mfxinfomfx>
+pub struct mfxInfoMFX {
+    reserved: [mfxU32; 7],
+    /// For encoders set this flag to ON to reduce power consumption and GPU usage. See the CodingOptionValue enumerator for values of this option. Use Query function to check if this feature is supported.
+    pub LowPower: mfxU16,
+    /// Specifies a multiplier for bitrate control parameters. Affects next four variables InitialDelayInKB, BufferSizeInKB, TargetKbps, MaxKbps. If this value is not equal to zero encoder calculates BRC parameters as value * BRCParamMultiplier.
+    pub BRCParamMultiplier: mfxU16,
+    /// mfxFrameInfo structure that specifies frame parameters.
+    pub FrameInfo: mfxFrameInfo,
+    /// Specifies the codec format identifier in the FOURCC code; see the CodecFormatFourCC enumerator for details. This is a mandated input parameter for QueryIOSurf and Init functions.
+    pub CodecId: mfxU32,
+    /// Specifies the codec profile; see the CodecProfile enumerator for details. Specify the codec profile explicitly or the SDK functions will determine the correct profile from other sources, such as resolution and bitrate.
+    pub CodecProfile: mfxU16,
+    /// Codec level; see the CodecLevel enumerator for details. Specify the codec level explicitly or the SDK functions will determine the correct level from other sources, such as resolution and bitrate.
+    pub CodecLevel: mfxU16,
+    #[deprecated]
+    /// Deprecated; Used to represent the number of threads the underlying implementation can use on the host processor. **Always set this parameter to zero.**
+    pub NumThread: mfxU16,
+    /// Encoding, decoding, JPEG decoding or JPEG encoding options, read and written through the views, e.g. [`mfxInfoMFX::as_encode`]. Not public: assigning a smaller member would leave bytes of the larger ones uninitialized.
+    pub(crate) u: mfxInfoMFXUnion,
+}
+
+impl Default for mfxInfoMFX {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxInfoMFX {
+    #[allow(deprecated)]
+    pub fn new() -> Self {
+        mfxInfoMFX {
+            reserved: [0; 7],
+            LowPower: 0,
+            BRCParamMultiplier: 0,
+            FrameInfo: mfxFrameInfo::new(),
+            CodecId: 0,
+            CodecProfile: 0,
+            CodecLevel: 0,
+            NumThread: 0,
+            u: mfxInfoMFXUnion::new(),
+        }
+    }
+
+    // The union members only contain integers and `new` zeroes the whole union, whose members are only written through
+    // these views, so every view reads initialized bytes.
+
+    /// The options as seen by an encoder other than JPEG.
+    pub fn as_encode(&self) -> &mfxInfoMFXEncode {
+        unsafe { &self.u.encode }
+    }
+
+    pub fn as_encode_mut(&mut self) -> &mut mfxInfoMFXEncode {
+        unsafe { &mut self.u.encode }
+    }
+
+    /// The options as seen by a decoder other than JPEG.
+    pub fn as_decode(&self) -> &mfxInfoMFXDecode {
+        unsafe { &self.u.decode }
+    }
+
+    pub fn as_decode_mut(&mut self) -> &mut mfxInfoMFXDecode {
+        unsafe { &mut self.u.decode }
+    }
+
+    /// The options as seen by the JPEG decoder.
+    pub fn as_jpeg_decode(&self) -> &mfxInfoMFXJpegDecode {
+        unsafe { &self.u.jpeg_decode }
+    }
+
+    pub fn as_jpeg_decode_mut(&mut self) -> &mut mfxInfoMFXJpegDecode {
+        unsafe { &mut self.u.jpeg_decode }
+    }
+
+    /// The options as seen by the JPEG encoder.
+    pub fn as_jpeg_encode(&self) -> &mfxInfoMFXJpegEncode {
+        unsafe { &self.u.jpeg_encode }
+    }
+
+    pub fn as_jpeg_encode_mut(&mut self) -> &mut mfxInfoMFXJpegEncode {
+        unsafe { &mut self.u.jpeg_encode }
+    }
+
+    /// The CodecId field as a [`CodecId`].
+    pub fn codec_id(&self) -> Result<CodecId, UnknownValue> {
+        CodecId::try_from(self.CodecId)
+    }
+
+    pub fn set_codec_id(&mut self, codec_id: CodecId) {
+        self.CodecId = codec_id.raw();
+    }
+}
+
+/// The mfxExtBuffer structure is the common header definition for external buffers and video processing hints.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct mfxExtBuffer {
+    /// Identifier of the buffer content. See the ExtendedBufferID enumerator for a complete list of extended buffers.
+    pub BufferId: mfxU32,
+    /// Size of the buffer
+    pub BufferSz: mfxU32,
+}
+
+impl Default for mfxExtBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxExtBuffer {
+    pub fn new() -> Self {
+        mfxExtBuffer {
+            BufferId: 0,
+            BufferSz: 0,
+        }
+    }
+
+    /// Header of the extended buffer structure `T`, with BufferSz set to its size.
+    pub fn header<T>(buffer_id: mfxU32) -> Self {
+        mfxExtBuffer {
+            BufferId: buffer_id,
+            BufferSz: mem::size_of::<T>() as mfxU32,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+/// The mfxInfoVPP structure specifies configurations for video processing. A zero value in any of the fields indicates that the corresponding field is not explicitly specified.
+pub struct mfxInfoVPP {
+    pub reserved: [mfxU32; 8],
+    /// Input format for video processing
+    pub In: mfxFrameInfo,
+    /// Output format for video processing
+    pub Out: mfxFrameInfo,
+}
+
+impl Default for mfxInfoVPP {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxInfoVPP {
+    pub fn new() -> Self {
+        mfxInfoVPP {
+            reserved: [0; 8],
+            In: mfxFrameInfo::new(),
+            Out: mfxFrameInfo::new(),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union mfxVideoParamUnion {
+    /// Configurations related to encoding, decoding and transcoding; see the definition of the mfxInfoMFX structure for details.
+    pub mfx: mfxInfoMFX,
+    /// Configurations related to video processing; see the definition of the mfxInfoVPP structure for details.
+    pub vpp: mfxInfoVPP,
+}
+
+impl Default for mfxVideoParamUnion {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxVideoParamUnion {
+    pub fn new() -> Self {
+        // The largest member, so the whole union is zeroed.
+        mfxVideoParamUnion {
+            vpp: mfxInfoVPP::new(),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+/// The mfxVideoParam structure contains configuration parameters for encoding, decoding, transcoding and video processing.
+///
+/// <https://github.com/Intel-Media-SDK/MediaSDK/blob/master/doc/mediasdk-man.md#mfxvideoparam>
+pub struct mfxVideoParam {
+    /// Unique component ID that will be passed by SDK to mfxFrameAllocRequest. Useful in pipelines where several components of the same type share the same allocator.
+    pub AllocId: mfxU32,
+    reserved: [mfxU32; 2],
+    reserved3: mfxU16,
+    /// Specifies how many asynchronous operations an application performs before the application explicitly synchronizes the result. If zero, the value is not specified.
+    pub AsyncDepth: mfxU16,
+    /// Encoder/decoder or VPP parameters, read and written through [`mfxVideoParam::mfx`] and [`mfxVideoParam::vpp`]. Not public: assigning the smaller `mfx` member would leave the end of `vpp` uninitialized.
+    pub(crate) u: mfxVideoParamUnion,
+    /// Specifies the content protection mechanism; see the Protected enumerator for a list of supported protection schemes.
+    pub Protected: mfxU16,
+    /// Input and output memory access types for SDK functions; see the enumerator IOPattern for details. The Query functions return the natively supported IOPattern if the Query input argument is NULL. This parameter is a mandated input for QueryIOSurf and Init functions. For DECODE, the output pattern must be specified; for ENCODE, the input pattern must be specified; and for VPP, both input and output pattern must be specified.
+    pub IOPattern: mfxU16,
+    /// Points to an array of pointers to the extra configuration structures; see the ExtendedBufferID enumerator for a list of extended configurations.
+    ///
+    /// The list of extended buffers should not contain duplicated entries, i.e. entries of the same type. If mfxVideoParam structure is used to query the SDK capability, then list of extended buffers attached to input and output mfxVideoParam structure should be equal, i.e. should contain the same number of extended buffers of the same type.
+    ///
+    /// Use [`crate::ext_buffer::VideoParam`] to attach buffers safely.
+    pub ExtParam: *mut *mut mfxExtBuffer,
+    /// The number of extra configuration structures attached to this structure.
+    pub NumExtParam: mfxU16,
+    reserved2: mfxU16,
+}
+
+impl Default for mfxVideoParam {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxVideoParam {
+    pub fn new() -> Self {
+        mfxVideoParam {
+            AllocId: 0,
+            reserved: [0; 2],
+            reserved3: 0,
+            AsyncDepth: 0,
+            u: mfxVideoParamUnion::new(),
+            Protected: 0,
+            IOPattern: 0,
+            ExtParam: ptr::null_mut(),
+            NumExtParam: 0,
+            reserved2: 0,
+        }
+    }
+
+    /// The encoder/decoder parameters of the union `u`.
+    pub fn mfx(&self) -> &mfxInfoMFX {
+        // Both union members are plain integers and `new` zeroes the whole union, any bit pattern is valid.
+        unsafe { &self.u.mfx }
+    }
+
+    pub fn mfx_mut(&mut self) -> &mut mfxInfoMFX {
+        unsafe { &mut self.u.mfx }
+    }
+
+    /// The VPP parameters of the union `u`.
+    pub fn vpp(&self) -> &mfxInfoVPP {
+        unsafe { &self.u.vpp }
+    }
+
+    pub fn vpp_mut(&mut self) -> &mut mfxInfoVPP {
+        unsafe { &mut self.u.vpp }
+    }
+
+    /// The IOPattern field; unknown bits are kept.
+    pub fn io_pattern(&self) -> IOPattern {
+        IOPattern::from_bits_retain(self.IOPattern)
+    }
+
+    pub fn set_io_pattern(&mut self, io_pattern: IOPattern) {
+        self.IOPattern = io_pattern.bits();
+    }
+}
+
+#[repr(C)]
+/// The mfxFrameAllocRequest structure describes multiple frame allocations when initializing encoders, decoders and video preprocessors. A range specifies the number of video frames. Applications are free to allocate additional frames. In any case, the minimum number of frames must be at least NumFrameMin or the called function will return an error.
+pub struct mfxFrameAllocRequest {
+    // TODO: AllocId should be stored in a union
+    /// Unique (within the session) ID of component requested the allocation.
+    pub AllocId: mfxU32,
+    reserved3: [mfxU32; 3],
+    /// Describes the properties of allocated frames
+    pub Info: mfxFrameInfo,
+    /// Allocated memory type; see the ExtMemFrameType enumerator for details.
+    pub Type: mfxU16,
+    /// Minimum number of allocated frames
+    pub NumFrameMin: mfxU16,
+    /// Suggested number of allocated frames
+    pub NumFrameSuggested: mfxU16,
+    reserved2: mfxU16,
+}
+
+impl Default for mfxFrameAllocRequest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxFrameAllocRequest {
+    pub fn new() -> Self {
+        mfxFrameAllocRequest {
+            AllocId: 0,
+            reserved3: [0; 3],
+            Info: mfxFrameInfo::new(),
+            Type: 0,
+            NumFrameMin: 0,
+            NumFrameSuggested: 0,
+            reserved2: 0,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+/// The mfxFrameAllocResponse structure describes the response to multiple frame allocations. The calling function returns the number of video frames actually allocated and pointers to their memory IDs.
+pub struct mfxFrameAllocResponse {
+    /// Unique (within the session) ID of component requested the allocation.
+    pub AllocId: mfxU32,
+    reserved: [mfxU32; 3],
+    /// Pointer to the array of the returned memory IDs; the application allocates or frees this array.
+    pub mids: *mut mfxMemId,
+    /// Number of frames actually allocated
+    pub NumFrameActual: mfxU16,
+    reserved2: mfxU16,
+}
+
+impl Default for mfxFrameAllocResponse {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxFrameAllocResponse {
+    pub fn new() -> Self {
+        mfxFrameAllocResponse {
+            AllocId: 0,
+            reserved: [0; 3],
+            mids: ptr::null_mut(),
+            NumFrameActual: 0,
+            reserved2: 0,
+        }
+    }
+}
+
+#[repr(C)]
+/// The mfxFrameAllocator structure describes the callback functions Alloc, Lock, Unlock, GetHDL and Free that the SDK implementation might use for allocating internal frames. Applications that operate on OS-specific video surfaces must implement these callback functions.
+///
+/// Using the default allocator implies that frame data passes in or out of SDK functions through pointers, as opposed to using memory IDs. With the `hardware` feature, `allocator::FrameAllocator` implements the callbacks on top of a Rust trait.
+///
+/// <https://github.com/Intel-Media-SDK/MediaSDK/blob/master/doc/mediasdk-man.md#mfxframeallocator>
+pub struct mfxFrameAllocator {
+    reserved: [mfxU32; 4],
+    /// Pointer to the allocator object
+    pub pthis: mfxHDL,
+    /// This function allocates surface frames. For decoders, MFXVideoDECODE_Init calls Alloc only once. That call includes all frame allocation requests. For encoders, MFXVideoENCODE_Init calls Alloc twice: once for the input surfaces and again for the internal reconstructed surfaces.
+    ///
+    /// If two SDK components must share DirectX* surfaces, this function should pass the pre-allocated surface chain to SDK instead of allocating new DirectX surfaces.
+    pub Alloc: Option<
+        unsafe extern "C" fn(
+            pthis: mfxHDL,
+            request: *mut mfxFrameAllocRequest,
+            response: *mut mfxFrameAllocResponse,
+        ) -> mfxStatus,
+    >,
+    /// This function locks a frame and returns its pointer.
+    pub Lock: Option<unsafe extern "C" fn(pthis: mfxHDL, mid: mfxMemId, ptr: *mut mfxFrameData) -> mfxStatus>,
+    /// This function unlocks a frame and invalidates the specified frame structure.
+    pub Unlock: Option<unsafe extern "C" fn(pthis: mfxHDL, mid: mfxMemId, ptr: *mut mfxFrameData) -> mfxStatus>,
+    /// This function returns the OS-specific handle associated with a video frame. If the handle is a COM interface, the reference counter must increase. The SDK will release the interface afterward.
+    pub GetHDL: Option<unsafe extern "C" fn(pthis: mfxHDL, mid: mfxMemId, handle: *mut mfxHDL) -> mfxStatus>,
+    /// This function de-allocates all allocated frames.
+    pub Free: Option<unsafe extern "C" fn(pthis: mfxHDL, response: *mut mfxFrameAllocResponse) -> mfxStatus>,
+}
+
+impl Default for mfxFrameAllocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxFrameAllocator {
+    pub fn new() -> Self {
+        mfxFrameAllocator {
+            reserved: [0; 4],
+            pthis: ptr::null(),
+            Alloc: None,
+            Lock: None,
+            Unlock: None,
+            GetHDL: None,
+            Free: None,
+        }
+    }
+}
+
+#[repr(C)]
+/// The mfxFrameData structure describes frame buffer pointers.
+///
+/// <https://github.com/Intel-Media-SDK/MediaSDK/blob/master/doc/mediasdk-man.md#mfxframedata>
+pub struct mfxFrameData {
+    // TODO: union ExtParam: const* const* mfxExtBuffer
+    pub reserved2: mfxU64,
+    /// The number of extra configuration structures attached to this structure.
+    pub NumExtParam: mfxU16,
+    pub reserved: [mfxU16; 9],
+    /// Allocated memory type; see the ExtMemFrameType enumerator for details. Used for better integration of 3rd party plugins into SDK pipeline.
+    pub MemType: mfxU16,
+    /// PitchHigh/PitchLow params. Distance in bytes between the start of two consecutive rows in a frame.
+    pub PitchHigh: mfxU16,
+    /// Time stamp of the video frame in units of 90KHz (divide TimeStamp by 90,000 (90 KHz) to obtain the time in seconds). A value of MFX_TIMESTAMP_UNKNOWN indicates that there is no time stamp.
+    pub TimeStamp: mfxU64,
+    /// Current frame counter for the top field of the current frame; an invalid value of MFX_FRAMEORDER_UNKNOWN indicates that SDK functions that generate the frame output do not use this frame.
+    pub FrameOrder: mfxU32,
+    /// Counter flag for the application; if Locked is greater than zero then the application locks the frame or field pair. Do not move, alter or delete the frame.
+    pub Locked: mfxU16,
+    // TODO: union Pitch, pitch is depricated
+    /// PitchHigh/PitchLow params. Distance in bytes between the start of two consecutive rows in a frame.
+    pub PitchLow: mfxU16,
+
+    /// Data pointers to corresponding color channels. The frame buffer pointers must be 16-byte aligned. The application has to specify pointers to all color channels even for packed formats. For example, for YUY2 format the application has to specify Y, U and V pointers. For RGB32 – R, G, B and A pointers.
+    pub Y: *mut mfxU8,
+    // union
+    pub UV: *mut mfxU8,
+    // union
+    pub V: *mut mfxU8,
+    pub A: *mut mfxU8,
+    /// Memory ID of the data buffers; if any of the preceding data pointers is non-zero then the SDK ignores MemId.
+    pub MemId: mfxMemId,
+    /// Some part of the frame or field pair is corrupted. See the Corruption enumerator for details.
+    pub Corrupted: mfxU16,
+    /// Additional flags to indicate frame data properties. See the FrameDataFlag enumerator for details.
+    pub DataFlag: mfxU16,
+}
+
+impl Default for mfxFrameData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxFrameData {
+    pub fn new() -> Self {
+        mfxFrameData {
+            reserved2: 0,
+            NumExtParam: 0,
+            reserved: [0; 9],
+            MemType: 0,
+            PitchHigh: 0,
+            TimeStamp: 0,
+            FrameOrder: 0,
+            Locked: 0,
+            PitchLow: 0,
+
+            Y: ptr::null_mut(),
+            UV: ptr::null_mut(),
+            V: ptr::null_mut(),
+            A: ptr::null_mut(),
+            MemId: ptr::null(),
+            Corrupted: 0,
+            DataFlag: 0,
+        }
+    }
+
+    /// Distance in bytes between the start of two consecutive rows, combined from PitchHigh and PitchLow.
+    pub fn pitch(&self) -> mfxU32 {
+        (self.PitchHigh as mfxU32) << 16 | self.PitchLow as mfxU32
+    }
+
+    pub fn set_pitch(&mut self, pitch: mfxU32) {
+        self.PitchHigh = (pitch >> 16) as mfxU16;
+        self.PitchLow = pitch as mfxU16;
+    }
+}
+
+#[repr(C)]
+/// The mfxFrameSurface1 structure defines the uncompressed frames surface information and data buffers. The frame surface is in the frame or complementary field pairs of pixels up to four color-channels, in two parts: mfxFrameInfo and mfxFrameData.
+///
+/// <https://github.com/Intel-Media-SDK/MediaSDK/blob/master/doc/mediasdk-man.md#mfxframesurface1>
+pub struct mfxFrameSurface1 {
+    reserved: [mfxU32; 4],
+    /// mfxFrameInfo structure specifies surface properties
+    pub Info: mfxFrameInfo,
+    /// mfxFrameData structure describes the actual frame buffer.
+    pub Data: mfxFrameData,
+}
+
+impl Default for mfxFrameSurface1 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxFrameSurface1 {
+    pub fn new() -> Self {
+        mfxFrameSurface1 {
+            reserved: [0; 4],
+            Info: mfxFrameInfo::new(),
+            Data: mfxFrameData::new(),
+        }
+    }
+}
+
+#[repr(C)]
+/// The mfxBitstream structure defines the buffer that holds compressed video data.
+///
+/// <https://github.com/Intel-Media-SDK/MediaSDK/blob/master/doc/mediasdk-man.md#mfxbitstream>
+pub struct mfxBitstream {
+    // mfxEncryptedData, not supported
+    EncryptedData: mfxHDL,
+    /// Array of extended buffers for additional bitstream configuration or output, e.g. mfxExtAVCEncodedFrameInfo. See the ExtendedBufferID enumerator for a complete list of extended buffers.
+    pub ExtParam: *const *mut mfxExtBuffer,
+    /// The number of extended buffers attached to this structure.
+    pub NumExtParam: mfxU16,
+    reserved: [mfxU16; 3],
+    /// Decode time stamp of the compressed bitstream in units of 90KHz. A value of MFX_TIMESTAMP_UNKNOWN indicates that there is no time stamp.
+    /// This value is calculated by the SDK encoder from presentation time stamp provided by the application in mfxFrameSurface1 structure and from frame rate provided by the application during the SDK encoder initialization.
+    pub DecodeTimeStamp: mfxI64,
+    /// Time stamp of the compressed bitstream in units of 90KHz. A value of MFX_TIMESTAMP_UNKNOWN indicates that there is no time stamp.
+    pub TimeStamp: mfxU64,
+    /// Bitstream buffer pointer—32-bytes aligned
+    pub Data: *const mfxU8,
+    /// Next reading or writing position in the bitstream buffer
+    pub DataOffset: mfxU32,
+    /// Size of the actual bitstream data in bytes
+    pub DataLength: mfxU32,
+    /// Allocated bitstream buffer size in bytes
+    pub MaxLength: mfxU32,
+    /// Type of the picture in the bitstream; this is an output parameter.
+    pub PicStruct: mfxU16,
+    /// Frame type of the picture in the bitstream; this is an output parameter.
+    pub FrameType: mfxU16,
+    /// Indicates additional bitstream properties; see the BitstreamDataFlag enumerator for details.
+    pub DataFlag: mfxU16,
+    reserved2: mfxU16,
+}
+
+impl Default for mfxBitstream {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxBitstream {
+    pub fn new() -> Self {
+        mfxBitstream {
+            EncryptedData: ptr::null(),
+            ExtParam: ptr::null(),
+            NumExtParam: 0,
+            reserved: [0; 3],
+            DecodeTimeStamp: 0,
+            TimeStamp: 0,
+            Data: ptr::null(),
+            DataOffset: 0,
+            DataLength: 0,
+            MaxLength: 0,
+            PicStruct: 0,
+            FrameType: 0,
+            DataFlag: 0,
+            reserved2: 0,
+        }
+    }
+
+    /// The FrameType field of the encoded frame; unknown bits are kept.
+    pub fn frame_type(&self) -> FrameType {
+        FrameType::from_bits_retain(self.FrameType)
+    }
+
+    /// The PicStruct field of the encoded frame; unknown bits are kept.
+    pub fn pic_struct(&self) -> PicStruct {
+        PicStruct::from_bits_retain(self.PicStruct)
+    }
+}
+
+#[repr(C)]
+/// The mfxEncodeCtrl structure contains parameters for per-frame based encoding control.
+///
+/// <https://github.com/Intel-Media-SDK/MediaSDK/blob/master/doc/mediasdk-man.md#mfxencodectrl>
+pub struct mfxEncodeCtrl {
+    pub Header: mfxExtBuffer,
+    pub reserved: [mfxU32; 5],
+    /// Type of NAL unit that contains encoding frame. All supported values are defined by MfxNalUnitType enumerator. Other values defined in ITU-T H.265 specification are not supported.
+    /// The SDK encoder uses this field only if application sets mfxExtCodingOption3::EnableNalUnitType option to ON during encoder initialization.
+    /// Only encoded order is supported. If application specifies this value in display order or uses value inappropriate for current frame or invalid value, then SDK encoder silently ignores it.
+    pub MfxNalUnitType: mfxU16,
+    /// Indicates that current frame should be skipped or number of missed frames before the current frame. See the mfxExtCodingOption2::SkipFrame for details.
+    pub SkipFrame: mfxU16,
+
+    /// If nonzero, this value overwrites the global QP value for the current frame in the constant QP mode.
+    pub QP: mfxU16,
+    /// Encoding frame type; see the FrameType enumerator for details. If the encoder works in the encoded order, the application must specify the frame type. If the encoder works in the display order, only key frames are enforceable.
+    pub FrameType: mfxU16,
+    /// Number of extra control buffers.
+    pub NumExtParam: mfxU16,
+    /// Number of payload records to insert into the bitstream.
+    pub NumPayload: mfxU16,
+    pub reserved2: mfxU16,
+
+    /// Pointer to an array of pointers to external buffers that provide additional information or control to the encoder for this frame or field pair; a typical usage is to pass the VPP auxiliary data generated by the video processing pipeline to the encoder. See the ExtendedBufferID for the list of extended buffers.
+    pub ExtParam: *const *const mfxExtBuffer,
+    /// Pointer to an array of pointers to user data (MPEG-2) or SEI messages (H.264) for insertion into the bitstream; for field pictures, odd payloads are associated with the first field and even payloads are associated with the second field. See the mfxPayload structure for payload definitions.
+    pub Payload: *const *const mfxPayload,
+}
+
+impl Default for mfxEncodeCtrl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxEncodeCtrl {
+    pub fn new() -> Self {
+        mfxEncodeCtrl {
+            Header: mfxExtBuffer::new(),
+            reserved: [0; 5],
+            MfxNalUnitType: 0,
+            SkipFrame: 0,
+            QP: 0,
+            FrameType: MFX_FRAMETYPE_UNKNOWN,
+            NumExtParam: 0,
+            NumPayload: 0,
+            reserved2: 0,
+            ExtParam: ptr::null(),
+            Payload: ptr::null(),
+        }
+    }
+
+    /// The FrameType field; unknown bits are kept.
+    pub fn frame_type(&self) -> FrameType {
+        FrameType::from_bits_retain(self.FrameType)
+    }
+
+    pub fn set_frame_type(&mut self, frame_type: FrameType) {
+        self.FrameType = frame_type.bits();
+    }
+}
+
+#[repr(C)]
+/// The mfxPayload structure describes user data payload in MPEG-2 or SEI message payload in H.264. For encoding, these payloads can be inserted into the bitstream. The payload buffer must contain a valid formatted payload. For H.264, this is the sei_message() as specified in the section 7.3.2.3.1 “Supplemental enhancement information message syntax” of the ISO*/IEC* 14496-10 specification. For MPEG-2, this is the section 6.2.2.2.2 “User data” of the ISO*/IEC* 13818-2 specification, excluding the user data start_code. For decoding, these payloads can be retrieved as the decoder parses the bitstream and caches them in an internal buffer.
+///
+/// See <https://github.com/Intel-Media-SDK/MediaSDK/blob/master/doc/mediasdk-man.md#mfxpayload> for list of Payloads insertion support in encoders.
+pub struct mfxPayload {
+    ///Additional payload properties. See the PayloadCtrlFlags enumerator for details.
+    pub CtrlFlags: mfxU32,
+    reserved: [mfxU32; 3],
+    /// Pointer to the actual payload data buffer
+    pub Data: *const mfxU8,
+    /// Number of bits in the payload data
+    pub NumBit: mfxU32,
+    /// MPEG-2 user data start code or H.264 SEI message type
+    pub Type: mfxU16,
+    /// Payload buffer size in bytes
+    pub BufSize: mfxU16,
+}
+
+impl Default for mfxPayload {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxPayload {
+    pub fn new() -> Self {
+        mfxPayload {
+            CtrlFlags: 0,
+            reserved: [0; 3],
+            Data: ptr::null(),
+            NumBit: 0,
+            Type: 0,
+            BufSize: 0,
+        }
+    }
+}
+
+#[repr(C)]
+/// The mfxExtVppAuxData structure returns auxiliary data generated by the video processing pipeline. The encoding process may use the auxiliary data by attaching this structure to the mfxEncodeCtrl structure.
+///
+/// <https://github.com/Intel-Media-SDK/MediaSDK/blob/master/doc/mediasdk-man.md#mfxextvppauxdata>
+pub struct mfxExtVppAuxData {
+    /// Header.BufferId must be set to MFX_EXTBUFF_VPP_AUXDATA
+    Header: mfxExtBuffer,
+
+    // TODO: union
+    #[deprecated]
+    SpatialComplexity: mfxU32,
+    #[deprecated]
+    TemporalComplexity: mfxU32,
+
+    #[deprecated]
+    SceneChangeRate: mfxU16,
+    #[deprecated]
+    RepeatedFrame: mfxU16,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// A pair of signed 16-bit values, e.g. a motion vector.
+pub struct mfxI16Pair {
+    pub x: mfxI16,
+    pub y: mfxI16,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+/// The mfxExtCodingOption structure specifies additional options for encoding. Tri-state options take an MFX_CODINGOPTION value, see [`CodingOption`].
+///
+/// <https://github.com/Intel-Media-SDK/MediaSDK/blob/master/doc/mediasdk-man.md#mfxextcodingoption>
+pub struct mfxExtCodingOption {
+    /// Header.BufferId must be set to MFX_EXTBUFF_CODING_OPTION
+    pub Header: mfxExtBuffer,
+    reserved1: mfxU16,
+    /// Set this flag if rate distortion optimization is needed.
+    pub RateDistortionOpt: mfxU16,
+    /// Motion estimation cost type; this value is reserved and must be zero.
+    pub MECostType: mfxU16,
+    /// Motion estimation search algorithm; this value is reserved and must be zero.
+    pub MESearchType: mfxU16,
+    /// Rectangular size of the search window for motion estimation; this parameter is reserved and must be (0, 0).
+    pub MVSearchWindow: mfxI16Pair,
+    #[deprecated]
+    pub EndOfSequence: mfxU16,
+    /// Set this flag to encode interlaced fields as interlaced frames; this flag does not affect progressive input frames.
+    pub FramePicture: mfxU16,
+    /// If set, CAVLC is used; if unset, CABAC is used for encoding.
+    pub CAVLC: mfxU16,
+    reserved2: [mfxU16; 2],
+    /// Set this flag to insert the recovery point SEI message at the beginning of every intra refresh cycle.
+    pub RecoveryPointSEI: mfxU16,
+    /// Set this flag to instruct the MVC encoder to output each view in separate bitstream buffer.
+    pub ViewOutput: mfxU16,
+    /// If this option is turned ON, then AVC encoder produces HRD conformant bitstream.
+    pub NalHrdConformance: mfxU16,
+    /// If set, encoder puts all SEI messages in the singe NAL unit.
+    pub SingleSeiNalUnit: mfxU16,
+    /// If set and VBR rate control method is used then VCL HRD parameters are written in bitstream with identical to NAL HRD parameters content.
+    pub VuiVclHrdParameters: mfxU16,
+    /// Set this flag to activate reference picture list reordering.
+    pub RefPicListReordering: mfxU16,
+    /// Set this flag to reset the reference list to non-IDR I-frames of a GOP sequence.
+    pub ResetRefList: mfxU16,
+    /// Set this flag to write the reference picture marking repetition SEI message into the output bitstream.
+    pub RefPicMarkRep: mfxU16,
+    /// Set this flag to instruct the AVC encoder to output bitstreams immediately after the encoder encodes a field.
+    pub FieldOutput: mfxU16,
+    /// Minimum block size of intra-prediction; this value is reserved and must be zero.
+    pub IntraPredBlockSize: mfxU16,
+    /// Minimum block size of inter-prediction; this value is reserved and must be zero.
+    pub InterPredBlockSize: mfxU16,
+    /// Motion vector precision; this value is reserved and must be zero.
+    pub MVPrecision: mfxU16,
+    /// Specifies the maximum number of frames buffered in a DPB. A value of zero means unspecified.
+    pub MaxDecFrameBuffering: mfxU16,
+    /// Set this flag to insert the Access Unit Delimiter NAL.
+    pub AUDelimiter: mfxU16,
+    #[deprecated]
+    pub EndOfStream: mfxU16,
+    /// Set this flag to insert the picture timing SEI with pic_struct syntax element.
+    pub PicTimingSEI: mfxU16,
+    /// Set this flag to insert NAL HRD parameters in the VUI header.
+    pub VuiNalHrdParameters: mfxU16,
+}
+
+impl Default for mfxExtCodingOption {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxExtCodingOption {
+    #[allow(deprecated)]
+    pub fn new() -> Self {
+        mfxExtCodingOption {
+            Header: mfxExtBuffer::header::<Self>(MFX_EXTBUFF_CODING_OPTION),
+            reserved1: 0,
+            RateDistortionOpt: 0,
+            MECostType: 0,
+            MESearchType: 0,
+            MVSearchWindow: mfxI16Pair::default(),
+            EndOfSequence: 0,
+            FramePicture: 0,
+            CAVLC: 0,
+            reserved2: [0; 2],
+            RecoveryPointSEI: 0,
+            ViewOutput: 0,
+            NalHrdConformance: 0,
+            SingleSeiNalUnit: 0,
+            VuiVclHrdParameters: 0,
+            RefPicListReordering: 0,
+            ResetRefList: 0,
+            RefPicMarkRep: 0,
+            FieldOutput: 0,
+            IntraPredBlockSize: 0,
+            InterPredBlockSize: 0,
+            MVPrecision: 0,
+            MaxDecFrameBuffering: 0,
+            AUDelimiter: 0,
+            EndOfStream: 0,
+            PicTimingSEI: 0,
+            VuiNalHrdParameters: 0,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+/// The mfxExtCodingOption2 structure together with mfxExtCodingOption structure specifies additional options for encoding. Tri-state options take an MFX_CODINGOPTION value, see [`CodingOption`].
+///
+/// <https://github.com/Intel-Media-SDK/MediaSDK/blob/master/doc/mediasdk-man.md#mfxextcodingoption2>
+pub struct mfxExtCodingOption2 {
+    /// Header.BufferId must be set to MFX_EXTBUFF_CODING_OPTION2
+    pub Header: mfxExtBuffer,
+    /// Specifies intra refresh type, 0 for no refresh.
+    pub IntRefType: mfxU16,
+    /// Specifies number of pictures within refresh cycle starting from 2. 0 and 1 are invalid values.
+    pub IntRefCycleSize: mfxU16,
+    /// Specifies QP difference for inserted intra MBs. This is signed value in [-51, 51] range.
+    pub IntRefQPDelta: mfxI16,
+    /// Specify maximum encoded frame size in byte. This parameter is used in VBR based bitrate control modes and ignored in others.
+    pub MaxFrameSize: mfxU32,
+    /// Specify maximum slice size in bytes. If this parameter is specified other controls over number of slices are ignored.
+    pub MaxSliceSize: mfxU32,
+    /// Modifies bitrate to be in the range imposed by the SDK encoder. Setting this flag off may lead to violation of HRD conformance.
+    pub BitrateLimit: mfxU16,
+    /// Setting this flag enables macroblock level bitrate control that generally improves subjective visual quality.
+    pub MBBRC: mfxU16,
+    /// Turn ON this option to enable external BRC.
+    pub ExtBRC: mfxU16,
+    /// Specifies the depth of look ahead rate control algorithm. It is the number of frames that SDK encoder analyzes before encoding.
+    pub LookAheadDepth: mfxU16,
+    /// Used to control trellis quantization in AVC encoder, see the TrellisControl enumerator.
+    pub Trellis: mfxU16,
+    /// Controls picture parameter set repetition in AVC encoder.
+    pub RepeatPPS: mfxU16,
+    /// Controls usage of B frames as reference, see the BRefControl enumerator.
+    pub BRefType: mfxU16,
+    /// Controls picture type decision in encoder: insertion of I frames on scene changes.
+    pub AdaptiveI: mfxU16,
+    /// Controls changing of frame type from B to P.
+    pub AdaptiveB: mfxU16,
+    /// Controls down sampling in look ahead bitrate control mode, see the LookAheadDownSampling enumerator.
+    pub LookAheadDS: mfxU16,
+    /// Specifies suggested slice size in number of macroblocks.
+    pub NumMbPerSlice: mfxU16,
+    /// Enables usage of mfxEncodeCtrl::SkipFrame parameter, see the SkipFrame enumerator.
+    pub SkipFrame: mfxU16,
+    /// Minimum allowed QP value for I frame types. Valid range is 1..51 inclusive. Zero means default value.
+    pub MinQPI: mfxU8,
+    /// Maximum allowed QP value for I frame types.
+    pub MaxQPI: mfxU8,
+    /// Minimum allowed QP value for P frame types.
+    pub MinQPP: mfxU8,
+    /// Maximum allowed QP value for P frame types.
+    pub MaxQPP: mfxU8,
+    /// Minimum allowed QP value for B frame types.
+    pub MinQPB: mfxU8,
+    /// Maximum allowed QP value for B frame types.
+    pub MaxQPB: mfxU8,
+    /// Sets fixed_frame_rate_flag in VUI.
+    pub FixedFrameRate: mfxU16,
+    /// Disables deblocking.
+    pub DisableDeblockingIdc: mfxU16,
+    /// Completely disables VUI in output bitstream.
+    pub DisableVUI: mfxU16,
+    /// Controls insertion of buffering period SEI in the encoded bitstream, see the BufferingPeriodSEI enumerator.
+    pub BufferingPeriodSEI: mfxU16,
+    /// Turn ON this flag to enable per-frame reporting of Mean Absolute Difference.
+    pub EnableMAD: mfxU16,
+    /// Turn ON this flag to use raw frames for reference instead of reconstructed frames.
+    pub UseRawRef: mfxU16,
+}
+
+impl Default for mfxExtCodingOption2 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxExtCodingOption2 {
+    pub fn new() -> Self {
+        mfxExtCodingOption2 {
+            Header: mfxExtBuffer::header::<Self>(MFX_EXTBUFF_CODING_OPTION2),
+            IntRefType: 0,
+            IntRefCycleSize: 0,
+            IntRefQPDelta: 0,
+            MaxFrameSize: 0,
+            MaxSliceSize: 0,
+            BitrateLimit: 0,
+            MBBRC: 0,
+            ExtBRC: 0,
+            LookAheadDepth: 0,
+            Trellis: 0,
+            RepeatPPS: 0,
+            BRefType: 0,
+            AdaptiveI: 0,
+            AdaptiveB: 0,
+            LookAheadDS: 0,
+            NumMbPerSlice: 0,
+            SkipFrame: 0,
+            MinQPI: 0,
+            MaxQPI: 0,
+            MinQPP: 0,
+            MaxQPP: 0,
+            MinQPB: 0,
+            MaxQPB: 0,
+            FixedFrameRate: 0,
+            DisableDeblockingIdc: 0,
+            DisableVUI: 0,
+            BufferingPeriodSEI: 0,
+            EnableMAD: 0,
+            UseRawRef: 0,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+/// The mfxExtCodingOption3 structure together with mfxExtCodingOption and mfxExtCodingOption2 structures specifies additional options for encoding. Tri-state options take an MFX_CODINGOPTION value, see [`CodingOption`].
+///
+/// <https://github.com/Intel-Media-SDK/MediaSDK/blob/master/doc/mediasdk-man.md#mfxextcodingoption3>
+pub struct mfxExtCodingOption3 {
+    /// Header.BufferId must be set to MFX_EXTBUFF_CODING_OPTION3
+    pub Header: mfxExtBuffer,
+    /// The number of slices for I frames.
+    pub NumSliceI: mfxU16,
+    /// The number of slices for P frames.
+    pub NumSliceP: mfxU16,
+    /// The number of slices for B frames.
+    pub NumSliceB: mfxU16,
+    /// When rate control method is MFX_RATECONTROL_VBR, MFX_RATECONTROL_LA, MFX_RATECONTROL_LA_HRD or MFX_RATECONTROL_QVBR this parameter specifies the maximum bitrate averaged over a sliding window specified by WinBRCSize.
+    pub WinBRCMaxAvgKbps: mfxU16,
+    /// When rate control method is MFX_RATECONTROL_CBR, MFX_RATECONTROL_VBR, MFX_RATECONTROL_LA, MFX_RATECONTROL_LA_HRD or MFX_RATECONTROL_QVBR this parameter specifies sliding window size in frames.
+    pub WinBRCSize: mfxU16,
+    /// When rate control method is MFX_RATECONTROL_QVBR this parameter specifies quality factor. It is a value in the 1,...,51 range, where 1 corresponds to the best quality.
+    pub QVBRQuality: mfxU16,
+    /// Turn ON this option to enable per-macroblock QP control, rate control method must be MFX_RATECONTROL_CQP.
+    pub EnableMBQP: mfxU16,
+    /// Distance between the beginnings of the intra-refresh cycles in frames. Zero means no distance between cycles.
+    pub IntRefCycleDist: mfxU16,
+    /// Turn ON this option to enable the ENC mode decision algorithm to bias to fewer B Direct/Skip types.
+    pub DirectBiasAdjustment: mfxU16,
+    /// Enables global motion bias.
+    pub GlobalMotionBiasAdjustment: mfxU16,
+    /// Values are: 0: set MV cost to be 0; 1: scale MV cost to be 1/2 of the default value; 2: 1/4; 3: 1/8.
+    pub MVCostScalingFactor: mfxU16,
+    /// Turn ON this option to enable usage of mfxExtMBDisableSkipMap.
+    pub MBDisableSkipMap: mfxU16,
+    /// Weighted prediction mode, see the WeightedPred enumerator.
+    pub WeightedPred: mfxU16,
+    /// Weighted prediction mode for B frames, see the WeightedPred enumerator.
+    pub WeightedBiPred: mfxU16,
+    /// Instructs encoder whether aspect ratio info should present in VUI parameters.
+    pub AspectRatioInfoPresent: mfxU16,
+    /// Instructs encoder whether overscan info should present in VUI parameters.
+    pub OverscanInfoPresent: mfxU16,
+    /// ON indicates that the cropped decoded pictures output are suitable for display using overscan.
+    pub OverscanAppropriate: mfxU16,
+    /// Instructs encoder whether frame rate info should present in VUI parameters.
+    pub TimingInfoPresent: mfxU16,
+    /// Instructs encoder whether bitstream restriction info should present in VUI parameters.
+    pub BitstreamRestriction: mfxU16,
+    /// Corresponds to AVC syntax element low_delay_hrd_flag (VUI).
+    pub LowDelayHrd: mfxU16,
+    /// When set to OFF, no sample outside the picture boundaries and no sample at a fractional sample position is used in inter prediction.
+    pub MotionVectorsOverPicBoundaries: mfxU16,
+    reserved1: [mfxU16; 2],
+    /// Provides a hint to encoder about the scenario for the encoding session, see the ScenarioInfo enumerator.
+    pub ScenarioInfo: mfxU16,
+    /// Provides a hint to encoder about the content for the encoding session, see the ContentInfo enumerator.
+    pub ContentInfo: mfxU16,
+    /// When GopRefDist=1, specifies the model of reference list construction and DPB management, see the PRefType enumerator.
+    pub PRefType: mfxU16,
+    /// Instructs encoder whether internal fade detection algorithm should be used for calculation of weigh/offset values for pred_weight_table unless application provided mfxExtPredWeightTable for this frame.
+    pub FadeDetection: mfxU16,
+    reserved2: [mfxU16; 2],
+    /// Set this flag to OFF to make HEVC encoder use regular P-frames instead of GPB.
+    pub GPB: mfxU16,
+    /// Same as mfxExtCodingOption2::MaxFrameSize but affects only I-frames.
+    pub MaxFrameSizeI: mfxU32,
+    /// Same as mfxExtCodingOption2::MaxFrameSize but affects only P/B-frames.
+    pub MaxFrameSizeP: mfxU32,
+    reserved3: [mfxU32; 3],
+    /// Enables QPOffset control.
+    pub EnableQPOffset: mfxU16,
+    /// When EnableQPOffset set to ON and RateControlMethod is CQP specifies QP offset per pyramid layer.
+    pub QPOffset: [mfxI16; 8],
+    /// Max number of active references for P and GPB frames.
+    pub NumRefActiveP: [mfxU16; 8],
+    /// Max number of active references for B frames in reference picture list 0.
+    pub NumRefActiveBL0: [mfxU16; 8],
+    /// Max number of active references for B frames in reference picture list 1.
+    pub NumRefActiveBL1: [mfxU16; 8],
+    reserved6: mfxU16,
+    /// For HEVC if this option turned ON, transform_skip_enabled_flag will be set to 1 in PPS.
+    pub TransformSkip: mfxU16,
+    /// Minus 1 specifies target encoding chroma format, see the ChromaFormatIdc enumerator.
+    pub TargetChromaFormatPlus1: mfxU16,
+    /// Target encoding bit depth for luma samples. May differ from source one.
+    pub TargetBitDepthLuma: mfxU16,
+    /// Target encoding bit depth for chroma samples. May differ from source one.
+    pub TargetBitDepthChroma: mfxU16,
+    /// Controls BRC panic mode (skipping frames) for the VBR and CBR rate control methods.
+    pub BRCPanicMode: mfxU16,
+    /// When rate control method is MFX_RATECONTROL_VBR, MFX_RATECONTROL_QVBR or MFX_RATECONTROL_VCM this parameter specifies frame size tolerance.
+    pub LowDelayBRC: mfxU16,
+    /// Turn ON this option to enable usage of mfxExtMBForceIntra for AVC encoder.
+    pub EnableMBForceIntra: mfxU16,
+    /// If this option is ON, BRC may decide a larger P or B frame size than what MaxFrameSizeP dictates when the scene change is detected.
+    pub AdaptiveMaxFrameSize: mfxU16,
+    /// Controls AVC encoder attempts to predict from small partitions.
+    pub RepartitionCheckEnable: mfxU16,
+    reserved5: [mfxU16; 3],
+    /// Turn this option ON to make encoded units info available in mfxExtEncodedUnitsInfo.
+    pub EncodedUnitsInfo: mfxU16,
+    /// If this option is turned ON, then HEVC encoder uses NAL unit type provided by application in mfxEncodeCtrl::MfxNalUnitType field.
+    pub EnableNalUnitType: mfxU16,
+    /// Turn OFF to prevent Adaptive marking of Long Term Reference Frames when using ExtBRC.
+    pub ExtBrcAdaptiveLTR: mfxU16,
+    reserved: [mfxU16; 163],
+}
+
+impl Default for mfxExtCodingOption3 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxExtCodingOption3 {
+    pub fn new() -> Self {
+        mfxExtCodingOption3 {
+            Header: mfxExtBuffer::header::<Self>(MFX_EXTBUFF_CODING_OPTION3),
+            NumSliceI: 0,
+            NumSliceP: 0,
+            NumSliceB: 0,
+            WinBRCMaxAvgKbps: 0,
+            WinBRCSize: 0,
+            QVBRQuality: 0,
+            EnableMBQP: 0,
+            IntRefCycleDist: 0,
+            DirectBiasAdjustment: 0,
+            GlobalMotionBiasAdjustment: 0,
+            MVCostScalingFactor: 0,
+            MBDisableSkipMap: 0,
+            WeightedPred: 0,
+            WeightedBiPred: 0,
+            AspectRatioInfoPresent: 0,
+            OverscanInfoPresent: 0,
+            OverscanAppropriate: 0,
+            TimingInfoPresent: 0,
+            BitstreamRestriction: 0,
+            LowDelayHrd: 0,
+            MotionVectorsOverPicBoundaries: 0,
+            reserved1: [0; 2],
+            ScenarioInfo: 0,
+            ContentInfo: 0,
+            PRefType: 0,
+            FadeDetection: 0,
+            reserved2: [0; 2],
+            GPB: 0,
+            MaxFrameSizeI: 0,
+            MaxFrameSizeP: 0,
+            reserved3: [0; 3],
+            EnableQPOffset: 0,
+            QPOffset: [0; 8],
+            NumRefActiveP: [0; 8],
+            NumRefActiveBL0: [0; 8],
+            NumRefActiveBL1: [0; 8],
+            reserved6: 0,
+            TransformSkip: 0,
+            TargetChromaFormatPlus1: 0,
+            TargetBitDepthLuma: 0,
+            TargetBitDepthChroma: 0,
+            BRCPanicMode: 0,
+            LowDelayBRC: 0,
+            EnableMBForceIntra: 0,
+            AdaptiveMaxFrameSize: 0,
+            RepartitionCheckEnable: 0,
+            reserved5: [0; 3],
+            EncodedUnitsInfo: 0,
+            EnableNalUnitType: 0,
+            ExtBrcAdaptiveLTR: 0,
+            reserved: [0; 163],
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+/// Attach this structure as part of the mfxVideoParam extended buffers to configure the SDK encoder during MFXVideoENCODE_Init or to retrieve the sequence and picture parameter sets with MFXVideoENCODE_GetVideoParam. The buffers hold the NAL units including their start codes.
+///
+/// <https://github.com/Intel-Media-SDK/MediaSDK/blob/master/doc/mediasdk-man.md#mfxextcodingoptionspspps>
+pub struct mfxExtCodingOptionSPSPPS {
+    /// Header.BufferId must be set to MFX_EXTBUFF_CODING_OPTION_SPSPPS
+    pub Header: mfxExtBuffer,
+    /// Pointer to a valid bitstream that contains the SPS (sequence parameter set for H.264 or sequence header for MPEG-2) buffer; can be NULL to skip specifying the SPS.
+    pub SPSBuffer: *mut mfxU8,
+    /// Pointer to a valid bitstream that contains the PPS (picture parameter set for H.264 or picture header for MPEG-2) buffer; can be NULL to skip specifying the PPS.
+    pub PPSBuffer: *mut mfxU8,
+    /// Size of the SPS in bytes. On output from GetVideoParam, the number of bytes written.
+    pub SPSBufSize: mfxU16,
+    /// Size of the PPS in bytes. On output from GetVideoParam, the number of bytes written.
+    pub PPSBufSize: mfxU16,
+    /// SPS identifier; the value is reserved and must be zero.
+    pub SPSId: mfxU16,
+    /// PPS identifier; the value is reserved and must be zero.
+    pub PPSId: mfxU16,
+}
+
+impl Default for mfxExtCodingOptionSPSPPS {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxExtCodingOptionSPSPPS {
+    pub fn new() -> Self {
+        mfxExtCodingOptionSPSPPS {
+            Header: mfxExtBuffer::header::<Self>(MFX_EXTBUFF_CODING_OPTION_SPSPPS),
+            SPSBuffer: ptr::null_mut(),
+            PPSBuffer: ptr::null_mut(),
+            SPSBufSize: 0,
+            PPSBufSize: 0,
+            SPSId: 0,
+            PPSId: 0,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+/// The mfxExtEncoderResetOption structure is used to control the SDK encoder behavior during reset. By using this structure, the application instructs the SDK encoder to start a new coded sequence after reset or to continue encoding of the current sequence.
+///
+/// This structure is also used in the mode of MFXVideoENCODE_Query function, that queries for the outcome of the MFXVideoENCODE_Reset function. Attach it to both the in and out mfxVideoParam; the out structure then reports whether reset with the new parameters will start a new sequence.
+///
+/// <https://github.com/Intel-Media-SDK/MediaSDK/blob/master/doc/mediasdk-man.md#mfxextencoderresetoption>
+pub struct mfxExtEncoderResetOption {
+    /// Header.BufferId must be set to MFX_EXTBUFF_ENCODER_RESET_OPTION
+    pub Header: mfxExtBuffer,
+    /// Instructs encoder to start new sequence after reset. It is one of the CodingOptionValue options: MFX_CODINGOPTION_ON – encoder completely resets internal state and begins new coded sequence after reset, including insertion of IDR frame, sequence and picture headers. MFX_CODINGOPTION_OFF – encoder continues encoding of current coded sequence after reset, without insertion of IDR frame. MFX_CODINGOPTION_UNKNOWN – depending on the current encoder state and changes in configuration parameters encoder may or may not start new coded sequence. This value is also used to query reset outcome.
+    pub StartNewSequence: mfxU16,
+    reserved: [mfxU16; 11],
+}
+
+impl Default for mfxExtEncoderResetOption {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxExtEncoderResetOption {
+    pub fn new() -> Self {
+        mfxExtEncoderResetOption {
+            Header: mfxExtBuffer::header::<Self>(MFX_EXTBUFF_ENCODER_RESET_OPTION),
+            StartNewSequence: MFX_CODINGOPTION_UNKNOWN,
+            reserved: [0; 11],
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+/// A reference frame used to encode the frame described by mfxExtAVCEncodedFrameInfo.
+pub struct mfxExtAVCEncodedFrameInfoStruct1 {
+    /// Frame order of the reference picture, MFX_FRAMEORDER_UNKNOWN for an unused entry.
+    pub FrameOrder: mfxU32,
+    /// Picture structure of the reference picture.
+    pub PicStruct: mfxU16,
+    /// Long term index of the reference picture if applicable.
+    pub LongTermIdx: mfxU16,
+    reserved: [mfxU16; 4],
+}
+
+impl Default for mfxExtAVCEncodedFrameInfoStruct1 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxExtAVCEncodedFrameInfoStruct1 {
+    pub fn new() -> Self {
+        mfxExtAVCEncodedFrameInfoStruct1 {
+            FrameOrder: 0,
+            PicStruct: 0,
+            LongTermIdx: 0,
+            reserved: [0; 4],
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+/// The mfxExtAVCEncodedFrameInfo is used by the SDK encoder to report additional information about encoded picture. The application can attach this buffer to the mfxBitstream structure before calling MFXVideoENCODE_EncodeFrameAsync function.
+///
+/// <https://github.com/Intel-Media-SDK/MediaSDK/blob/master/doc/mediasdk-man.md#mfxextavcencodedframeinfo>
+pub struct mfxExtAVCEncodedFrameInfo {
+    /// Header.BufferId must be set to MFX_EXTBUFF_ENCODED_FRAME_INFO
+    pub Header: mfxExtBuffer,
+    /// Frame order of encoded picture.
+    pub FrameOrder: mfxU32,
+    /// Picture structure of encoded picture.
+    pub PicStruct: mfxU16,
+    /// Long term index of encoded picture if applicable.
+    pub LongTermIdx: mfxU16,
+    /// Mean Absolute Difference between original pixels of the frame and motion compensated (for inter macroblocks) or spatially predicted (for intra macroblocks) pixels. Only luma component, Y plane, is used in calculation.
+    pub MAD: mfxU32,
+    /// Bitrate control was not able to allocate enough bits for this frame. Frame quality may be unacceptably low.
+    pub BRCPanicMode: mfxU16,
+    /// Luma QP.
+    pub QP: mfxU16,
+    /// Offset to second field. Second field starts at mfxBitstream::Data + mfxBitstream::DataOffset + mfxExtAVCEncodedFrameInfo::SecondFieldOffset.
+    pub SecondFieldOffset: mfxU32,
+    reserved: [mfxU16; 2],
+    /// Reference list that has been used to encode picture.
+    pub UsedRefListL0: [mfxExtAVCEncodedFrameInfoStruct1; 32],
+    pub UsedRefListL1: [mfxExtAVCEncodedFrameInfoStruct1; 32],
+}
+
+impl Default for mfxExtAVCEncodedFrameInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxExtAVCEncodedFrameInfo {
+    pub fn new() -> Self {
+        mfxExtAVCEncodedFrameInfo {
+            Header: mfxExtBuffer::header::<Self>(MFX_EXTBUFF_ENCODED_FRAME_INFO),
+            FrameOrder: 0,
+            PicStruct: 0,
+            LongTermIdx: 0,
+            MAD: 0,
+            BRCPanicMode: 0,
+            QP: 0,
+            SecondFieldOffset: 0,
+            reserved: [0; 2],
+            UsedRefListL0: [mfxExtAVCEncodedFrameInfoStruct1::new(); 32],
+            UsedRefListL1: [mfxExtAVCEncodedFrameInfoStruct1::new(); 32],
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+/// The mfxExtVideoSignalInfo structure defines the video signal information. For H.264, see Annex E of the ISO/IEC 14496-10 specification for the definition of these parameters.
+///
+/// <https://github.com/Intel-Media-SDK/MediaSDK/blob/master/doc/mediasdk-man.md#mfxextvideosignalinfo>
+pub struct mfxExtVideoSignalInfo {
+    /// Header.BufferId must be set to MFX_EXTBUFF_VIDEO_SIGNAL_INFO
+    pub Header: mfxExtBuffer,
+    /// video_format, e.g. 5 for unspecified.
+    pub VideoFormat: mfxU16,
+    /// video_full_range_flag.
+    pub VideoFullRange: mfxU16,
+    /// If set, the three fields below are written to the bitstream.
+    pub ColourDescriptionPresent: mfxU16,
+    /// colour_primaries, e.g. 1 for BT.709.
+    pub ColourPrimaries: mfxU16,
+    /// transfer_characteristics.
+    pub TransferCharacteristics: mfxU16,
+    /// matrix_coefficients.
+    pub MatrixCoefficients: mfxU16,
+}
+
+impl Default for mfxExtVideoSignalInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl mfxExtVideoSignalInfo {
+    pub fn new() -> Self {
+        mfxExtVideoSignalInfo {
+            Header: mfxExtBuffer::header::<Self>(MFX_EXTBUFF_VIDEO_SIGNAL_INFO),
+            VideoFormat: 0,
+            VideoFullRange: 0,
+            ColourDescriptionPresent: 0,
+            ColourPrimaries: 0,
+            TransferCharacteristics: 0,
+            MatrixCoefficients: 0,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+/// Value of an mfxVariant, interpreted according to mfxVariant::Type.
+pub union mfxVariantData {
+    pub U8: mfxU8,
+    pub I8: mfxI8,
+    pub U16: mfxU16,
+    pub I16: mfxI16,
+    pub U32: mfxU32,
+    pub I32: mfxI32,
+    pub U64: mfxU64,
+    pub I64: mfxI64,
+    pub F32: mfxF32,
+    pub F64: mfxF64,
+    pub Ptr: mfxHDL,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+/// The mfxVariant structure is used for passing a property value to MFXSetConfigFilterProperty.
+///
+/// <https://spec.oneapi.io/versions/latest/elements/oneVPL/source/API_ref/VPL_structs_dispatcher.html#mfxvariant>
+pub struct mfxVariant {
+    /// Version of the structure, must be MFX_VARIANT_VERSION.
+    pub Version: mfxU16,
+    /// Value type. See the mfxVariantType enumerator for details.
+    pub Type: mfxVariantType,
+    /// Value data member.
+    pub Data: mfxVariantData,
+}
+
+impl mfxVariant {
+    /// A variant holding an mfxU32, the type of most implementation properties.
+    pub const fn u32(value: mfxU32) -> Self {
+        mfxVariant {
+            Version: MFX_VARIANT_VERSION,
+            Type: MFX_VARIANT_TYPE_U32,
+            Data: mfxVariantData { U32: value },
+        }
+    }
+}
+
+#[repr(C)]
+/// The mfxImplDescription structure represents the implementation description returned by MFXEnumImplementations.
+///
+/// Only the leading, fixed part of the structure is declared; the capability descriptions that follow it are never read by this crate. The structure is always allocated by the dispatcher and only accessed through the pointer it returns.
+///
+/// <https://spec.oneapi.io/versions/latest/elements/oneVPL/source/API_ref/VPL_structs_implementation_capabilities.html#mfximpldescription>
+pub struct mfxImplDescription {
+    /// Version of the structure.
+    pub Version: mfxU16,
+    /// Implementation type, MFX_IMPL_TYPE_SOFTWARE or MFX_IMPL_TYPE_HARDWARE.
+    pub Impl: mfxImplType,
+    /// Default hardware acceleration stack to use. OS dependent parameter.
+    pub AccelerationMode: mfxAccelerationMode,
+    /// Supported API version.
+    pub ApiVersion: mfxVersion,
+    /// Null-terminated string with implementation name given by vendor.
+    pub ImplName: [mfxChar; MFX_IMPL_NAME_LEN],
+    /// Null-terminated string with comma-separated list of license names of the implementation.
+    pub License: [mfxChar; MFX_STRFIELD_LEN],
+    /// Null-terminated string with comma-separated list of keywords specific to this implementation that dispatcher can search for.
+    pub Keywords: [mfxChar; MFX_STRFIELD_LEN],
+    /// Standard vendor ID 0x8086 - Intel.
+    pub VendorID: mfxU32,
+    /// Vendor specific number with given implementation ID.
+    pub VendorImplID: mfxU32,
+}
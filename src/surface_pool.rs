@@ -0,0 +1,249 @@
+//! Frame surfaces in system memory.
+//!
+//! Frames are laid out like the system memory allocator of the SDK samples: the pitch and the number of rows are aligned to 32, the chroma planes follow the luma plane and every frame starts on a 32-byte boundary. The surfaces never move once the pool is built, so the SDK can keep pointers to them while it has them locked.
+use std::alloc::{self, Layout};
+use std::ptr::NonNull;
+
+use crate::constants::*;
+use crate::enums::{ColorFourCC, FourCC};
+use crate::error::{Error, Result};
+use crate::structures::*;
+use crate::utils::align32;
+
+/// Alignment of every frame buffer in bytes.
+pub const SURFACE_ALIGNMENT: usize = 32;
+
+/// A zeroed allocation aligned to [`SURFACE_ALIGNMENT`].
+struct AlignedBuffer {
+    ptr: NonNull<u8>,
+    layout: Layout,
+}
+
+impl AlignedBuffer {
+    fn zeroed(size: usize) -> Self {
+        let layout = Layout::from_size_align(size.max(1), SURFACE_ALIGNMENT).expect("surface pool too large");
+        let ptr =
+            NonNull::new(unsafe { alloc::alloc_zeroed(layout) }).unwrap_or_else(|| alloc::handle_alloc_error(layout));
+        AlignedBuffer { ptr, layout }
+    }
+}
+
+impl Drop for AlignedBuffer {
+    fn drop(&mut self) {
+        unsafe { alloc::dealloc(self.ptr.as_ptr(), self.layout) };
+    }
+}
+
+/// Surfaces sharing one frame format, each with its own frame buffer.
+pub struct SurfacePool {
+    surfaces: Vec<mfxFrameSurface1>,
+    pitch: usize,
+    /// Where the search for a free surface starts: right after the surface handed out last.
+    next: usize,
+    _buffer: AlignedBuffer,
+}
+
+impl SurfacePool {
+    /// NumFrameSuggested surfaces of the format in `request.Info`, as returned by `QueryIOSurf`.
+    pub fn new(request: &mfxFrameAllocRequest) -> Result<Self> {
+        SurfacePool::with_count(&request.Info, request.NumFrameSuggested as usize)
+    }
+
+    /// `count` surfaces of the format in `info`. Only NV12, YV12, IYUV, P010 and RGB4 are supported.
+    pub fn with_count(info: &mfxFrameInfo, count: usize) -> Result<Self> {
+        let fourcc = info
+            .fourcc()
+            .map_err(|_| Error::UnsupportedFourCC(FourCC(info.FourCC)))?;
+        let width = align32(info.Width as u32) as usize;
+        let height = align32(info.Height as u32) as usize;
+        let (pitch, frame_size) = match fourcc {
+            ColorFourCC::Nv12 | ColorFourCC::Yv12 | ColorFourCC::Iyuv => (width, width * height * 3 / 2),
+            ColorFourCC::P010 => (2 * width, 2 * width * height * 3 / 2),
+            ColorFourCC::Rgb4 => (4 * width, 4 * width * height),
+            _ => return Err(Error::UnsupportedFourCC(fourcc.into())),
+        };
+
+        let buffer = AlignedBuffer::zeroed(frame_size * count);
+        let surfaces = (0..count)
+            .map(|i| {
+                let mut surface = mfxFrameSurface1::new();
+                surface.Info = *info;
+                unsafe {
+                    let frame = buffer.ptr.as_ptr().add(frame_size * i);
+                    set_planes(&mut surface.Data, fourcc, frame, pitch, height);
+                }
+                surface
+            })
+            .collect();
+        Ok(SurfacePool {
+            surfaces,
+            pitch,
+            next: 0,
+            _buffer: buffer,
+        })
+    }
+
+    /// Makes the pool fit `request`, e.g. after [`crate::encoder::Encoder::reconfigure`] changed the frame size. Returns whether the surfaces were reallocated.
+    ///
+    /// The surfaces are kept if they have the requested FourCC and size and there are at least NumFrameSuggested of them; only their crop and frame rate are updated. The SDK must not have any of them locked.
+    pub fn resize(&mut self, request: &mfxFrameAllocRequest) -> Result<bool> {
+        let info = &request.Info;
+        let fits = self.surfaces.first().is_some_and(|surface| {
+            (surface.Info.FourCC, surface.Info.Width, surface.Info.Height) == (info.FourCC, info.Width, info.Height)
+        });
+        if fits && self.surfaces.len() >= request.NumFrameSuggested as usize {
+            for surface in self.surfaces.iter_mut() {
+                surface.Info = *info;
+            }
+            return Ok(false);
+        }
+        *self = SurfacePool::new(request)?;
+        Ok(true)
+    }
+
+    pub fn len(&self) -> usize {
+        self.surfaces.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.surfaces.is_empty()
+    }
+
+    /// Bytes between the start of two rows of the first plane.
+    pub fn pitch(&self) -> usize {
+        self.pitch
+    }
+
+    pub fn surfaces(&self) -> &[mfxFrameSurface1] {
+        &self.surfaces
+    }
+
+    /// A surface the SDK does not have locked, or `None` if all of them are in use.
+    ///
+    /// The search starts after the surface handed out last, so a surface just filled and submitted is not handed out again before the SDK gets to lock it.
+    pub fn free_surface(&mut self) -> Option<&mut mfxFrameSurface1> {
+        let len = self.surfaces.len();
+        let index = (0..len)
+            .map(|offset| (self.next + offset) % len)
+            .find(|&index| self.surfaces[index].Data.Locked == 0)?;
+        self.next = (index + 1) % len;
+        Some(&mut self.surfaces[index])
+    }
+}
+
+/// Points the planes of `data` into the frame at `frame`, `height` rows of `pitch` bytes of luma followed by chroma.
+unsafe fn set_planes(data: &mut mfxFrameData, fourcc: ColorFourCC, frame: *mut mfxU8, pitch: usize, height: usize) {
+    let luma_size = pitch * height;
+    let chroma_size = pitch / 2 * height / 2;
+    match fourcc {
+        ColorFourCC::Nv12 => {
+            data.Y = frame;
+            data.UV = frame.add(luma_size);
+            data.V = data.UV.add(1);
+        }
+        ColorFourCC::P010 => {
+            data.Y = frame;
+            data.UV = frame.add(luma_size);
+            data.V = data.UV.add(2);
+        }
+        ColorFourCC::Yv12 => {
+            data.Y = frame;
+            data.V = frame.add(luma_size);
+            data.UV = data.V.add(chroma_size);
+        }
+        ColorFourCC::Iyuv => {
+            data.Y = frame;
+            data.UV = frame.add(luma_size);
+            data.V = data.UV.add(chroma_size);
+        }
+        // Y, UV and V are R, G and B in the SDK's unions, BGRA is the byte order.
+        ColorFourCC::Rgb4 => {
+            data.V = frame;
+            data.UV = frame.add(1);
+            data.Y = frame.add(2);
+            data.A = frame.add(3);
+        }
+        _ => unreachable!("rejected by SurfacePool::with_count"),
+    }
+    data.set_pitch(pitch as mfxU32);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn request(fourcc: ColorFourCC, count: mfxU16) -> mfxFrameAllocRequest {
+        let mut request = mfxFrameAllocRequest::new();
+        request.Info.set_fourcc(fourcc);
+        request.Info.Width = 80;
+        request.Info.Height = 48;
+        request.NumFrameSuggested = count;
+        request
+    }
+
+    #[test]
+    fn nv12_layout() {
+        let pool = SurfacePool::new(&request(ColorFourCC::Nv12, 3)).unwrap();
+        assert_eq!(pool.len(), 3);
+        assert_eq!(pool.pitch(), 96);
+
+        let frame_size = 96 * 64 * 3 / 2;
+        let first = pool.surfaces()[0].Data.Y;
+        for (i, surface) in pool.surfaces().iter().enumerate() {
+            let data = &surface.Data;
+            assert_eq!(surface.Info.Width, 80);
+            assert_eq!(data.pitch(), 96);
+            assert_eq!(data.Y as usize % SURFACE_ALIGNMENT, 0);
+            assert_eq!(data.Y, first.wrapping_add(frame_size * i));
+            assert_eq!(data.UV, data.Y.wrapping_add(96 * 64));
+            assert_eq!(data.V, data.UV.wrapping_add(1));
+        }
+    }
+
+    #[test]
+    fn yv12_has_v_before_u() {
+        let pool = SurfacePool::new(&request(ColorFourCC::Yv12, 1)).unwrap();
+        let data = &pool.surfaces()[0].Data;
+        assert_eq!(data.V, data.Y.wrapping_add(96 * 64));
+        assert_eq!(data.UV, data.V.wrapping_add(48 * 32));
+    }
+
+    #[test]
+    fn skips_locked_surfaces() {
+        let mut pool = SurfacePool::new(&request(ColorFourCC::Nv12, 3)).unwrap();
+        let ys: Vec<_> = pool.surfaces().iter().map(|surface| surface.Data.Y).collect();
+
+        pool.free_surface().unwrap().Data.Locked = 1;
+        assert_eq!(pool.free_surface().unwrap().Data.Y, ys[1]);
+        assert_eq!(pool.free_surface().unwrap().Data.Y, ys[2]);
+        // Wraps around past the locked first surface.
+        assert_eq!(pool.free_surface().unwrap().Data.Y, ys[1]);
+
+        for surface in pool.surfaces.iter_mut() {
+            surface.Data.Locked = 1;
+        }
+        assert!(pool.free_surface().is_none());
+    }
+
+    #[test]
+    fn resizes_for_new_frame_size() {
+        let mut pool = SurfacePool::new(&request(ColorFourCC::Nv12, 2)).unwrap();
+        let mut cropped = request(ColorFourCC::Nv12, 2);
+        cropped.Info.CropW = 72;
+        assert!(!pool.resize(&cropped).unwrap());
+        assert_eq!(pool.surfaces()[1].Info.CropW, 72);
+
+        let mut larger = request(ColorFourCC::Nv12, 3);
+        larger.Info.Width = 160;
+        assert!(pool.resize(&larger).unwrap());
+        assert_eq!(pool.len(), 3);
+        assert_eq!(pool.pitch(), 160);
+        assert_eq!(pool.surfaces()[0].Info.Width, 160);
+    }
+
+    #[test]
+    fn unsupported_fourcc() {
+        let err = SurfacePool::new(&request(ColorFourCC::Yuy2, 1)).err().unwrap();
+        assert!(matches!(err, Error::UnsupportedFourCC(fourcc) if fourcc == ColorFourCC::Yuy2.into()));
+    }
+}